@@ -0,0 +1,161 @@
+//! 主要な変換関数の簡易ベンチマーク
+//!
+//! `cargo bench` で実行します。外部クレートに依存しないよう、
+//! `std::time::Instant` で一定回数の呼び出しにかかった時間を計測します。
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use japanese_text::*;
+
+const ITERATIONS: u32 = 100;
+
+fn bench(name: &str, input: &str, f: impl Fn(&str) -> String) {
+    // ウォームアップ
+    black_box(f(black_box(input)));
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(f(black_box(input)));
+    }
+    let elapsed = start.elapsed();
+    report(name, input.len(), elapsed);
+}
+
+fn report(name: &str, bytes: usize, elapsed: Duration) {
+    let per_iter = elapsed / ITERATIONS;
+    let mib_per_sec = bytes as f64 / per_iter.as_secs_f64() / (1024.0 * 1024.0);
+    println!("{:<56} {:>12.3?}/iter {:>10.1} MiB/s", name, per_iter, mib_per_sec);
+}
+
+/// 比較用に残している、最適化前の実装です。
+mod baseline {
+    /// `match` の分岐で半角カタカナを全角に変換していた実装です。
+    pub fn half_width_katakana_to_full_width(input: &str) -> String {
+        let chars: Vec<char> = input.chars().collect();
+        let mut result = String::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            let c = chars[i];
+
+            // 次の文字が濁点または半濁点かチェック
+            let next = if i + 1 < chars.len() {
+                Some(chars[i + 1])
+            } else {
+                None
+            };
+
+            match (c, next) {
+                // 濁点付き
+                ('ｶ', Some('ﾞ')) => { result.push('ガ'); i += 2; }
+                ('ｷ', Some('ﾞ')) => { result.push('ギ'); i += 2; }
+                ('ｸ', Some('ﾞ')) => { result.push('グ'); i += 2; }
+                ('ｹ', Some('ﾞ')) => { result.push('ゲ'); i += 2; }
+                ('ｺ', Some('ﾞ')) => { result.push('ゴ'); i += 2; }
+                ('ｻ', Some('ﾞ')) => { result.push('ザ'); i += 2; }
+                ('ｼ', Some('ﾞ')) => { result.push('ジ'); i += 2; }
+                ('ｽ', Some('ﾞ')) => { result.push('ズ'); i += 2; }
+                ('ｾ', Some('ﾞ')) => { result.push('ゼ'); i += 2; }
+                ('ｿ', Some('ﾞ')) => { result.push('ゾ'); i += 2; }
+                ('ﾀ', Some('ﾞ')) => { result.push('ダ'); i += 2; }
+                ('ﾁ', Some('ﾞ')) => { result.push('ヂ'); i += 2; }
+                ('ﾂ', Some('ﾞ')) => { result.push('ヅ'); i += 2; }
+                ('ﾃ', Some('ﾞ')) => { result.push('デ'); i += 2; }
+                ('ﾄ', Some('ﾞ')) => { result.push('ド'); i += 2; }
+                ('ﾊ', Some('ﾞ')) => { result.push('バ'); i += 2; }
+                ('ﾋ', Some('ﾞ')) => { result.push('ビ'); i += 2; }
+                ('ﾌ', Some('ﾞ')) => { result.push('ブ'); i += 2; }
+                ('ﾍ', Some('ﾞ')) => { result.push('ベ'); i += 2; }
+                ('ﾎ', Some('ﾞ')) => { result.push('ボ'); i += 2; }
+                ('ｳ', Some('ﾞ')) => { result.push('ヴ'); i += 2; }
+
+                // 半濁点付き
+                ('ﾊ', Some('ﾟ')) => { result.push('パ'); i += 2; }
+                ('ﾋ', Some('ﾟ')) => { result.push('ピ'); i += 2; }
+                ('ﾌ', Some('ﾟ')) => { result.push('プ'); i += 2; }
+                ('ﾍ', Some('ﾟ')) => { result.push('ペ'); i += 2; }
+                ('ﾎ', Some('ﾟ')) => { result.push('ポ'); i += 2; }
+
+                // 通常の半角カタカナ
+                _ => {
+                    let full = match c {
+                        'ｦ' => 'ヲ', 'ｧ' => 'ァ', 'ｨ' => 'ィ', 'ｩ' => 'ゥ', 'ｪ' => 'ェ', 'ｫ' => 'ォ',
+                        'ｬ' => 'ャ', 'ｭ' => 'ュ', 'ｮ' => 'ョ', 'ｯ' => 'ッ', 'ｰ' => 'ー',
+                        'ｱ' => 'ア', 'ｲ' => 'イ', 'ｳ' => 'ウ', 'ｴ' => 'エ', 'ｵ' => 'オ',
+                        'ｶ' => 'カ', 'ｷ' => 'キ', 'ｸ' => 'ク', 'ｹ' => 'ケ', 'ｺ' => 'コ',
+                        'ｻ' => 'サ', 'ｼ' => 'シ', 'ｽ' => 'ス', 'ｾ' => 'セ', 'ｿ' => 'ソ',
+                        'ﾀ' => 'タ', 'ﾁ' => 'チ', 'ﾂ' => 'ツ', 'ﾃ' => 'テ', 'ﾄ' => 'ト',
+                        'ﾅ' => 'ナ', 'ﾆ' => 'ニ', 'ﾇ' => 'ヌ', 'ﾈ' => 'ネ', 'ﾉ' => 'ノ',
+                        'ﾊ' => 'ハ', 'ﾋ' => 'ヒ', 'ﾌ' => 'フ', 'ﾍ' => 'ヘ', 'ﾎ' => 'ホ',
+                        'ﾏ' => 'マ', 'ﾐ' => 'ミ', 'ﾑ' => 'ム', 'ﾒ' => 'メ', 'ﾓ' => 'モ',
+                        'ﾔ' => 'ヤ', 'ﾕ' => 'ユ', 'ﾖ' => 'ヨ',
+                        'ﾗ' => 'ラ', 'ﾘ' => 'リ', 'ﾙ' => 'ル', 'ﾚ' => 'レ', 'ﾛ' => 'ロ',
+                        'ﾜ' => 'ワ', 'ﾝ' => 'ン',
+                        '｡' => '。', '｢' => '「', '｣' => '」', '､' => '、', '･' => '・',
+                        _ => c,
+                    };
+                    result.push(full);
+                    i += 1;
+                }
+            }
+        }
+
+        result
+    }
+
+    /// `chars` → `map` → `collect` で半角ASCIIを全角に変換していた実装です。
+    pub fn to_full_width(input: &str) -> String {
+        input
+            .chars()
+            .map(|c| match c {
+                ' ' => '　',
+                '\u{0021}'..='\u{007E}' => char::from_u32(c as u32 - 0x0021 + 0xFF01).unwrap_or(c),
+                _ => c,
+            })
+            .collect()
+    }
+}
+
+/// 最適化前後の実装を同じ入力で計測します。結果が一致することも確かめます。
+fn compare(
+    name: &str,
+    input: &str,
+    before: impl Fn(&str) -> String,
+    after: impl Fn(&str) -> String,
+) {
+    assert_eq!(before(input), after(input), "{name}");
+    bench(&format!("{name} (before)"), input, before);
+    bench(&format!("{name} (after)"), input, after);
+}
+
+fn main() {
+    let ascii_log = "2024-01-01T00:00:00Z INFO request handled in 12ms path=/api/v1/items\n".repeat(20_000);
+    bench("to_owned (baseline, ascii)", &ascii_log, str::to_owned);
+    bench("to_half_width (ascii)", &ascii_log, to_half_width);
+    bench("to_hiragana (ascii)", &ascii_log, to_hiragana);
+    bench("to_katakana (ascii)", &ascii_log, to_katakana);
+
+    let mixed_width = "ログ：ＡＢＣ１２３　ERROR code=42 ｔｅｓｔ\n".repeat(20_000);
+    bench("to_half_width (mixed)", &mixed_width, to_half_width);
+
+    // 約10MBの半角ASCII入力
+    let ten_mb = "The quick brown fox jumps over the lazy dog 0123456789!?\n".repeat(180_000);
+    compare("to_full_width (10MB ascii)", &ten_mb, baseline::to_full_width, to_full_width);
+
+    let half_width_kana = "ｶﾞｷﾞｸﾞｹﾞｺﾞﾊﾟﾋﾟﾌﾟﾍﾟﾎﾟｱｲｳｴｵｶﾀｶﾅﾃﾞｽ｡".repeat(20_000);
+    compare(
+        "half_width_katakana_to_full_width",
+        &half_width_kana,
+        baseline::half_width_katakana_to_full_width,
+        half_width_katakana_to_full_width,
+    );
+
+    let mixed = "ｺﾝﾋﾟｭｰﾀｰ 東京都ﾃﾞｰﾀ ABC ｱｲｳ ﾊﾞｲｵﾘﾝ ﾎﾟｲﾝﾄ 123".repeat(20_000);
+    compare(
+        "half_width_katakana_to_full_width (mixed)",
+        &mixed,
+        baseline::half_width_katakana_to_full_width,
+        half_width_katakana_to_full_width,
+    );
+}
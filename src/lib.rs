@@ -7,6 +7,8 @@
 //! - 全角⇔半角変換（ASCII文字）
 //! - カタカナ⇔ひらがな変換
 //! - シンプルでゼロ依存の実装
+//! - `no_std` 対応（`std` feature を無効にすると `alloc` のみで動作）
+//! - 漢数字（`numerals`）、ローマ字入力・音素（`romaji`）、ルビ（`ruby`）は opt-in の feature
 //!
 //! ## 使用例
 //!
@@ -26,10 +28,27 @@
 //! assert_eq!(to_katakana("ひらがな"), "ヒラガナ");
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+pub mod tables;
+
 /// 全角ASCII文字を半角に変換します。
 ///
 /// この関数は全角の英数字や記号（U+FF01-U+FF5E）を、
 /// 対応する半角ASCII文字（U+0021-U+007E）に変換します。
+/// 全角で入力された URL やメールアドレスに含まれる記号（`：／．～％？＝＆＃＠` など）もすべて変換されます。
+///
+/// 波ダッシュ（〜、U+301C）は全角チルダ（～、U+FF5E）とは別の文字のため変換しません。
+/// 日本語入力で `~` のつもりで入力された波ダッシュを含む URL は、
+/// 事前に置き換えてから変換してください。
+///
+/// カタカナは対象外です。全角カタカナは全角のまま、半角カタカナも半角のまま残します。
+/// 全角カタカナも半角にする場合は [`to_half_width_all`] を使用してください。
 ///
 /// # 使用例
 ///
@@ -42,599 +61,9722 @@
 /// assert_eq!(to_half_width("Hello　World"), "Hello World");
 /// ```
 pub fn to_half_width(input: &str) -> String {
-    input
-        .chars()
-        .map(|c| {
-            match c {
-                // Full-width space (U+3000) to half-width space
-                '　' => ' ',
-                // Full-width ASCII variants (U+FF01-U+FF5E) to half-width
-                '\u{FF01}'..='\u{FF5E}' => {
-                    char::from_u32(c as u32 - 0xFF01 + 0x0021).unwrap_or(c)
-                }
-                // Keep other characters as-is
-                _ => c,
-            }
-        })
-        .collect()
+    to_half_width_with(input, ConversionOptions::default())
 }
 
-/// 半角ASCII文字を全角に変換します。
-///
-/// この関数は半角ASCII文字（U+0021-U+007E）を、
-/// 対応する全角文字（U+FF01-U+FF5E）に変換します。
-///
-/// # 使用例
+/// [`to_half_width_with`] で変換する文字の種類を指定するオプションです。
 ///
-/// ```
-/// use japanese_text::to_full_width;
+/// 各フラグが対象とする範囲は次のとおりです。[`Default`] ではすべて有効です。
 ///
-/// assert_eq!(to_full_width("ABC"), "ＡＢＣ");
-/// assert_eq!(to_full_width("123"), "１２３");
-/// assert_eq!(to_full_width("!@#"), "！＠＃");
-/// assert_eq!(to_full_width("Hello World"), "Ｈｅｌｌｏ　Ｗｏｒｌｄ");
-/// ```
-pub fn to_full_width(input: &str) -> String {
-    input
-        .chars()
-        .map(|c| {
-            match c {
-                // Half-width space to full-width space (U+3000)
-                ' ' => '　',
-                // Half-width ASCII (U+0021-U+007E) to full-width
-                '\u{0021}'..='\u{007E}' => {
-                    char::from_u32(c as u32 - 0x0021 + 0xFF01).unwrap_or(c)
-                }
-                // Keep other characters as-is
-                _ => c,
-            }
-        })
-        .collect()
+/// | フラグ | 対象 | 範囲 |
+/// |--------|------|------|
+/// | `convert_space` | 全角スペース | U+3000 |
+/// | `convert_ascii` | 全角英数字（０-９、Ａ-Ｚ、ａ-ｚ） | U+FF10-U+FF19、U+FF21-U+FF3A、U+FF41-U+FF5A |
+/// | `convert_symbols` | 全角記号（！-／、：-＠、［-｀、｛-～） | U+FF01-U+FF0F、U+FF1A-U+FF20、U+FF3B-U+FF40、U+FF5B-U+FF5E |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConversionOptions {
+    /// 全角スペース（U+3000）を半角スペースに変換します。
+    pub convert_space: bool,
+    /// 全角英数字を半角に変換します。
+    pub convert_ascii: bool,
+    /// 全角記号（チルダ ～ を含む）を半角に変換します。
+    pub convert_symbols: bool,
 }
 
-/// カタカナをひらがなに変換します。
+impl Default for ConversionOptions {
+    fn default() -> Self {
+        ConversionOptions {
+            convert_space: true,
+            convert_ascii: true,
+            convert_symbols: true,
+        }
+    }
+}
+
+/// 変換する文字の種類を指定して、全角ASCII文字を半角に変換します。
 ///
-/// この関数はカタカナ文字（U+30A1-U+30F6）を、
-/// 対応するひらがな文字（U+3041-U+3096）に変換します。
+/// 対象の範囲は [`ConversionOptions`] を参照してください。すべてのフラグが有効な場合は
+/// [`to_half_width`] と同じです。
 ///
 /// # 使用例
 ///
 /// ```
-/// use japanese_text::to_hiragana;
+/// use japanese_text::{to_half_width_with, ConversionOptions};
 ///
-/// assert_eq!(to_hiragana("カタカナ"), "かたかな");
-/// assert_eq!(to_hiragana("コンニチハ"), "こんにちは");
-/// assert_eq!(to_hiragana("ヴァイオリン"), "ゔぁいおりん");
+/// // 英数字だけを半角にし、和文中の全角スペースと記号は残す
+/// let opts = ConversionOptions { convert_space: false, convert_symbols: false, ..Default::default() };
+/// assert_eq!(to_half_width_with("（株）ＡＢＣ　ＮＯ．１", opts), "（株）ABC　NO．1");
 /// ```
-pub fn to_hiragana(input: &str) -> String {
-    input
-        .chars()
-        .map(|c| {
-            match c {
-                // Katakana (U+30A1-U+30F6) to Hiragana (U+3041-U+3096)
-                '\u{30A1}'..='\u{30F6}' => {
-                    char::from_u32(c as u32 - 0x30A1 + 0x3041).unwrap_or(c)
-                }
-                // Keep other characters as-is
-                _ => c,
+pub fn to_half_width_with(input: &str, opts: ConversionOptions) -> String {
+    // 変換対象（U+3000, U+FF01-U+FF5E）はすべて 0xE3 または 0xEF で始まる3バイト文字
+    map_chars_with_lead_byte(
+        input,
+        |b| matches!(b, 0xE3 | 0xEF),
+        |c| {
+            let enabled = match c {
+                '　' => opts.convert_space,
+                '０'..='９' | 'Ａ'..='Ｚ' | 'ａ'..='ｚ' => opts.convert_ascii,
+                _ => opts.convert_symbols,
+            };
+            if enabled {
+                half_width_char_of(c)
+            } else {
+                c
             }
-        })
-        .collect()
+        },
+    )
 }
 
-/// ひらがなをカタカナに変換します。
+/// 全角ASCII・全角スペースに加えて、全角カタカナも半角に変換します。
 ///
-/// この関数はひらがな文字（U+3041-U+3096）を、
-/// 対応するカタカナ文字（U+30A1-U+30F6）に変換します。
+/// [`to_half_width`] の後に [`full_width_katakana_to_half_width`] を適用します。
+/// 濁音・半濁音は半角の濁点・半濁点との2文字に分解され（`ガ` → `ｶﾞ`）、
+/// 句読点・かぎ括弧・中黒・長音記号も半角になります。
+/// ひらがなと漢字、対応する半角の無いカタカナ（`ヶ` など）はそのまま残します。
 ///
 /// # 使用例
 ///
 /// ```
-/// use japanese_text::to_katakana;
+/// use japanese_text::to_half_width_all;
 ///
-/// assert_eq!(to_katakana("ひらがな"), "ヒラガナ");
-/// assert_eq!(to_katakana("こんにちは"), "コンニチハ");
-/// assert_eq!(to_katakana("ゔぁいおりん"), "ヴァイオリン");
+/// assert_eq!(to_half_width_all("ＡＢＣ　ガイド"), "ABC ｶﾞｲﾄﾞ");
+/// assert_eq!(to_half_width_all("「コーヒー」１杯"), "｢ｺｰﾋｰ｣1杯");
 /// ```
-pub fn to_katakana(input: &str) -> String {
-    input
-        .chars()
-        .map(|c| {
-            match c {
-                // Hiragana (U+3041-U+3096) to Katakana (U+30A1-U+30F6)
-                '\u{3041}'..='\u{3096}' => {
-                    char::from_u32(c as u32 - 0x3041 + 0x30A1).unwrap_or(c)
+pub fn to_half_width_all(input: &str) -> String {
+    full_width_katakana_to_half_width(&to_half_width(input))
+}
+
+/// 指定した先頭バイトで始まる文字だけを変換します（内部ヘルパー関数）。
+///
+/// `is_lead` を満たさないバイトは読み飛ばし、変換が必要な文字の手前までをまとめてコピーします。
+/// 変換対象がすべて 0xE3 以上の先頭バイトを持つ場合に使えます。
+/// ASCII だけのブロックは8バイト単位でまとめて読み飛ばすため、ASCII が大半のテキストでは
+/// ほぼ入力をコピーするだけのコストで済みます。
+fn map_chars_with_lead_byte(
+    input: &str,
+    is_lead: impl Fn(u8) -> bool,
+    f: impl Fn(char) -> char,
+) -> String {
+    const BLOCK: usize = 32;
+
+    let bytes = input.as_bytes();
+    let mut result = String::with_capacity(input.len());
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        // ASCII だけのブロックは変換対象を含まないので、まとめて読み飛ばす
+        if let Some(block) = bytes.get(i..i + BLOCK) {
+            if is_ascii_block(block) {
+                i += BLOCK;
+                continue;
+            }
+        }
+
+        let end = (i + BLOCK).min(bytes.len());
+        while i < end {
+            if is_lead(bytes[i]) {
+                let c = input[i..].chars().next().unwrap_or_default();
+                let converted = f(c);
+                if converted != c {
+                    result.push_str(&input[start..i]);
+                    result.push(converted);
+                    start = i + c.len_utf8();
                 }
-                // Keep other characters as-is
-                _ => c,
+                i += c.len_utf8();
+            } else {
+                i += 1;
             }
-        })
-        .collect()
+        }
+    }
+
+    result.push_str(&input[start..]);
+    result
 }
 
-/// 文字がひらがなかどうかを判定します。
+/// 長さが8の倍数のバイト列がすべて ASCII かどうかを、8バイトずつまとめて判定します（内部ヘルパー関数）。
+fn is_ascii_block(block: &[u8]) -> bool {
+    let mut high_bits = 0;
+    for word in block.chunks_exact(8) {
+        let mut buf = [0; 8];
+        buf.copy_from_slice(word);
+        high_bits |= u64::from_ne_bytes(buf);
+    }
+    high_bits & 0x8080_8080_8080_8080 == 0
+}
+
+/// 全角文字1文字に対応する半角文字を返します。
+///
+/// [`to_half_width`] が使う1文字単位の対応表です。全角英数字・記号（U+FF01-U+FF5E）と
+/// 全角スペース（U+3000）に対応する半角文字を返し、それ以外の文字には `None` を返します。
+/// [`is_full_width`] などの判定と組み合わせて、独自の変換を書く場合に使います。
 ///
 /// # 使用例
 ///
 /// ```
-/// use japanese_text::is_hiragana;
+/// use japanese_text::half_width_char;
 ///
-/// assert_eq!(is_hiragana('あ'), true);
-/// assert_eq!(is_hiragana('ア'), false);
-/// assert_eq!(is_hiragana('A'), false);
+/// assert_eq!(half_width_char('Ａ'), Some('A'));
+/// assert_eq!(half_width_char('　'), Some(' '));
+/// assert_eq!(half_width_char('A'), None);
+/// assert_eq!(half_width_char('ア'), None);
 /// ```
-pub fn is_hiragana(c: char) -> bool {
-    matches!(c, '\u{3041}'..='\u{3096}')
+pub fn half_width_char(c: char) -> Option<char> {
+    match c {
+        // Full-width space (U+3000) to half-width space
+        '　' => Some(' '),
+        // Full-width ASCII variants (U+FF01-U+FF5E) to half-width
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFF01 + 0x0021),
+        _ => None,
+    }
 }
 
-/// 文字がカタカナかどうかを判定します。
+/// 全角ASCII文字1文字を半角に変換し、対応が無ければそのまま返します（内部ヘルパー関数）。
+fn half_width_char_of(c: char) -> char {
+    half_width_char(c).unwrap_or(c)
+}
+
+/// [`to_half_width`] で変換されずに残る全角幅の文字を、位置とともに返します。
+///
+/// 表示幅が2（[`is_wide`]）で、[`to_half_width`] に対応する半角文字が無い文字を対象とします。
+/// ひらがな・カタカナ・漢字・絵文字は変換対象外であることが明らかなため含めません。
+/// 変換テーブルの拡張が必要な文字種（`￥`、`〜`、`「」` など）を調べる用途を想定しています。
+///
+/// 位置は入力文字列の**バイトオフセット**です（文字インデックスではありません）。
+/// そのまま `&input[offset..]` のようにスライスに使えます。
 ///
 /// # 使用例
 ///
 /// ```
-/// use japanese_text::is_katakana;
+/// use japanese_text::find_unconverted_full_width;
 ///
-/// assert_eq!(is_katakana('ア'), true);
-/// assert_eq!(is_katakana('あ'), false);
-/// assert_eq!(is_katakana('A'), false);
+/// assert_eq!(
+///     find_unconverted_full_width("Ａ￥１００〜"),
+///     vec![(3, '￥'), (15, '〜')]
+/// );
+/// assert!(find_unconverted_full_width("ＡＢＣ　かな漢字").is_empty());
 /// ```
-pub fn is_katakana(c: char) -> bool {
-    matches!(c, '\u{30A1}'..='\u{30F6}')
+pub fn find_unconverted_full_width(input: &str) -> Vec<(usize, char)> {
+    input
+        .char_indices()
+        .filter(|&(_, c)| {
+            is_wide(c)
+                && half_width_char(c).is_none()
+                && !matches!(
+                    classify(c),
+                    CharClass::Hiragana | CharClass::Katakana | CharClass::Kanji
+                )
+                && !is_emoji(c)
+        })
+        .collect()
 }
 
-/// 文字が半角カタカナかどうかを判定します。
+/// [`to_half_width_protected`] に渡された保護範囲が不正であることを表すエラーです。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProtectedRangeError {
+    /// 範囲の開始位置が終了位置より後ろにあるか、終了位置が入力の長さを超えています。
+    OutOfBounds(core::ops::Range<usize>),
+    /// 範囲の開始位置または終了位置が UTF-8 の文字境界ではありません。
+    NotCharBoundary(core::ops::Range<usize>),
+}
+
+impl core::fmt::Display for ProtectedRangeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ProtectedRangeError::OutOfBounds(range) => {
+                write!(f, "protected range {:?} is out of bounds", range)
+            }
+            ProtectedRangeError::NotCharBoundary(range) => {
+                write!(f, "protected range {:?} is not on a char boundary", range)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ProtectedRangeError {}
+
+/// 指定したバイト範囲を除いて、全角ASCII文字を半角に変換します。
 ///
-/// # 使用例
+/// `protected` に含まれる範囲は変換せず、入力のまま出力します。
+/// Markdown のコード部分や URL など、書き換えたくない区間を [`to_half_width`] から保護する用途を想定しています。
+/// 範囲は入力文字列の**バイトオフセット**で指定し、重なっていても順不同でも構いません。
 ///
-/// ```
-/// use japanese_text::is_half_width_katakana;
+/// # エラー
 ///
-/// assert_eq!(is_half_width_katakana('ｱ'), true);
-/// assert_eq!(is_half_width_katakana('ア'), false);
-/// assert_eq!(is_half_width_katakana('A'), false);
-/// ```
-pub fn is_half_width_katakana(c: char) -> bool {
-    matches!(c, '\u{FF61}'..='\u{FF9F}')
-}
-
-/// 文字が漢字（CJK統合漢字）かどうかを判定します。
+/// 範囲が入力の外を指す場合は [`ProtectedRangeError::OutOfBounds`] を、
+/// 開始位置・終了位置が文字境界にない場合は [`ProtectedRangeError::NotCharBoundary`] を返します。
+/// いずれの場合もパニックしません。
 ///
 /// # 使用例
 ///
 /// ```
-/// use japanese_text::is_kanji;
+/// use japanese_text::{to_half_width_protected, ProtectedRangeError};
 ///
-/// assert_eq!(is_kanji('漢'), true);
-/// assert_eq!(is_kanji('字'), true);
-/// assert_eq!(is_kanji('あ'), false);
-/// assert_eq!(is_kanji('A'), false);
+/// let input = "ＡＢＣ ＤＥＦ";
+/// assert_eq!(to_half_width_protected(input, &[10..19]), Ok("ABC ＤＥＦ".to_string()));
+///
+/// assert_eq!(
+///     to_half_width_protected(input, &[0..1]),
+///     Err(ProtectedRangeError::NotCharBoundary(0..1))
+/// );
 /// ```
-pub fn is_kanji(c: char) -> bool {
-    matches!(c, '\u{4E00}'..='\u{9FFF}')
+pub fn to_half_width_protected(
+    input: &str,
+    protected: &[core::ops::Range<usize>],
+) -> Result<String, ProtectedRangeError> {
+    for range in protected {
+        if range.start > range.end || range.end > input.len() {
+            return Err(ProtectedRangeError::OutOfBounds(range.clone()));
+        }
+        if !input.is_char_boundary(range.start) || !input.is_char_boundary(range.end) {
+            return Err(ProtectedRangeError::NotCharBoundary(range.clone()));
+        }
+    }
+
+    let mut ranges = protected.to_vec();
+    ranges.sort_by_key(|range| range.start);
+    Ok(convert_half_width_outside(input, &ranges))
 }
 
-/// 文字が全角文字かどうかを判定します。
+/// 指定したバイト範囲の中だけ、全角ASCII文字を半角に変換します。
+///
+/// 範囲の外は入力のまま連結します。マークアップ中の特定の要素の中だけを正規化する用途を想定しています。
+/// 範囲外を保護する場合は [`to_half_width_protected`] を使用してください。
+///
+/// 範囲はパニックしないように次のとおり丸めます。
+///
+/// - 終了位置は入力の長さまでに切り詰め、文字の途中を指す場合はその文字の末尾まで広げます。
+/// - 開始位置が文字の途中を指す場合はその文字の先頭まで広げます。
+/// - 開始位置が終了位置より後ろにある場合は空の範囲として扱い、何も変換しません。
 ///
 /// # 使用例
 ///
 /// ```
-/// use japanese_text::is_full_width;
+/// use japanese_text::to_half_width_range;
 ///
-/// assert_eq!(is_full_width('Ａ'), true);
-/// assert_eq!(is_full_width('１'), true);
-/// assert_eq!(is_full_width('A'), false);
+/// let input = "ＡＢ<b>ＣＤ</b>ＥＦ";
+/// assert_eq!(to_half_width_range(input, 9..15), "ＡＢ<b>CD</b>ＥＦ");
+///
+/// // 文字の途中を指す範囲は、文字全体を含むように広げる
+/// assert_eq!(to_half_width_range("ＡＢＣ", 4..5), "ＡBＣ");
 /// ```
-pub fn is_full_width(c: char) -> bool {
-    matches!(c, '\u{FF01}'..='\u{FF5E}' | '　')
+pub fn to_half_width_range(input: &str, range: core::ops::Range<usize>) -> String {
+    let mut end = range.end.min(input.len());
+    while !input.is_char_boundary(end) {
+        end += 1;
+    }
+    let mut start = range.start.min(end);
+    while !input.is_char_boundary(start) {
+        start -= 1;
+    }
+
+    let mut result = String::with_capacity(input.len());
+    result.push_str(&input[..start]);
+    result.push_str(&to_half_width(&input[start..end]));
+    result.push_str(&input[end..]);
+    result
 }
 
-/// 文字列内の各文字種の数をカウントします。
+/// バッククォートで囲まれたコード部分を除いて、全角ASCII文字を半角に変換します。
+///
+/// Markdown のコードスパンと同様に、同じ個数のバッククォートの連続で囲まれた区間（囲みのバッククォートを含む）を保護します。
+/// そのため `` `code` `` のようなインラインコードに加えて、```` ``` ```` で囲まれたコードブロックも保護されます。
+/// 閉じられていないバッククォートは通常の文字として扱います。
+/// 全角のバッククォート（｀）は区切りとして扱わず、半角に変換します。
 ///
 /// # 使用例
 ///
 /// ```
-/// use japanese_text::count_character_types;
+/// use japanese_text::to_half_width_skip_code;
 ///
-/// let counts = count_character_types("あア漢ABC123");
-/// assert_eq!(counts.hiragana, 1);
-/// assert_eq!(counts.katakana, 1);
-/// assert_eq!(counts.kanji, 1);
-/// assert_eq!(counts.ascii, 6);
+/// assert_eq!(to_half_width_skip_code("ＡＢＣ `ＤＥＦ` ＧＨＩ"), "ABC `ＤＥＦ` GHI");
+/// assert_eq!(to_half_width_skip_code("```\nＡ\n```\nＢ"), "```\nＡ\n```\nB");
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct CharacterTypes {
-    pub hiragana: usize,
-    pub katakana: usize,
-    pub half_width_katakana: usize,
-    pub kanji: usize,
-    pub ascii: usize,
-    pub full_width: usize,
-    pub other: usize,
+pub fn to_half_width_skip_code(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut ranges = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] != b'`' {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        let run = backtick_run(bytes, i);
+        i += run;
+
+        // 同じ長さのバッククォートの連続を閉じとして探す
+        let mut j = i;
+        while j < bytes.len() {
+            if bytes[j] == b'`' {
+                let closing = backtick_run(bytes, j);
+                j += closing;
+                if closing == run {
+                    ranges.push(start..j);
+                    i = j;
+                    break;
+                }
+            } else {
+                j += 1;
+            }
+        }
+    }
+
+    convert_half_width_outside(input, &ranges)
 }
 
-pub fn count_character_types(input: &str) -> CharacterTypes {
-    let mut counts = CharacterTypes {
-        hiragana: 0,
-        katakana: 0,
-        half_width_katakana: 0,
-        kanji: 0,
-        ascii: 0,
-        full_width: 0,
-        other: 0,
-    };
+/// 指定位置から続くバッククォートの個数を返します（内部ヘルパー関数）。
+fn backtick_run(bytes: &[u8], start: usize) -> usize {
+    bytes[start..].iter().take_while(|&&b| b == b'`').count()
+}
 
-    for c in input.chars() {
-        if is_hiragana(c) {
-            counts.hiragana += 1;
-        } else if is_katakana(c) {
-            counts.katakana += 1;
-        } else if is_half_width_katakana(c) {
-            counts.half_width_katakana += 1;
-        } else if is_kanji(c) {
-            counts.kanji += 1;
-        } else if c.is_ascii() {
-            counts.ascii += 1;
-        } else if is_full_width(c) {
-            counts.full_width += 1;
-        } else {
-            counts.other += 1;
+/// 開始位置順に並んだ保護範囲の外側だけを半角に変換します（内部ヘルパー関数）。
+fn convert_half_width_outside(input: &str, ranges: &[core::ops::Range<usize>]) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut pos = 0;
+
+    for range in ranges {
+        if range.start > pos {
+            result.push_str(&to_half_width(&input[pos..range.start]));
+        }
+        if range.end > pos {
+            result.push_str(&input[pos.max(range.start)..range.end]);
+            pos = range.end;
         }
     }
 
-    counts
+    result.push_str(&to_half_width(&input[pos..]));
+    result
 }
 
-/// 文字列内の空白文字を正規化します（全角スペース、タブなどを半角スペースに統一）。
+/// テキスト中の URL とメールアドレスのバイト範囲を検出します。
+///
+/// [`to_half_width_protected`] と組み合わせると、URL やメールアドレスを保ったまま本文だけを正規化できます。
+/// 外部クレートに依存しない簡易的なスキャナで、次の基本形だけを検出します。
+///
+/// - URL: `http://` または `https://`（小文字のみ）で始まり、空白・`<` `>` `"`・ASCII 以外の文字の直前まで。
+///   末尾の `.` `,` `;` `:` `!` `?` は文の区切りとみなして範囲に含めません。
+/// - メールアドレス: `@` の前に英数字と `.` `_` `%` `+` `-` が1文字以上、
+///   後ろに英数字と `.` `-` からなるドメインが続き、ドメインが `.` を含み最後の `.` の後に英字が1文字以上あるもの。
+///
+/// 全角文字で書かれた URL（`ｈｔｔｐｓ：／／`）は検出しません。
+/// 範囲は入力文字列のバイトオフセットで、開始位置順に重ならずに並びます。
 ///
 /// # 使用例
 ///
 /// ```
-/// use japanese_text::normalize_whitespace;
+/// use japanese_text::{protect_urls, to_half_width_protected};
 ///
-/// assert_eq!(normalize_whitespace("Hello　World"), "Hello World");
-/// assert_eq!(normalize_whitespace("A\t\tB"), "A B");
+/// let input = "詳細はhttps://example.com/~ＡＢＣ、連絡はinfo@example.jpまで";
+/// let ranges = protect_urls(input);
+/// assert_eq!(&input[ranges[0].clone()], "https://example.com/~");
+/// assert_eq!(&input[ranges[1].clone()], "info@example.jp");
+///
+/// let input = "ＵＲＬ：https://example.com/~user";
+/// let converted = to_half_width_protected(input, &protect_urls(input)).unwrap();
+/// assert_eq!(converted, "URL:https://example.com/~user");
 /// ```
-pub fn normalize_whitespace(input: &str) -> String {
-    input
-        .chars()
-        .map(|c| {
-            if c.is_whitespace() || c == '　' {
-                ' '
-            } else {
-                c
+pub fn protect_urls(input: &str) -> Vec<core::ops::Range<usize>> {
+    let bytes = input.as_bytes();
+    let mut ranges: Vec<core::ops::Range<usize>> = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let rest = &bytes[i..];
+        if rest.starts_with(b"http://") || rest.starts_with(b"https://") {
+            let mut end = i + bytes[i..]
+                .iter()
+                .take_while(|&&b| b.is_ascii_graphic() && !matches!(b, b'<' | b'>' | b'"'))
+                .count();
+            while matches!(bytes[end - 1], b'.' | b',' | b';' | b':' | b'!' | b'?') {
+                end -= 1;
             }
-        })
-        .collect::<String>()
-        .split_whitespace()
-        .collect::<Vec<_>>()
-        .join(" ")
+            ranges.push(i..end);
+            i = end;
+        } else if bytes[i] == b'@' {
+            // ローカル部は直前の範囲の終わりより前にさかのぼらないため、範囲は重ならない
+            let floor = ranges.last().map_or(0, |range| range.end);
+            if let Some(range) = email_range(bytes, floor, i) {
+                i = range.end;
+                ranges.push(range);
+                continue;
+            }
+            i += 1;
+        } else {
+            i += 1;
+        }
+    }
+
+    ranges
 }
 
-/// 半角カタカナを全角カタカナに変換します。
+/// `@` の位置からメールアドレスの範囲を求めます（内部ヘルパー関数）。
 ///
-/// 濁点（゛）と半濁点（゜）も正しく結合されます。
+/// ローカル部は `floor` より前にはさかのぼりません。
+fn email_range(bytes: &[u8], floor: usize, at: usize) -> Option<core::ops::Range<usize>> {
+    let is_local = |b: u8| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'_' | b'%' | b'+' | b'-');
+    let is_domain = |b: u8| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-');
+
+    let start = at - bytes[floor..at].iter().rev().take_while(|&&b| is_local(b)).count();
+    let mut end = at + 1 + bytes[at + 1..].iter().take_while(|&&b| is_domain(b)).count();
+    while end > at + 1 && matches!(bytes[end - 1], b'.' | b'-') {
+        end -= 1;
+    }
+
+    let domain = &bytes[at + 1..end];
+    let last_dot = domain.iter().rposition(|&b| b == b'.')?;
+    let valid = start < at
+        && last_dot > 0
+        && domain[last_dot + 1..].iter().any(|b| b.is_ascii_alphabetic());
+    valid.then_some(start..end)
+}
+
+/// 英数字に隣接する全角記号だけを半角化し、和文の約物として使われる全角記号は残します。
+///
+/// [`to_half_width`] のように全角ASCII文字を一括で変換すると、「（株）」の括弧のような
+/// 和文中の記号まで半角になります。この関数は次の規則で変換する文字を選びます。
+///
+/// 1. 全角英数字（Ａ-Ｚ、ａ-ｚ、０-９）は常に半角化します。
+/// 2. 全角記号（U+FF01-U+FF5E のうち英数字以外）は、連続する記号をひとまとまりとして扱い、
+///    そのまとまりの直前または直後が英数字（半角・全角）の場合に半角化します。
+///    ただし開き括弧（（［｛＜）は直後、閉じ括弧（）］｝＞）は直前が英数字の場合だけ半角化します。
+/// 3. それ以外の全角記号と全角スペース（U+3000）はそのまま残します。
+///
+/// 英数字に隣接するかどうかだけを見るヒューリスティックのため、「価格：１００円」の「：」のように
+/// 和文の記号でも数字に接していれば半角化されます。
 ///
 /// # 使用例
 ///
 /// ```
-/// use japanese_text::half_width_katakana_to_full_width;
+/// use japanese_text::context_aware_half_width;
 ///
-/// assert_eq!(half_width_katakana_to_full_width("ｶﾀｶﾅ"), "カタカナ");
-/// assert_eq!(half_width_katakana_to_full_width("ｶﾞｷﾞｸﾞｹﾞｺﾞ"), "ガギグゲゴ");
-/// assert_eq!(half_width_katakana_to_full_width("ﾊﾟﾋﾟﾌﾟﾍﾟﾎﾟ"), "パピプペポ");
+/// assert_eq!(context_aware_half_width("（株）ＡＢＣ（１）"), "（株）ABC(1)");
+/// assert_eq!(context_aware_half_width("本当！？　ＯＫ！？"), "本当！？　OK!?");
 /// ```
-pub fn half_width_katakana_to_full_width(input: &str) -> String {
+pub fn context_aware_half_width(input: &str) -> String {
     let chars: Vec<char> = input.chars().collect();
-    let mut result = String::new();
+    let mut result = String::with_capacity(input.len());
     let mut i = 0;
 
     while i < chars.len() {
         let c = chars[i];
+        if !tables::FULL_WIDTH_ASCII_RANGE.contains(&c) {
+            result.push(c);
+            i += 1;
+            continue;
+        }
+        if is_alphanumeric_either_width(c) {
+            result.push(half_width_char_of(c));
+            i += 1;
+            continue;
+        }
 
-        // 次の文字が濁点または半濁点かチェック
-        let next = if i + 1 < chars.len() {
-            Some(chars[i + 1])
-        } else {
-            None
-        };
+        // 連続する全角記号の範囲を求め、前後の文字で半角化するかを決める
+        let end = chars[i..]
+            .iter()
+            .position(|&c| {
+                !tables::FULL_WIDTH_ASCII_RANGE.contains(&c) || is_alphanumeric_either_width(c)
+            })
+            .map_or(chars.len(), |n| i + n);
+        let before = i
+            .checked_sub(1)
+            .is_some_and(|j| is_alphanumeric_either_width(chars[j]));
+        let after = chars.get(end).is_some_and(|&c| is_alphanumeric_either_width(c));
 
-        match (c, next) {
-            // 濁点付き
-            ('ｶ', Some('ﾞ')) => { result.push('ガ'); i += 2; }
-            ('ｷ', Some('ﾞ')) => { result.push('ギ'); i += 2; }
-            ('ｸ', Some('ﾞ')) => { result.push('グ'); i += 2; }
-            ('ｹ', Some('ﾞ')) => { result.push('ゲ'); i += 2; }
-            ('ｺ', Some('ﾞ')) => { result.push('ゴ'); i += 2; }
-            ('ｻ', Some('ﾞ')) => { result.push('ザ'); i += 2; }
-            ('ｼ', Some('ﾞ')) => { result.push('ジ'); i += 2; }
-            ('ｽ', Some('ﾞ')) => { result.push('ズ'); i += 2; }
-            ('ｾ', Some('ﾞ')) => { result.push('ゼ'); i += 2; }
-            ('ｿ', Some('ﾞ')) => { result.push('ゾ'); i += 2; }
-            ('ﾀ', Some('ﾞ')) => { result.push('ダ'); i += 2; }
-            ('ﾁ', Some('ﾞ')) => { result.push('ヂ'); i += 2; }
-            ('ﾂ', Some('ﾞ')) => { result.push('ヅ'); i += 2; }
-            ('ﾃ', Some('ﾞ')) => { result.push('デ'); i += 2; }
-            ('ﾄ', Some('ﾞ')) => { result.push('ド'); i += 2; }
-            ('ﾊ', Some('ﾞ')) => { result.push('バ'); i += 2; }
-            ('ﾋ', Some('ﾞ')) => { result.push('ビ'); i += 2; }
-            ('ﾌ', Some('ﾞ')) => { result.push('ブ'); i += 2; }
-            ('ﾍ', Some('ﾞ')) => { result.push('ベ'); i += 2; }
-            ('ﾎ', Some('ﾞ')) => { result.push('ボ'); i += 2; }
-            ('ｳ', Some('ﾞ')) => { result.push('ヴ'); i += 2; }
-
-            // 半濁点付き
-            ('ﾊ', Some('ﾟ')) => { result.push('パ'); i += 2; }
-            ('ﾋ', Some('ﾟ')) => { result.push('ピ'); i += 2; }
-            ('ﾌ', Some('ﾟ')) => { result.push('プ'); i += 2; }
-            ('ﾍ', Some('ﾟ')) => { result.push('ペ'); i += 2; }
-            ('ﾎ', Some('ﾟ')) => { result.push('ポ'); i += 2; }
-
-            // 通常の半角カタカナ
-            _ => {
-                let full = match c {
-                    'ｦ' => 'ヲ', 'ｧ' => 'ァ', 'ｨ' => 'ィ', 'ｩ' => 'ゥ', 'ｪ' => 'ェ', 'ｫ' => 'ォ',
-                    'ｬ' => 'ャ', 'ｭ' => 'ュ', 'ｮ' => 'ョ', 'ｯ' => 'ッ', 'ｰ' => 'ー',
-                    'ｱ' => 'ア', 'ｲ' => 'イ', 'ｳ' => 'ウ', 'ｴ' => 'エ', 'ｵ' => 'オ',
-                    'ｶ' => 'カ', 'ｷ' => 'キ', 'ｸ' => 'ク', 'ｹ' => 'ケ', 'ｺ' => 'コ',
-                    'ｻ' => 'サ', 'ｼ' => 'シ', 'ｽ' => 'ス', 'ｾ' => 'セ', 'ｿ' => 'ソ',
-                    'ﾀ' => 'タ', 'ﾁ' => 'チ', 'ﾂ' => 'ツ', 'ﾃ' => 'テ', 'ﾄ' => 'ト',
-                    'ﾅ' => 'ナ', 'ﾆ' => 'ニ', 'ﾇ' => 'ヌ', 'ﾈ' => 'ネ', 'ﾉ' => 'ノ',
-                    'ﾊ' => 'ハ', 'ﾋ' => 'ヒ', 'ﾌ' => 'フ', 'ﾍ' => 'ヘ', 'ﾎ' => 'ホ',
-                    'ﾏ' => 'マ', 'ﾐ' => 'ミ', 'ﾑ' => 'ム', 'ﾒ' => 'メ', 'ﾓ' => 'モ',
-                    'ﾔ' => 'ヤ', 'ﾕ' => 'ユ', 'ﾖ' => 'ヨ',
-                    'ﾗ' => 'ラ', 'ﾘ' => 'リ', 'ﾙ' => 'ル', 'ﾚ' => 'レ', 'ﾛ' => 'ロ',
-                    'ﾜ' => 'ワ', 'ﾝ' => 'ン',
-                    '｡' => '。', '｢' => '「', '｣' => '」', '､' => '、', '･' => '・',
-                    _ => c,
-                };
-                result.push(full);
-                i += 1;
-            }
+        for &c in &chars[i..end] {
+            let adjacent = match c {
+                '（' | '［' | '｛' | '＜' => after,
+                '）' | '］' | '｝' | '＞' => before,
+                _ => before || after,
+            };
+            result.push(if adjacent { half_width_char_of(c) } else { c });
         }
+        i = end;
     }
 
     result
 }
 
-/// 長音記号を正規化します（ー、〜、～などを統一）。
+/// 半角または全角の英数字かどうかを判定します（内部ヘルパー関数）。
+fn is_alphanumeric_either_width(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, 'Ａ'..='Ｚ' | 'ａ'..='ｚ' | '０'..='９')
+}
+
+/// 半角ASCII文字を全角に変換します。
 ///
-/// # 使用例
+/// この関数は半角ASCII文字（U+0021-U+007E）を、
+/// 対応する全角文字（U+FF01-U+FF5E）に変換します。
+///
+/// # 使用例
 ///
 /// ```
-/// use japanese_text::normalize_prolonged_sound;
+/// use japanese_text::to_full_width;
 ///
-/// assert_eq!(normalize_prolonged_sound("コーヒー"), "コーヒー");
-/// assert_eq!(normalize_prolonged_sound("コ〜ヒ〜"), "コーヒー");
+/// assert_eq!(to_full_width("ABC"), "ＡＢＣ");
+/// assert_eq!(to_full_width("123"), "１２３");
+/// assert_eq!(to_full_width("!@#"), "！＠＃");
+/// assert_eq!(to_full_width("Hello World"), "Ｈｅｌｌｏ　Ｗｏｒｌｄ");
 /// ```
-pub fn normalize_prolonged_sound(input: &str) -> String {
+pub fn to_full_width(input: &str) -> String {
+    // 半角ASCII（1バイト）は全角（3バイト）になるため、最大で入力の3倍の長さを確保する
+    let mut result = String::with_capacity(input.len().saturating_mul(3));
+    for c in input.chars() {
+        result.push(full_width_char_of(c));
+    }
+    result
+}
+
+/// 半角文字1文字に対応する全角文字を返します。
+///
+/// [`to_full_width`] が使う1文字単位の対応表です。半角ASCII（U+0021-U+007E）と
+/// 半角スペースに対応する全角文字を返し、それ以外の文字（制御文字を含む）には `None` を返します。
+/// [`half_width_char`] の逆の対応です。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::full_width_char;
+///
+/// assert_eq!(full_width_char('A'), Some('Ａ'));
+/// assert_eq!(full_width_char(' '), Some('　'));
+/// assert_eq!(full_width_char('\n'), None);
+/// assert_eq!(full_width_char('Ａ'), None);
+/// ```
+pub fn full_width_char(c: char) -> Option<char> {
+    match c {
+        // Half-width space to full-width space (U+3000)
+        ' ' => Some('　'),
+        // Half-width ASCII (U+0021-U+007E) to full-width
+        '\u{0021}'..='\u{007E}' => char::from_u32(c as u32 - 0x0021 + 0xFF01),
+        _ => None,
+    }
+}
+
+/// 半角ASCII文字1文字を全角に変換し、対応が無ければそのまま返します（内部ヘルパー関数）。
+fn full_width_char_of(c: char) -> char {
+    full_width_char(c).unwrap_or(c)
+}
+
+/// 全角と半角を1文字ずつ切り替えます。
+///
+/// 全角ASCII（U+FF01-U+FF5E）と全角スペースは半角に、半角ASCIIと半角スペースは全角に変換します。
+/// 各文字を独立に判定するため、全角と半角が混在した入力はそれぞれ反対側の幅になります。
+/// エディタの「全角/半角の切り替え」のように、選択範囲の幅を反転する用途を想定しています。
+/// 混在したテキストを一方の幅にそろえたい場合は [`to_half_width`] / [`to_full_width`] を使用してください。
+///
+/// どちらにも該当しない文字（かな・漢字・制御文字など）はそのまま残します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::toggle_width;
+///
+/// assert_eq!(toggle_width("ABC"), "ＡＢＣ");
+/// assert_eq!(toggle_width("ＡＢＣ"), "ABC");
+/// assert_eq!(toggle_width("Ａb　c 漢字"), "Aｂ ｃ　漢字");
+/// ```
+pub fn toggle_width(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| half_width_char(c).or_else(|| full_width_char(c)).unwrap_or(c))
+        .collect()
+}
+
+/// 半角・全角のラテン文字を大文字に変換します。
+///
+/// 半角の `a-z` は `A-Z` に、全角の `ａ-ｚ`（U+FF41-U+FF5A）は `Ａ-Ｚ`（U+FF21-U+FF3A）に変換します。
+/// 全角は半角のまま、半角は全角のまま変換するため、文字幅は変わりません。
+/// 英字以外の文字（アクセント付きのラテン文字を含む）はそのまま残します。
+///
+/// 大文字・小文字の変換と [`to_half_width`] / [`to_full_width`] はどちらを先に適用しても
+/// 結果は同じです。ただし `str::to_uppercase` などの標準の変換を併用する場合は、
+/// 英字以外も変換されるため、先に [`to_half_width`] で半角にそろえてから適用してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::to_upper_wide;
+///
+/// assert_eq!(to_upper_wide("abcａｂｃ１２３"), "ABCＡＢＣ１２３");
+/// ```
+pub fn to_upper_wide(input: &str) -> String {
     input
         .chars()
         .map(|c| match c {
-            '〜' | '～' => 'ー',
+            'a'..='z' => c.to_ascii_uppercase(),
+            'ａ'..='ｚ' => char::from_u32(c as u32 - 0xFF41 + 0xFF21).unwrap_or(c),
             _ => c,
         })
         .collect()
 }
 
-/// 繰り返し記号を展開します。
+/// 半角・全角のラテン文字を小文字に変換します。
 ///
-/// ひらがな・カタカナの繰り返し記号（ゝ、ゞ、ヽ、ヾ）を実際の文字に展開します。
+/// [`to_upper_wide`] の逆変換です。全角の `Ａ-Ｚ`（U+FF21-U+FF3A）は `ａ-ｚ`（U+FF41-U+FF5A）に変換します。
 ///
 /// # 使用例
 ///
 /// ```
-/// use japanese_text::expand_iteration_marks;
+/// use japanese_text::to_lower_wide;
 ///
-/// assert_eq!(expand_iteration_marks("いろゝ"), "いろろ");
-/// assert_eq!(expand_iteration_marks("かゞ"), "かが");
+/// assert_eq!(to_lower_wide("ABCＡＢＣ１２３"), "abcａｂｃ１２３");
 /// ```
-pub fn expand_iteration_marks(input: &str) -> String {
-    let chars: Vec<char> = input.chars().collect();
-    let mut result = String::new();
+pub fn to_lower_wide(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| match c {
+            'A'..='Z' => c.to_ascii_lowercase(),
+            'Ａ'..='Ｚ' => char::from_u32(c as u32 - 0xFF21 + 0xFF41).unwrap_or(c),
+            _ => c,
+        })
+        .collect()
+}
+
+/// 全角と半角の括弧を、開き括弧と閉じ括弧の組ごとに相互変換します。
+///
+/// `to_full` が `true` なら半角 → 全角、`false` なら全角 → 半角に変換します。
+/// 変換は下表の組単位で定義されているため、開き括弧だけ・閉じ括弧だけが変換されることはありません。
+///
+/// | 全角 | 半角 |
+/// |------|------|
+/// | `（）` | `()` |
+/// | `［］` | `[]` |
+/// | `｛｝` | `{}` |
+/// | `＜＞` | `<>` |
+/// | `「」` | `｢｣` |
+/// | `｟｠` | `⦅⦆` |
+///
+/// `『』`、`【】`、`〔〕`、`〈〉`、`《》` は対応する半角の括弧が無いため変換しません。
+/// 括弧以外の文字もそのまま残します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::convert_brackets;
+///
+/// assert_eq!(convert_brackets("「注意」（必読）", false), "｢注意｣(必読)");
+/// assert_eq!(convert_brackets("[a](b)", true), "［a］（b）");
+/// ```
+pub fn convert_brackets(input: &str, to_full: bool) -> String {
+    input
+        .chars()
+        .map(|c| {
+            for &(full_open, full_close, half_open, half_close) in BRACKET_PAIRS {
+                let (from_open, from_close, to_open, to_close) = if to_full {
+                    (half_open, half_close, full_open, full_close)
+                } else {
+                    (full_open, full_close, half_open, half_close)
+                };
+                if c == from_open {
+                    return to_open;
+                }
+                if c == from_close {
+                    return to_close;
+                }
+            }
+            c
+        })
+        .collect()
+}
+
+/// 全角と半角の括弧の組の一覧です（全角の開き、全角の閉じ、半角の開き、半角の閉じ）。
+const BRACKET_PAIRS: &[(char, char, char, char)] = &[
+    ('（', '）', '(', ')'),
+    ('［', '］', '[', ']'),
+    ('｛', '｝', '{', '}'),
+    ('＜', '＞', '<', '>'),
+    ('「', '」', '｢', '｣'),
+    ('｟', '｠', '⦅', '⦆'),
+];
+
+/// カタカナをひらがなに変換します。
+///
+/// この関数はカタカナ文字（U+30A1-U+30F6）を、
+/// 対応するひらがな文字（U+3041-U+3096）に変換します。
+/// 歴史的仮名（ヰ → ゐ、ヱ → ゑ、ヲ → を）も変換します。繰り返し記号（ヽ ヾ）は変換しません。
+///
+/// 対応するひらがなが無い以下の文字はそのまま残します。
+///
+/// - 濁点付きのワ行（ヷ ヸ ヹ ヺ）とコト（ヿ）
+/// - アイヌ語表記用の小書きカタカナ（U+31F0-U+31FF、ㇰ ㇱ など、[`is_katakana_phonetic_ext`] を参照）
+/// - 丸付きカタカナ（U+32D0-U+32FE、㋐ など）と半角カタカナ
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::to_hiragana;
+///
+/// assert_eq!(to_hiragana("カタカナ"), "かたかな");
+/// assert_eq!(to_hiragana("コンニチハ"), "こんにちは");
+/// assert_eq!(to_hiragana("ヴァイオリン"), "ゔぁいおりん");
+/// ```
+pub fn to_hiragana(input: &str) -> String {
+    // カタカナ（U+30A1-U+30F6）はすべて 0xE3 で始まる3バイト文字
+    map_chars_with_lead_byte(input, |b| b == 0xE3, hiragana_char)
+}
+
+/// カタカナ1文字をひらがなに変換します。
+///
+/// [`to_hiragana`] が使う1文字単位の変換です。対応するひらがなが無い文字はそのまま返します。
+/// 1文字だけ変換したい場合に、`String` を経由せずに使えます。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::hiragana_char;
+///
+/// assert_eq!(hiragana_char('ア'), 'あ');
+/// assert_eq!(hiragana_char('ヰ'), 'ゐ');
+/// assert_eq!(hiragana_char('ヷ'), 'ヷ');
+/// assert_eq!(hiragana_char('A'), 'A');
+/// ```
+pub fn hiragana_char(c: char) -> char {
+    match c {
+        // Katakana (U+30A1-U+30F6) to Hiragana (U+3041-U+3096)
+        '\u{30A1}'..='\u{30F6}' => char::from_u32(c as u32 - 0x30A1 + 0x3041).unwrap_or(c),
+        // Keep other characters as-is
+        _ => c,
+    }
+}
+
+/// [`to_hiragana_with`] で「ヴ」をどのように出力するかを指定します。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VuHandling {
+    /// 合成済みの「ゔ」（U+3094）を出力します。
+    #[default]
+    Precomposed,
+    /// 「う」と結合用濁点（U+3099）に分解して出力します。
+    Decomposed,
+    /// 「ゔ」を表示できない環境向けに、バ行で近似します（「ヴ」→「ぶ」、「ヴァ」→「ば」）。
+    Fallback,
+}
+
+/// [`to_hiragana_with`] や [`search_key_with`] で長音記号（ー）をどのように扱うかを指定します。
+///
+/// 既定は [`ProlongedPolicy::Keep`] です。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProlongedPolicy {
+    /// 長音記号をそのまま残します（`コーヒー` → `こーひー`）。
+    #[default]
+    Keep,
+    /// 長音記号を直前のかなの母音に展開します（`コーヒー` → `こうひい`）。
+    ToVowel,
+    /// 長音記号を取り除きます（`コーヒー` → `こひ`）。
+    Remove,
+}
+
+/// [`to_hiragana_with`] の動作を指定するオプションです。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HiraganaOptions {
+    /// 長音記号（ー）の扱いです。既定では [`ProlongedPolicy::Keep`] で、そのまま残します。
+    pub prolonged: ProlongedPolicy,
+    /// 「ヴ」の出力方法です。既定では「ゔ」を出力します。
+    pub vu_handling: VuHandling,
+}
+
+/// オプションを指定してカタカナをひらがなに変換します。
+///
+/// 長音を母音に展開する変換もこの関数で行います。`prolonged` に [`ProlongedPolicy::ToVowel`] を
+/// 指定すると、検索用途で「ラーメン」と「らあめん」を同一視できます。
+///
+/// `prolonged` で長音記号（ー）の扱いを指定します。既定の [`ProlongedPolicy::Keep`] では
+/// [`to_hiragana`] と同じくそのまま残し、[`ProlongedPolicy::Remove`] ではすべて取り除きます。
+/// [`ProlongedPolicy::ToVowel`] では、直前のかなの段に応じて展開します。
+///
+/// - あ段・い段・う段・え段の後は、それぞれ「あ」「い」「う」「え」
+/// - お段の後は「う」（例: `コーヒー` → `こうひい`）
+/// - 長音記号が連続する場合は、すべて同じ母音に展開
+/// - 文頭や、「ん」「っ」・漢字などの母音を持たない文字の後では展開せず「ー」のまま残す
+///
+/// `vu_handling` は「ヴ」（および入力中の「ゔ」）の出力方法を指定します。
+/// [`VuHandling::Fallback`] では、直後の小書きの「ァィゥェォ」と合わせて
+/// 「ば」「び」「ぶ」「べ」「ぼ」に、「ャュョ」の前では「び」にします（「ヴュ」→「びゅ」）。
+/// 「ヷヸヹヺ」も、[`VuHandling::Decomposed`] では「わゐゑを」と結合用濁点に、
+/// [`VuHandling::Fallback`] では「ばびべぼ」になります。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{to_hiragana_with, HiraganaOptions, ProlongedPolicy, VuHandling};
+///
+/// // 長音を母音に展開する
+/// let opts = HiraganaOptions { prolonged: ProlongedPolicy::ToVowel, ..Default::default() };
+/// assert_eq!(to_hiragana_with("コーヒー", opts), "こうひい");
+/// assert_eq!(to_hiragana_with("コーヒー", HiraganaOptions::default()), "こーひー");
+///
+/// let remove = HiraganaOptions { prolonged: ProlongedPolicy::Remove, ..Default::default() };
+/// assert_eq!(to_hiragana_with("コーヒー", remove), "こひ");
+///
+/// // 検索用途で「ラーメン」と「らあめん」を同一視する
+/// assert_eq!(to_hiragana_with("ラーメン", opts), to_hiragana_with("らあめん", opts));
+///
+/// // 文頭や母音を持たない文字の後の長音記号はそのまま
+/// assert_eq!(to_hiragana_with("ーン", opts), "ーん");
+/// assert_eq!(to_hiragana_with("ンー", opts), "んー");
+///
+/// let opts = HiraganaOptions { vu_handling: VuHandling::Fallback, ..Default::default() };
+/// assert_eq!(to_hiragana_with("ヴァイオリン", opts), "ばいおりん");
+/// ```
+pub fn to_hiragana_with(input: &str, opts: HiraganaOptions) -> String {
+    let mut hiragana = to_hiragana(input);
+    match opts.prolonged {
+        ProlongedPolicy::Keep => {}
+        ProlongedPolicy::ToVowel => hiragana = expand_prolonged_marks(&hiragana),
+        ProlongedPolicy::Remove => hiragana.retain(|c| c != 'ー'),
+    }
+    if opts.vu_handling != VuHandling::Precomposed {
+        hiragana = convert_vu(&hiragana, opts.vu_handling);
+    }
+    hiragana
+}
+
+/// 「ゔ」と「ヷヸヹヺ」を指定した方法で出力し直します（内部ヘルパー関数）。
+fn convert_vu(text: &str, handling: VuHandling) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let base = match c {
+            'ゔ' => 'う',
+            'ヷ' => 'わ',
+            'ヸ' => 'ゐ',
+            'ヹ' => 'ゑ',
+            'ヺ' => 'を',
+            _ => {
+                result.push(c);
+                continue;
+            }
+        };
+
+        match handling {
+            VuHandling::Precomposed => result.push(c),
+            VuHandling::Decomposed => {
+                result.push(base);
+                result.push('\u{3099}');
+            }
+            VuHandling::Fallback => {
+                let small = if c == 'ゔ' { chars.peek().copied() } else { None };
+                let fallback = match small {
+                    Some('ぁ') => 'ば',
+                    Some('ぃ') => 'び',
+                    Some('ぅ') => 'ぶ',
+                    Some('ぇ') => 'べ',
+                    Some('ぉ') => 'ぼ',
+                    Some('ゃ' | 'ゅ' | 'ょ') => 'び',
+                    _ => match base {
+                        'わ' => 'ば',
+                        'ゐ' => 'び',
+                        'ゑ' => 'べ',
+                        'を' => 'ぼ',
+                        _ => 'ぶ',
+                    },
+                };
+                if matches!(small, Some('ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ')) {
+                    chars.next();
+                }
+                result.push(fallback);
+            }
+        }
+    }
+
+    result
+}
+
+/// 長音記号（ー）を直前のかなの母音に展開します（内部ヘルパー関数）。
+///
+/// 展開した母音は、直前のかなと同じ種類（ひらがな・カタカナ）で出力します。
+/// 規則は [`to_hiragana_with`] の [`ProlongedPolicy::ToVowel`] を参照してください。
+fn expand_prolonged_marks(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut last_vowel: Option<char> = None;
+
+    for c in text.chars() {
+        if c == 'ー' {
+            match last_vowel {
+                Some('お') => result.push('う'),
+                Some('オ') => result.push('ウ'),
+                Some(v) => result.push(v),
+                None => result.push(c),
+            }
+            continue;
+        }
+
+        last_vowel = hiragana_vowel(hiragana_char(c)).map(|v| {
+            if is_katakana(c) {
+                katakana_char(v)
+            } else {
+                v
+            }
+        });
+        result.push(c);
+    }
+
+    result
+}
+
+/// ひらがなの母音（段）を返します（内部ヘルパー関数）。
+fn hiragana_vowel(c: char) -> Option<char> {
+    match c {
+        'あ' | 'ぁ' | 'か' | 'が' | 'ゕ' | 'さ' | 'ざ' | 'た' | 'だ' | 'な' | 'は' | 'ば' | 'ぱ'
+        | 'ま' | 'や' | 'ゃ' | 'ら' | 'わ' | 'ゎ' => Some('あ'),
+        'い' | 'ぃ' | 'き' | 'ぎ' | 'し' | 'じ' | 'ち' | 'ぢ' | 'に' | 'ひ' | 'び' | 'ぴ' | 'み'
+        | 'り' | 'ゐ' => Some('い'),
+        'う' | 'ぅ' | 'く' | 'ぐ' | 'す' | 'ず' | 'つ' | 'づ' | 'ぬ' | 'ふ' | 'ぶ' | 'ぷ' | 'む'
+        | 'ゆ' | 'ゅ' | 'る' | 'ゔ' => Some('う'),
+        'え' | 'ぇ' | 'け' | 'げ' | 'ゖ' | 'せ' | 'ぜ' | 'て' | 'で' | 'ね' | 'へ' | 'べ' | 'ぺ'
+        | 'め' | 'れ' | 'ゑ' => Some('え'),
+        'お' | 'ぉ' | 'こ' | 'ご' | 'そ' | 'ぞ' | 'と' | 'ど' | 'の' | 'ほ' | 'ぼ' | 'ぽ' | 'も'
+        | 'よ' | 'ょ' | 'ろ' | 'を' => Some('お'),
+        _ => None,
+    }
+}
+
+/// ひらがなをカタカナに変換します。
+///
+/// この関数はひらがな文字（U+3041-U+3096）を、
+/// 対応するカタカナ文字（U+30A1-U+30F6）に変換します。
+/// 歴史的仮名（ゐ → ヰ、ゑ → ヱ、を → ヲ）も変換します。繰り返し記号（ゝ ゞ）は変換しません。
+/// 範囲の末尾の ゔ（U+3094）・ゕ（U+3095）・ゖ（U+3096）は、それぞれ ヴ（U+30F4）・ヵ（U+30F5）・ヶ（U+30F6）になります。
+///
+/// より（ゟ、U+309F）は合字で対応するカタカナが無いためそのまま残します（コト「ヿ」とは別の文字です）。
+/// 濁点・半濁点（゛゜、U+309B, U+309C）と結合用濁点・半濁点（U+3099, U+309A）はひらがなとカタカナで共通のため変換しません。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::to_katakana;
+///
+/// assert_eq!(to_katakana("ひらがな"), "ヒラガナ");
+/// assert_eq!(to_katakana("こんにちは"), "コンニチハ");
+/// assert_eq!(to_katakana("ゔぁいおりん"), "ヴァイオリン");
+/// assert_eq!(to_katakana("ゕゖゟ"), "ヵヶゟ");
+/// ```
+pub fn to_katakana(input: &str) -> String {
+    // ひらがな（U+3041-U+3096）はすべて 0xE3 で始まる3バイト文字
+    map_chars_with_lead_byte(input, |b| b == 0xE3, katakana_char)
+}
+
+/// ひらがな1文字をカタカナに変換します。
+///
+/// [`to_katakana`] が使う1文字単位の変換です。対応するカタカナが無い文字はそのまま返します。
+/// 1文字だけ変換したい場合に、`String` を経由せずに使えます。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::katakana_char;
+///
+/// assert_eq!(katakana_char('あ'), 'ア');
+/// assert_eq!(katakana_char('ゑ'), 'ヱ');
+/// assert_eq!(katakana_char('ゟ'), 'ゟ');
+/// assert_eq!(katakana_char('A'), 'A');
+/// ```
+pub fn katakana_char(c: char) -> char {
+    match c {
+        // Hiragana (U+3041-U+3096) to Katakana (U+30A1-U+30F6)
+        '\u{3041}'..='\u{3096}' => char::from_u32(c as u32 - 0x3041 + 0x30A1).unwrap_or(c),
+        // Keep other characters as-is
+        _ => c,
+    }
+}
+
+/// 文字がひらがなかどうかを判定します。
+///
+/// 音節文字（U+3041-U+3096）のみを対象とし、[`is_hiragana_strict`] と同じ結果を返します。
+/// 繰り返し記号（ゝゞ）やより（ゟ）もひらがなとして扱う場合は [`is_hiragana_block`] を使用してください。
+/// [`classify`] と [`count_character_types`] もこの判定に基づいて集計します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::is_hiragana;
+///
+/// assert_eq!(is_hiragana('あ'), true);
+/// assert_eq!(is_hiragana('ア'), false);
+/// assert_eq!(is_hiragana('A'), false);
+/// ```
+pub fn is_hiragana(c: char) -> bool {
+    is_hiragana_strict(c)
+}
+
+/// 文字がひらがなの音節文字（U+3041-U+3096）かどうかを判定します。
+///
+/// 小書きの仮名（ぁ ゃ ゎ ゕ ゖ など）と ゔ を含み、結合用濁点・半濁点、濁点・半濁点（゛゜）、
+/// 繰り返し記号（ゝゞ）、より（ゟ）は含みません。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::is_hiragana_strict;
+///
+/// assert!(is_hiragana_strict('あ'));
+/// assert!(is_hiragana_strict('ゖ'));
+/// assert!(!is_hiragana_strict('ゝ'));
+/// assert!(!is_hiragana_strict('ゟ'));
+/// ```
+pub fn is_hiragana_strict(c: char) -> bool {
+    tables::HIRAGANA_RANGE.contains(&c)
+}
+
+/// 文字が Unicode のひらがなブロック（U+3040-U+309F）に含まれるかどうかを判定します。
+///
+/// [`is_hiragana_strict`] の対象に加えて、結合用濁点・半濁点（U+3099, U+309A）、
+/// 濁点・半濁点（゛゜）、繰り返し記号（ゝゞ）、より（ゟ）も対象とします。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::is_hiragana_block;
+///
+/// assert!(is_hiragana_block('あ'));
+/// assert!(is_hiragana_block('ゝ'));
+/// assert!(is_hiragana_block('ゟ'));
+/// assert!(!is_hiragana_block('ア'));
+/// ```
+pub fn is_hiragana_block(c: char) -> bool {
+    tables::HIRAGANA_BLOCK_RANGE.contains(&c)
+}
+
+/// 文字がカタカナかどうかを判定します。
+///
+/// 対象は U+30A1-U+30F6 です。アイヌ語表記用の小書きカタカナ（U+31F0-U+31FF）は含まないため、
+/// 必要に応じて [`is_katakana_phonetic_ext`] と組み合わせてください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::is_katakana;
+///
+/// assert_eq!(is_katakana('ア'), true);
+/// assert_eq!(is_katakana('あ'), false);
+/// assert_eq!(is_katakana('A'), false);
+/// ```
+pub fn is_katakana(c: char) -> bool {
+    tables::KATAKANA_RANGE.contains(&c)
+}
+
+/// 文字がカタカナ拡張ブロック（U+31F0-U+31FF）の小書きカタカナかどうかを判定します。
+///
+/// アイヌ語の表記に使う ㇰ ㇱ ㇲ ㇳ ㇴ ㇵ ㇶ ㇷ ㇸ ㇹ ㇺ ㇻ ㇼ ㇽ ㇾ ㇿ が対象です。
+/// これらは [`is_katakana`] では `false` になり、[`classify`] では [`CharClass::Other`] に分類されます。
+/// 対応するひらがなが無いため、[`to_hiragana`] はこれらの文字を変換せずにそのまま残します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{is_katakana, is_katakana_phonetic_ext};
+///
+/// assert!(is_katakana_phonetic_ext('ㇰ'));
+/// assert!(!is_katakana('ㇰ'));
+/// assert!(!is_katakana_phonetic_ext('ク'));
+/// ```
+pub fn is_katakana_phonetic_ext(c: char) -> bool {
+    tables::KATAKANA_PHONETIC_EXTENSIONS_RANGE.contains(&c)
+}
+
+/// 文字が半角カタカナかどうかを判定します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::is_half_width_katakana;
+///
+/// assert_eq!(is_half_width_katakana('ｱ'), true);
+/// assert_eq!(is_half_width_katakana('ア'), false);
+/// assert_eq!(is_half_width_katakana('A'), false);
+/// ```
+pub fn is_half_width_katakana(c: char) -> bool {
+    tables::HALF_WIDTH_KATAKANA_RANGE.contains(&c)
+}
+
+/// 文字が歴史的仮名・変体仮名かどうかを判定します。
+///
+/// 以下の文字を対象とします。
+///
+/// - 歴史的仮名: ゐ ゑ を ヰ ヱ ヲ、小書きの ゎ ヮ
+/// - 繰り返し記号: ゝ ゞ ヽ ヾ
+/// - 濁点付きのワ行カタカナ: ヷ ヸ ヹ ヺ
+/// - 仮名補助・仮名拡張A・小仮名拡張ブロック（U+1B000-U+1B16F、変体仮名を含む）
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::is_archaic_kana;
+///
+/// assert!(is_archaic_kana('ゐ'));
+/// assert!(is_archaic_kana('ヱ'));
+/// assert!(is_archaic_kana('を'));
+/// assert!(is_archaic_kana('\u{1B002}'));
+/// assert!(!is_archaic_kana('い'));
+/// ```
+pub fn is_archaic_kana(c: char) -> bool {
+    matches!(
+        c,
+        'ゐ' | 'ゑ' | 'を' | 'ゎ' | 'ゝ' | 'ゞ' | 'ヰ' | 'ヱ' | 'ヲ' | 'ヮ' | 'ヽ' | 'ヾ'
+            | '\u{30F7}'..='\u{30FA}'
+            | '\u{1B000}'..='\u{1B16F}'
+    )
+}
+
+/// 文字が漢字かどうかを判定します。
+///
+/// CJK統合漢字とその拡張、CJK互換漢字を対象とします。範囲は [`tables::KANJI_RANGES`] を参照してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::is_kanji;
+///
+/// assert_eq!(is_kanji('漢'), true);
+/// assert_eq!(is_kanji('字'), true);
+/// assert_eq!(is_kanji('あ'), false);
+/// assert_eq!(is_kanji('A'), false);
+/// ```
+pub fn is_kanji(c: char) -> bool {
+    tables::KANJI_RANGES.iter().any(|range| range.contains(&c))
+}
+
+/// 文字が全角文字かどうかを判定します。
+///
+/// ここでの「全角文字」は全角英数字・記号（U+FF01-U+FF5E）と全角スペース（U+3000）のみを指します。
+/// 全角カタカナや漢字のように表示幅が2になる文字を判定する場合は [`is_wide`] を使用してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::is_full_width;
+///
+/// assert_eq!(is_full_width('Ａ'), true);
+/// assert_eq!(is_full_width('１'), true);
+/// assert_eq!(is_full_width('A'), false);
+/// ```
+pub fn is_full_width(c: char) -> bool {
+    tables::FULL_WIDTH_ASCII_RANGE.contains(&c) || c == '　'
+}
+
+/// 文字の表示幅が2かどうか（East Asian Width の Wide または Fullwidth か）を判定します。
+///
+/// [`is_full_width`] と異なり、全角カタカナ・ひらがな・漢字・全角スペースなども対象になります。
+/// 半角カタカナ（U+FF61-U+FF9F）は表示幅1のため対象外です。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::is_wide;
+///
+/// assert_eq!(is_wide('漢'), true);
+/// assert_eq!(is_wide('カ'), true);
+/// assert_eq!(is_wide('Ａ'), true);
+/// assert_eq!(is_wide('ｶ'), false);
+/// assert_eq!(is_wide('A'), false);
+/// ```
+pub fn is_wide(c: char) -> bool {
+    tables::WIDE_RANGES.iter().any(|range| range.contains(&c))
+}
+
+/// 文字列がひらがなのみで構成されているかを判定します。
+///
+/// 各文字を [`is_hiragana`] で判定します。**空文字列は `false`** を返すため、
+/// フォーム入力のチェックでは未入力も不正として扱われます。
+/// 長音符（ー）や中黒（・）を許容する場合は [`is_all_hiragana_lenient`] を使用してください。
+///
+/// `is_all_*` の判定は、全角英数字を変換するだけの [`to_half_width`] の前後で結果が変わりません。
+/// 一方で半角カタカナはカタカナとみなさないため、半角カタカナの入力を許容する場合は
+/// 先に [`half_width_katakana_to_full_width`] で全角にそろえてから判定してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::is_all_hiragana;
+///
+/// assert!(is_all_hiragana("ひらがな"));
+/// assert!(!is_all_hiragana("ひらがなと漢字"));
+/// assert!(!is_all_hiragana(""));
+/// ```
+pub fn is_all_hiragana(input: &str) -> bool {
+    is_all(input, false, is_hiragana)
+}
+
+/// 文字列がひらがなのみで構成されているかを、長音符（ー）と中黒（・）を許容して判定します。
+///
+/// 長音符と中黒だけの文字列や空文字列は `false` です（ひらがなを1文字以上含む必要があります）。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::is_all_hiragana_lenient;
+///
+/// assert!(is_all_hiragana_lenient("らーめん"));
+/// assert!(!is_all_hiragana_lenient("ー"));
+/// ```
+pub fn is_all_hiragana_lenient(input: &str) -> bool {
+    is_all(input, true, is_hiragana)
+}
+
+/// 文字列が全角カタカナのみで構成されているかを判定します。
+///
+/// 各文字を [`is_katakana`] で判定します。空文字列は `false` を返します。
+/// 長音符（ー）を含む一般的なカタカナ語は [`is_all_katakana_lenient`] で判定してください。
+/// 半角カタカナは対象外です（[`is_all_hiragana`] の注意を参照）。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::is_all_katakana;
+///
+/// assert!(is_all_katakana("カタカナ"));
+/// assert!(!is_all_katakana("ｶﾀｶﾅ"));
+/// assert!(!is_all_katakana("コーヒー"));
+/// ```
+pub fn is_all_katakana(input: &str) -> bool {
+    is_all(input, false, is_katakana)
+}
+
+/// 文字列が全角カタカナのみで構成されているかを、長音符（ー）と中黒（・）を許容して判定します。
+///
+/// 長音符と中黒だけの文字列や空文字列は `false` です。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::is_all_katakana_lenient;
+///
+/// assert!(is_all_katakana_lenient("ジョン・スミス"));
+/// assert!(is_all_katakana_lenient("コーヒー"));
+/// ```
+pub fn is_all_katakana_lenient(input: &str) -> bool {
+    is_all(input, true, is_katakana)
+}
+
+/// 文字列がひらがなと全角カタカナのみで構成されているかを判定します。
+///
+/// 空文字列は `false` を返します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::is_all_kana;
+///
+/// assert!(is_all_kana("ひらがなとカタカナ"));
+/// assert!(!is_all_kana("かな漢字"));
+/// ```
+pub fn is_all_kana(input: &str) -> bool {
+    is_all(input, false, is_kana)
+}
+
+/// 文字列がひらがなと全角カタカナのみで構成されているかを、長音符（ー）と中黒（・）を許容して判定します。
+///
+/// 長音符と中黒だけの文字列や空文字列は `false` です。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::is_all_kana_lenient;
+///
+/// assert!(is_all_kana_lenient("すーぱー・マーケット"));
+/// ```
+pub fn is_all_kana_lenient(input: &str) -> bool {
+    is_all(input, true, is_kana)
+}
+
+/// 文字列が漢字のみで構成されているかを判定します。
+///
+/// 各文字を [`is_kanji`] で判定します。空文字列は `false` を返します。
+/// 踊り字（々）は漢字とみなさないため、`佐々木` は `false` になります。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::is_all_kanji;
+///
+/// assert!(is_all_kanji("漢字"));
+/// assert!(!is_all_kanji("漢字かな"));
+/// ```
+pub fn is_all_kanji(input: &str) -> bool {
+    is_all(input, false, is_kanji)
+}
+
+/// 文字列に半角カタカナが含まれているかを判定します。
+///
+/// 最初の半角カタカナを見つけた時点で `true` を返すため、すべての文字を数える
+/// [`count_character_types`] よりも軽量です。フォーム入力のバリデーションなどに使います。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::contains_half_width_katakana;
+///
+/// assert!(contains_half_width_katakana("ﾔﾏﾀﾞ太郎"));
+/// assert!(!contains_half_width_katakana("ヤマダ太郎"));
+/// ```
+pub fn contains_half_width_katakana(input: &str) -> bool {
+    input.chars().any(is_half_width_katakana)
+}
+
+/// 文字列に全角英数字・記号（U+FF01-U+FF5E）が含まれているかを判定します。
+///
+/// 全角スペース（U+3000）は対象外です。最初の1文字を見つけた時点で `true` を返します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::contains_full_width_ascii;
+///
+/// assert!(contains_full_width_ascii("電話番号：０３"));
+/// assert!(!contains_full_width_ascii("電話番号 03　"));
+/// ```
+pub fn contains_full_width_ascii(input: &str) -> bool {
+    input.chars().any(|c| tables::FULL_WIDTH_ASCII_RANGE.contains(&c))
+}
+
+/// 文字列に漢字が含まれているかを判定します。
+///
+/// 漢字の範囲は [`is_kanji`] と同じです。最初の1文字を見つけた時点で `true` を返します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::contains_kanji;
+///
+/// assert!(contains_kanji("やまだ太郎"));
+/// assert!(!contains_kanji("やまだたろう"));
+/// ```
+pub fn contains_kanji(input: &str) -> bool {
+    input.chars().any(is_kanji)
+}
+
+/// かなの清音・濁音・半濁音の区別です。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Voicing {
+    /// 清音（濁点・半濁点の付かないかな。例: か、あ、ん）
+    Plain,
+    /// 濁音（例: が、ゔ、ヷ）
+    Voiced,
+    /// 半濁音（例: ぱ）
+    SemiVoiced,
+}
+
+/// かな1文字の清音・濁音・半濁音を判定します。
+///
+/// 濁音・半濁音の対応は [`tables::VOICING_HIRAGANA`] と [`tables::VOICING_KATAKANA`] に従います。
+/// それ以外のひらがな・全角カタカナは、濁点を付けられない文字（「あ」「ん」など）も含めて
+/// [`Voicing::Plain`] です。かな以外の文字（半角カタカナ、長音記号、繰り返し記号を含む）は `None` を返します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{kana_voicing, Voicing};
+///
+/// assert_eq!(kana_voicing('か'), Some(Voicing::Plain));
+/// assert_eq!(kana_voicing('ガ'), Some(Voicing::Voiced));
+/// assert_eq!(kana_voicing('ぱ'), Some(Voicing::SemiVoiced));
+/// assert_eq!(kana_voicing('漢'), None);
+/// ```
+pub fn kana_voicing(c: char) -> Option<Voicing> {
+    match voicing_entry(c) {
+        Some((_, voiced, _)) if voiced == c => Some(Voicing::Voiced),
+        Some((_, _, semi_voiced)) if semi_voiced == Some(c) => Some(Voicing::SemiVoiced),
+        _ if is_kana(c) => Some(Voicing::Plain),
+        _ => None,
+    }
+}
+
+/// かな1文字の濁点を付け外しします。
+///
+/// 清音には濁点を付け、濁音は清音に戻します。半濁音は濁音に置き換えます（「ぱ」→「ば」）。
+/// 濁音を持たない文字やかな以外の文字はそのまま返します。
+/// IME のように、入力済みの文字に濁点キーを押したときの処理に使います。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::toggle_dakuten;
+///
+/// assert_eq!(toggle_dakuten('か'), 'が');
+/// assert_eq!(toggle_dakuten('が'), 'か');
+/// assert_eq!(toggle_dakuten('ぱ'), 'ば');
+/// assert_eq!(toggle_dakuten('あ'), 'あ');
+/// ```
+pub fn toggle_dakuten(c: char) -> char {
+    match voicing_entry(c) {
+        Some((base, voiced, _)) if voiced == c => base,
+        Some((_, voiced, _)) => voiced,
+        None => c,
+    }
+}
+
+/// ひらがなまたは全角カタカナかどうかを判定します（内部ヘルパー関数）。
+fn is_kana(c: char) -> bool {
+    is_hiragana(c) || is_katakana(c)
+}
+
+/// 文字列のすべての文字が条件を満たすかを判定します（内部ヘルパー関数）。
+///
+/// `lenient` が `true` なら長音符（ー）と中黒（・）も許容しますが、
+/// 条件を満たす文字が1文字以上必要です。空文字列は `false` です。
+fn is_all(input: &str, lenient: bool, predicate: impl Fn(char) -> bool) -> bool {
+    let mut matched = false;
+    for c in input.chars() {
+        if predicate(c) {
+            matched = true;
+        } else if !(lenient && matches!(c, 'ー' | '・')) {
+            return false;
+        }
+    }
+    matched
+}
+
+/// 文字の種別を表す列挙型です。
+///
+/// [`classify`] の戻り値として使われます。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CharClass {
+    /// ひらがな（U+3041-U+3096）
+    Hiragana,
+    /// カタカナ（U+30A1-U+30F6）
+    Katakana,
+    /// 半角カタカナ（U+FF61-U+FF9F）
+    HalfWidthKatakana,
+    /// 漢字（[`tables::KANJI_RANGES`] の範囲）
+    Kanji,
+    /// ASCII文字（U+0000-U+007F）
+    Ascii,
+    /// 全角ASCII文字と全角スペース（U+FF01-U+FF5E, U+3000）
+    FullWidth,
+    /// 和文の約物（、。〃〈〉《》「」『』【】〔〕〖〗〘〙〚〛〜〝〞〟・）
+    Punctuation,
+    /// 上記以外の文字
+    Other,
+}
+
+/// 文字の種別を判定します。
+///
+/// 判定は `Hiragana`、`Katakana`、`HalfWidthKatakana`、`Kanji`、`Ascii`、
+/// `FullWidth`、`Punctuation` の順に行い、いずれにも当てはまらない文字は `Other` になります。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{classify, CharClass};
+///
+/// assert_eq!(classify('あ'), CharClass::Hiragana);
+/// assert_eq!(classify('ア'), CharClass::Katakana);
+/// assert_eq!(classify('漢'), CharClass::Kanji);
+/// assert_eq!(classify('。'), CharClass::Punctuation);
+/// assert_eq!(classify('A'), CharClass::Ascii);
+/// ```
+pub fn classify(c: char) -> CharClass {
+    if is_hiragana(c) {
+        CharClass::Hiragana
+    } else if is_katakana(c) {
+        CharClass::Katakana
+    } else if is_half_width_katakana(c) {
+        CharClass::HalfWidthKatakana
+    } else if is_kanji(c) {
+        CharClass::Kanji
+    } else if c.is_ascii() {
+        CharClass::Ascii
+    } else if is_full_width(c) {
+        CharClass::FullWidth
+    } else if is_japanese_punctuation(c) {
+        CharClass::Punctuation
+    } else {
+        CharClass::Other
+    }
+}
+
+/// 和文の約物かどうかを判定します（内部ヘルパー関数）。
+fn is_japanese_punctuation(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3001}'..='\u{3003}' | '\u{3008}'..='\u{3011}' | '\u{3014}'..='\u{301F}' | '\u{30FB}'
+    )
+}
+
+/// 連続する同じ種別の文字のまとまり（ラン）に文字列を分割します。
+///
+/// 各ランは [`classify`] による種別と、元の文字列を借用したスライスの組で返されます。
+/// カタカナの直後にある長音記号（ー）はカタカナのランに含めます。
+/// この挙動を変更したり、中黒（・）もカタカナのランに含めたりする場合は
+/// [`segment_by_script_with`] を使用してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{segment_by_script, CharClass};
+///
+/// assert_eq!(
+///     segment_by_script("東京タワー123"),
+///     vec![
+///         (CharClass::Kanji, "東京"),
+///         (CharClass::Katakana, "タワー"),
+///         (CharClass::Ascii, "123"),
+///     ]
+/// );
+/// ```
+pub fn segment_by_script(input: &str) -> Vec<(CharClass, &str)> {
+    segment_by_script_with(input, SegmentOptions::default())
+}
+
+/// [`segment_by_script_with`] の動作を指定するオプションです。
+///
+/// デフォルトは [`segment_by_script`] と同じく、長音記号をカタカナのランに含め、中黒は含めません。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SegmentOptions {
+    /// `true` の場合、カタカナの直後の長音記号（ー）をカタカナのランに含めます。
+    pub prolonged_sound_in_katakana: bool,
+    /// `true` の場合、カタカナに挟まれた中黒（・）をカタカナのランに含めます。
+    pub middle_dot_in_katakana: bool,
+}
+
+impl Default for SegmentOptions {
+    fn default() -> Self {
+        Self {
+            prolonged_sound_in_katakana: true,
+            middle_dot_in_katakana: false,
+        }
+    }
+}
+
+/// オプションを指定して、文字列を同じ種別の文字のランに分割します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{segment_by_script_with, CharClass, SegmentOptions};
+///
+/// let opts = SegmentOptions {
+///     prolonged_sound_in_katakana: true,
+///     middle_dot_in_katakana: true,
+/// };
+/// assert_eq!(
+///     segment_by_script_with("アイス・コーヒーを", opts),
+///     vec![
+///         (CharClass::Katakana, "アイス・コーヒー"),
+///         (CharClass::Hiragana, "を"),
+///     ]
+/// );
+/// ```
+pub fn segment_by_script_with(input: &str, opts: SegmentOptions) -> Vec<(CharClass, &str)> {
+    let mut segments = Vec::new();
+    let mut rest = input;
+
+    while let Some(first) = rest.chars().next() {
+        let class = classify(first);
+        let end = if class == CharClass::Katakana {
+            let joins_katakana = |c: char| {
+                is_katakana(c)
+                    || (opts.prolonged_sound_in_katakana && c == 'ー')
+                    || (opts.middle_dot_in_katakana && c == '・')
+            };
+            let (run, _) = match_run(rest, joins_katakana).unwrap_or((rest, ""));
+            // 直後にカタカナが続かない中黒でランを区切る
+            run.char_indices()
+                .find(|&(i, c)| c == '・' && !run[i + c.len_utf8()..].starts_with(is_katakana))
+                .map_or(run.len(), |(i, _)| i)
+        } else {
+            match_run(rest, |c| classify(c) == class).map_or(rest.len(), |(run, _)| run.len())
+        };
+
+        let (run, remaining) = rest.split_at(end);
+        segments.push((class, run));
+        rest = remaining;
+    }
+
+    segments
+}
+
+/// 先頭から条件を満たす文字が続く部分（ラン）と、残りの部分に分割します。
+///
+/// 先頭の文字が条件を満たさない場合や、空文字列の場合は `None` を返します。
+/// 返り値はどちらも元の文字列を借用したスライスで、メモリ確保は行いません。
+/// [`is_hiragana`] などの判定関数を渡すと、正規表現を使わずに文字種のまとまりを切り出せます。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{is_hiragana, is_kanji, match_run};
+///
+/// assert_eq!(match_run("ひらがな漢字", is_hiragana), Some(("ひらがな", "漢字")));
+/// assert_eq!(match_run("ひらがな漢字", is_kanji), None);
+///
+/// // 残りの部分に繰り返し適用して、漢字のまとまりをすべて取り出す
+/// let mut rest = "東京と大阪";
+/// let mut words = Vec::new();
+/// while let Some(c) = rest.chars().next() {
+///     match match_run(rest, is_kanji) {
+///         Some((word, remaining)) => {
+///             words.push(word);
+///             rest = remaining;
+///         }
+///         None => rest = &rest[c.len_utf8()..],
+///     }
+/// }
+/// assert_eq!(words, vec!["東京", "大阪"]);
+/// ```
+pub fn match_run(input: &str, pred: impl Fn(char) -> bool) -> Option<(&str, &str)> {
+    let end = input.find(|c: char| !pred(c)).unwrap_or(input.len());
+    if end == 0 {
+        None
+    } else {
+        Some(input.split_at(end))
+    }
+}
+
+/// 先頭と末尾から、指定した種別の文字を取り除きます。
+///
+/// 種別の判定には [`classify`] を使います。`str::trim_matches` と同様に元の文字列のスライスを返すため、
+/// 新たなメモリ確保は行いません。
+/// 全角の感嘆符（！）や全角スペースは [`CharClass::FullWidth`] に分類される点に注意してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{trim_script, CharClass};
+///
+/// assert_eq!(trim_script("！！！重要！！！", CharClass::FullWidth), "重要");
+/// assert_eq!(trim_script("「お知らせ」", CharClass::Punctuation), "お知らせ");
+/// ```
+pub fn trim_script(input: &str, class: CharClass) -> &str {
+    input.trim_matches(|c| classify(c) == class)
+}
+
+/// 先頭から、指定した種別の文字を取り除きます。
+///
+/// [`trim_script`] の先頭のみ版です。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{trim_start_script, CharClass};
+///
+/// assert_eq!(trim_start_script("　　本文　", CharClass::FullWidth), "本文　");
+/// ```
+pub fn trim_start_script(input: &str, class: CharClass) -> &str {
+    input.trim_start_matches(|c| classify(c) == class)
+}
+
+/// 末尾から、指定した種別の文字を取り除きます。
+///
+/// [`trim_script`] の末尾のみ版です。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{trim_end_script, CharClass};
+///
+/// assert_eq!(trim_end_script("東京都。", CharClass::Punctuation), "東京都");
+/// ```
+pub fn trim_end_script(input: &str, class: CharClass) -> &str {
+    input.trim_end_matches(|c| classify(c) == class)
+}
+
+/// 指定した種別（[`classify`] の結果）の文字をマスク文字に置き換えます。
+///
+/// 1文字を1つのマスク文字に置き換えるため、文字数（`chars().count()`）は変わりません。
+/// マスク文字に日本語などのマルチバイト文字を指定した場合は、バイト長が変わることがあります。
+/// 半角・全角の両方を対象にする場合は `CharClass::Ascii` と `CharClass::FullWidth` を並べて指定します。
+/// 数字だけをマスクする場合は [`mask_digits`] を使用してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{mask_by_type, CharClass};
+///
+/// assert_eq!(mask_by_type("山田Taro", &[CharClass::Ascii], '*'), "山田****");
+/// assert_eq!(
+///     mask_by_type("山田ＴａｒｏとJiro", &[CharClass::Ascii, CharClass::FullWidth], '●'),
+///     "山田●●●●と●●●●"
+/// );
+/// ```
+pub fn mask_by_type(input: &str, types: &[CharClass], mask: char) -> String {
+    input
+        .chars()
+        .map(|c| if types.contains(&classify(c)) { mask } else { c })
+        .collect()
+}
+
+/// 半角・全角の算用数字（0-9、０-９）をマスク文字に置き換えます。
+///
+/// 電話番号や会員番号を含むログの匿名化などに使います。
+/// [`mask_by_type`] と同じく、1文字を1つのマスク文字に置き換えます。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::mask_digits;
+///
+/// assert_eq!(mask_digits("電話: 03-1234-５６７８", '*'), "電話: **-****-****");
+/// ```
+pub fn mask_digits(input: &str, mask: char) -> String {
+    input
+        .chars()
+        .map(|c| if is_arabic_digit(c) { mask } else { c })
+        .collect()
+}
+
+/// ゼロ幅接合子（ZWJ）
+const ZERO_WIDTH_JOINER: char = '\u{200D}';
+
+/// 文字が絵文字（または絵文字の構成要素）かどうかを判定します。
+///
+/// 判定に使う範囲は以下のとおりです。
+///
+/// - U+2600-U+26FF、U+2700-U+27BF（記号・装飾記号）
+/// - U+1F300-U+1F64F、U+1F680-U+1F6FF、U+1F900-U+1F9FF、U+1FA70-U+1FAFF（絵文字）
+/// - U+1F1E6-U+1F1FF（地域指示記号）
+/// - U+FE0E-U+FE0F（異体字セレクタ）、U+20E3（囲みキーキャップ）、U+E0020-U+E007F（タグ文字）
+///
+/// ゼロ幅接合子（U+200D）単体は絵文字とみなしません。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::is_emoji;
+///
+/// assert!(is_emoji('😀'));
+/// assert!(is_emoji('☀'));
+/// assert!(!is_emoji('あ'));
+/// ```
+pub fn is_emoji(c: char) -> bool {
+    tables::EMOJI_RANGES.iter().any(|range| range.contains(&c))
+}
+
+/// 文字列から絵文字を取り除きます。
+///
+/// ゼロ幅接合子（U+200D）で結合された複合絵文字（👨‍👩‍👧 など）は1つの単位として、
+/// 接合子を含めてまとめて取り除きます。絵文字に挟まれていない接合子はそのまま残します。
+/// 判定に使う範囲は [`is_emoji`] を参照してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::strip_emoji;
+///
+/// assert_eq!(strip_emoji("こんにちは😀"), "こんにちは");
+/// assert_eq!(strip_emoji("家族👨\u{200D}👩\u{200D}👧です"), "家族です");
+/// ```
+pub fn strip_emoji(input: &str) -> String {
+    non_emoji_char_indices(input)
+        .into_iter()
+        .map(|(_, c)| c)
+        .collect()
+}
+
+/// 絵文字として取り除かれない文字を、バイトオフセットとともに返します（内部ヘルパー関数）。
+fn non_emoji_char_indices(input: &str) -> Vec<(usize, char)> {
+    let chars: Vec<(usize, char)> = input.char_indices().collect();
+    let mut result = Vec::with_capacity(chars.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        if !is_emoji(chars[i].1) {
+            result.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        // 絵文字に続く構成要素と、ZWJ で結合された後続の絵文字を読み飛ばす
+        i += 1;
+        while i < chars.len() {
+            if is_emoji(chars[i].1) {
+                i += 1;
+            } else if chars[i].1 == ZERO_WIDTH_JOINER
+                && chars.get(i + 1).is_some_and(|&(_, c)| is_emoji(c))
+            {
+                i += 2;
+            } else {
+                break;
+            }
+        }
+    }
+
+    result
+}
+
+/// 文字が異体字セレクタかどうかを判定します。
+///
+/// 異体字セレクタ（U+FE00-U+FE0F）と異体字セレクタ補助（U+E0100-U+E01EF）を対象とします。
+/// 範囲は [`tables::VARIATION_SELECTOR_RANGES`] を参照してください。
+/// これらは [`classify`] では [`CharClass::Other`] に分類されるため、
+/// [`count_character_types`] では `other` として数えられます。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::is_variation_selector;
+///
+/// assert!(is_variation_selector('\u{E0100}'));
+/// assert!(is_variation_selector('\u{FE0F}'));
+/// assert!(!is_variation_selector('邊'));
+/// ```
+pub fn is_variation_selector(c: char) -> bool {
+    tables::VARIATION_SELECTOR_RANGES
+        .iter()
+        .any(|range| range.contains(&c))
+}
+
+/// 文字列から異体字セレクタを取り除きます。
+///
+/// `渡邊󠄊`（`邊` + U+E010A）のような異体字シーケンスを基底の文字だけにして、
+/// 異体字の指定が異なるデータ同士を照合できるようにします。対象は [`is_variation_selector`] を参照してください。
+///
+/// 異体字セレクタを取り除くと、字形の区別（戸籍・登記で使い分けられる異体字など）は失われます。
+/// また、絵文字の表示（U+FE0F）とテキストの表示（U+FE0E）の指定も失われるため、
+/// 表示用の文字列には使わず、照合用のキーを作る場合に使用してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::strip_variation_selectors;
+///
+/// assert_eq!(strip_variation_selectors("渡邊\u{E010A}"), "渡邊");
+/// assert_eq!(strip_variation_selectors("葛\u{E0100}飾"), "葛飾");
+/// ```
+pub fn strip_variation_selectors(input: &str) -> String {
+    let mut result = String::from(input);
+    result.retain(|c| !is_variation_selector(c));
+    result
+}
+
+/// 文字列に含まれる書記素クラスタの数を数えます。
+///
+/// `chars().count()` と異なり、結合文字や異体字セレクタ付きの漢字（`葛󠄀` など）、
+/// ZWJ で結合された絵文字、国旗（地域指示記号の組）を1文字として数えます。
+///
+/// `grapheme` feature を有効にすると、[`unicode-segmentation`](https://docs.rs/unicode-segmentation) による
+/// Unicode 標準附属書 #29 の拡張書記素クラスタで数えます。
+///
+/// feature が無効な場合（デフォルト）は、外部クレートに依存しない**近似**の規則で数えます。
+/// 結合する文字は [`tables::GRAPHEME_EXTEND_RANGES`] の範囲で、ZWJ は絵文字どうしの間でだけ結合し、
+/// ハングルの字母（L・V・T）は音節として結合します。スペーシングマーク（デーヴァナーガリーの母音記号など）と
+/// 前置文字には対応していないため、これらを含む文字列では feature 有効時と結果が異なることがあります。
+/// 日本語のテキスト（結合用濁点、異体字セレクタ、絵文字）では同じ結果になります。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::count_graphemes;
+///
+/// assert_eq!(count_graphemes("葛\u{E0100}飾"), 2);
+/// assert_eq!(count_graphemes("か\u{3099}"), 1);
+/// assert_eq!(count_graphemes("👨‍👩‍👧"), 1);
+/// ```
+pub fn count_graphemes(input: &str) -> usize {
+    Graphemes::new(input).count()
+}
+
+/// 見た目の文字数を数えます。
+///
+/// 半角・全角のカタカナが混在していても同じ数え方になるように、次の規則で数えます。
+///
+/// - 半角カタカナと、結合できる半角の濁点・半濁点の組（`ｶﾞ`、`ﾊﾟ`）は1文字です。`ガ`、`カ\u{3099}` と同じです。
+/// - 結合できない半角の濁点・半濁点（`ｱﾞ` の `ﾞ`）は、全角の濁点（゛）・半濁点（゜）と同じく独立した1文字です。
+/// - 結合文字（結合用濁点 U+3099 など）と異体字セレクタは、直前の文字に含めて数えます。
+/// - ZWJ で結合された絵文字と国旗は1文字です（[`count_graphemes`] と同じ）。
+///
+/// つまり、[`half_width_katakana_to_full_width`] で全角にそろえてから [`count_graphemes`] で数えた値と同じで、
+/// 変換の前後で結果は変わりません。表示幅（全角を2とする）が必要な場合は [`count_width_weighted`] を使用してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::visual_length;
+///
+/// assert_eq!(visual_length("ｶﾞｷﾞ"), 2);
+/// assert_eq!(visual_length("ガギ"), 2);
+/// assert_eq!(visual_length("カ\u{3099}ｷﾞ"), 2);
+/// assert_eq!(visual_length("ｱﾞ"), 2);
+/// ```
+pub fn visual_length(input: &str) -> usize {
+    count_graphemes(&half_width_katakana_to_full_width(input))
+}
+
+/// [`count_width_weighted_with`] の動作を指定するオプションです。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WidthCountOptions {
+    /// 半角カタカナ（U+FF61-U+FF9F）を全角と同じく2と数える場合は `true`。
+    /// 既定では半角カタカナは1と数えます。
+    pub half_width_katakana_as_full: bool,
+}
+
+/// 「全角は2、半角は1」の規則で文字数を数えます。
+///
+/// 携帯電話の文字数制限などで使われてきた、Shift_JIS の1バイト文字を1、2バイト文字を2とする数え方です。
+/// ASCII（改行などの制御文字を含む）と半角カタカナを1、それ以外の文字（かな・漢字・全角英数字・記号・絵文字など）を2とします。
+/// 表示上の幅ではなく、文字数制限のための数え方です。結合文字や異体字セレクタも1文字として2と数えるため、
+/// 必要に応じて [`strip_variation_selectors`] や [`normalize_voiced_marks`] で正規化してから数えてください。
+///
+/// 半角カタカナを2と数える場合は [`count_width_weighted_with`] を使用してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::count_width_weighted;
+///
+/// assert_eq!(count_width_weighted("ABC"), 3);
+/// assert_eq!(count_width_weighted("あいう"), 6);
+/// assert_eq!(count_width_weighted("ＡＢ漢ｶﾅ"), 8);
+/// ```
+pub fn count_width_weighted(input: &str) -> usize {
+    count_width_weighted_with(input, WidthCountOptions::default())
+}
+
+/// オプションを指定して、「全角は2、半角は1」の規則で文字数を数えます。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{count_width_weighted_with, WidthCountOptions};
+///
+/// let opts = WidthCountOptions { half_width_katakana_as_full: true };
+/// assert_eq!(count_width_weighted_with("ｶﾅ", opts), 4);
+/// ```
+pub fn count_width_weighted_with(input: &str, opts: WidthCountOptions) -> usize {
+    input
+        .chars()
+        .map(|c| {
+            if c.is_ascii() || (is_half_width_katakana(c) && !opts.half_width_katakana_as_full) {
+                1
+            } else {
+                2
+            }
+        })
+        .sum()
+}
+
+/// 文字列を書記素クラスタ単位で走査するイテレータです（内部ヘルパー）。
+///
+/// 区切りの規則は [`count_graphemes`] を参照してください。
+#[cfg(feature = "grapheme")]
+struct Graphemes<'a> {
+    inner: unicode_segmentation::Graphemes<'a>,
+}
+
+#[cfg(feature = "grapheme")]
+impl<'a> Graphemes<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            inner: unicode_segmentation::UnicodeSegmentation::graphemes(input, true),
+        }
+    }
+}
+
+#[cfg(feature = "grapheme")]
+impl<'a> Iterator for Graphemes<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        self.inner.next()
+    }
+}
+
+/// 文字列を書記素クラスタ単位で走査するイテレータです（内部ヘルパー）。
+///
+/// `grapheme` feature が無効な場合の近似実装です。区切りの規則は [`count_graphemes`] を参照してください。
+#[cfg(not(feature = "grapheme"))]
+struct Graphemes<'a> {
+    rest: &'a str,
+}
+
+#[cfg(not(feature = "grapheme"))]
+impl<'a> Graphemes<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { rest: input }
+    }
+}
+
+#[cfg(not(feature = "grapheme"))]
+impl<'a> Iterator for Graphemes<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        let mut chars = self.rest.char_indices();
+        let (_, first) = chars.next()?;
+        let mut end = first.len_utf8();
+
+        if first == '\r' && self.rest[end..].starts_with('\n') {
+            end += 1;
+        } else {
+            let mut prev = first;
+            let mut hangul = hangul_jamo_kind(first);
+            // 絵文字で始まり、結合文字と ZWJ だけが続いている間は `true`
+            let mut emoji_sequence = is_emoji(first);
+            let mut regional_count = usize::from(is_regional_indicator(first));
+            for (i, c) in chars {
+                let next_hangul = hangul_jamo_kind(c);
+                let joins_hangul = match (hangul, next_hangul) {
+                    (Some(HangulJamo::L), Some(next)) => !matches!(next, HangulJamo::T),
+                    (Some(HangulJamo::V | HangulJamo::Lv), Some(next)) => {
+                        matches!(next, HangulJamo::V | HangulJamo::T)
+                    }
+                    (Some(HangulJamo::T | HangulJamo::Lvt), Some(next)) => {
+                        matches!(next, HangulJamo::T)
+                    }
+                    _ => false,
+                };
+                let joins_emoji = prev == ZERO_WIDTH_JOINER && emoji_sequence && is_emoji(c);
+                let extends = is_grapheme_extend(c);
+                let joins = extends
+                    || joins_hangul
+                    || joins_emoji
+                    || (is_regional_indicator(c) && regional_count % 2 == 1);
+                if !joins {
+                    break;
+                }
+                if is_regional_indicator(c) {
+                    regional_count += 1;
+                }
+                if !extends {
+                    hangul = next_hangul;
+                }
+                emoji_sequence &= extends || joins_emoji;
+                prev = c;
+                end = i + c.len_utf8();
+            }
+        }
+
+        let (grapheme, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        Some(grapheme)
+    }
+}
+
+/// ハングルの字母・音節の種別です（内部ヘルパー）。
+#[cfg(not(feature = "grapheme"))]
+#[derive(Clone, Copy)]
+enum HangulJamo {
+    /// 初声
+    L,
+    /// 中声
+    V,
+    /// 終声
+    T,
+    /// 終声の無い音節
+    Lv,
+    /// 終声のある音節
+    Lvt,
+}
+
+/// ハングルの字母・音節の種別を返します（内部ヘルパー関数）。
+#[cfg(not(feature = "grapheme"))]
+fn hangul_jamo_kind(c: char) -> Option<HangulJamo> {
+    match c {
+        '\u{1100}'..='\u{115F}' | '\u{A960}'..='\u{A97C}' => Some(HangulJamo::L),
+        '\u{1160}'..='\u{11A7}' | '\u{D7B0}'..='\u{D7C6}' => Some(HangulJamo::V),
+        '\u{11A8}'..='\u{11FF}' | '\u{D7CB}'..='\u{D7FB}' => Some(HangulJamo::T),
+        '\u{AC00}'..='\u{D7A3}' => {
+            if (c as u32 - 0xAC00).is_multiple_of(28) {
+                Some(HangulJamo::Lv)
+            } else {
+                Some(HangulJamo::Lvt)
+            }
+        }
+        _ => None,
+    }
+}
+
+/// 直前の文字と同じ書記素クラスタに含める文字かどうかを判定します（内部ヘルパー関数）。
+#[cfg(not(feature = "grapheme"))]
+fn is_grapheme_extend(c: char) -> bool {
+    tables::GRAPHEME_EXTEND_RANGES
+        .iter()
+        .any(|range| range.contains(&c))
+}
+
+/// 地域指示記号（国旗の構成要素）かどうかを判定します（内部ヘルパー関数）。
+#[cfg(not(feature = "grapheme"))]
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c, '\u{1F1E6}'..='\u{1F1FF}')
+}
+
+/// 文字列内の各文字種の数をカウントします。
+///
+/// 各文字は [`classify`] で分類されます。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::count_character_types;
+///
+/// let counts = count_character_types("あア漢ABC123。");
+/// assert_eq!(counts.hiragana, 1);
+/// assert_eq!(counts.katakana, 1);
+/// assert_eq!(counts.kanji, 1);
+/// assert_eq!(counts.ascii, 6);
+/// assert_eq!(counts.punctuation, 1);
+/// ```
+///
+/// 文字種は今後追加される可能性があるため、`#[non_exhaustive]` を付けています。
+/// クレートの外では構造体リテラルで作成できません。
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CharacterTypes {
+    pub hiragana: usize,
+    pub katakana: usize,
+    pub half_width_katakana: usize,
+    pub kanji: usize,
+    pub ascii: usize,
+    pub full_width: usize,
+    pub punctuation: usize,
+    pub other: usize,
+}
+
+/// 2つの集計結果を文字種ごとに足し合わせます。複数のテキストの集計に使えます。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::count_character_types;
+///
+/// let total = count_character_types("あア") + count_character_types("あ漢");
+/// assert_eq!(total.hiragana, 2);
+/// assert_eq!(total.katakana, 1);
+/// assert_eq!(total.kanji, 1);
+/// ```
+impl core::ops::Add for CharacterTypes {
+    type Output = CharacterTypes;
+
+    fn add(self, rhs: CharacterTypes) -> CharacterTypes {
+        CharacterTypes {
+            hiragana: self.hiragana + rhs.hiragana,
+            katakana: self.katakana + rhs.katakana,
+            half_width_katakana: self.half_width_katakana + rhs.half_width_katakana,
+            kanji: self.kanji + rhs.kanji,
+            ascii: self.ascii + rhs.ascii,
+            full_width: self.full_width + rhs.full_width,
+            punctuation: self.punctuation + rhs.punctuation,
+            other: self.other + rhs.other,
+        }
+    }
+}
+
+/// 2つの集計結果の文字種ごとの差を求めます。
+///
+/// 各フィールドは飽和減算で計算され、負になる場合は `0` になります。
+/// 変換後から変換前を引くと「増えた文字種」、変換前から変換後を引くと「減った文字種」が得られます。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{count_character_types, half_width_katakana_to_full_width};
+///
+/// let before = count_character_types("ｶﾀｶﾅ");
+/// let after = count_character_types(&half_width_katakana_to_full_width("ｶﾀｶﾅ"));
+///
+/// let increased = after.clone() - before.clone();
+/// assert_eq!(increased.katakana, 4);
+/// assert_eq!(increased.half_width_katakana, 0);
+///
+/// let decreased = before - after;
+/// assert_eq!(decreased.half_width_katakana, 4);
+/// assert_eq!(decreased.katakana, 0);
+/// ```
+impl core::ops::Sub for CharacterTypes {
+    type Output = CharacterTypes;
+
+    fn sub(self, rhs: CharacterTypes) -> CharacterTypes {
+        CharacterTypes {
+            hiragana: self.hiragana.saturating_sub(rhs.hiragana),
+            katakana: self.katakana.saturating_sub(rhs.katakana),
+            half_width_katakana: self
+                .half_width_katakana
+                .saturating_sub(rhs.half_width_katakana),
+            kanji: self.kanji.saturating_sub(rhs.kanji),
+            ascii: self.ascii.saturating_sub(rhs.ascii),
+            full_width: self.full_width.saturating_sub(rhs.full_width),
+            punctuation: self.punctuation.saturating_sub(rhs.punctuation),
+            other: self.other.saturating_sub(rhs.other),
+        }
+    }
+}
+
+pub fn count_character_types(input: &str) -> CharacterTypes {
+    let mut counts = CharacterTypes {
+        hiragana: 0,
+        katakana: 0,
+        half_width_katakana: 0,
+        kanji: 0,
+        ascii: 0,
+        full_width: 0,
+        punctuation: 0,
+        other: 0,
+    };
+
+    for c in input.chars() {
+        match classify(c) {
+            CharClass::Hiragana => counts.hiragana += 1,
+            CharClass::Katakana => counts.katakana += 1,
+            CharClass::HalfWidthKatakana => counts.half_width_katakana += 1,
+            CharClass::Kanji => counts.kanji += 1,
+            CharClass::Ascii => counts.ascii += 1,
+            CharClass::FullWidth => counts.full_width += 1,
+            CharClass::Punctuation => counts.punctuation += 1,
+            CharClass::Other => counts.other += 1,
+        }
+    }
+
+    counts
+}
+
+/// [`japanese_score_with`] で使う、文字種ごとの重みです。
+///
+/// 各重みは `0.0`（日本語らしくない）から `1.0`（日本語らしい）の範囲で指定します。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreWeights {
+    /// ひらがなの重み（既定値 `1.0`）
+    pub hiragana: f64,
+    /// 全角カタカナの重み（既定値 `0.7`）
+    pub katakana: f64,
+    /// 半角カタカナの重み（既定値 `0.5`）
+    pub half_width_katakana: f64,
+    /// 漢字の重み（既定値 `0.5`）。中国語などとの区別のため、ひらがなより低くしています。
+    pub kanji: f64,
+    /// 和文の約物（、。「」など）の重み（既定値 `0.5`）
+    pub punctuation: f64,
+    /// 全角英数字・記号の重み（既定値 `0.2`）
+    pub full_width: f64,
+    /// ひらがなを1文字も含まない場合にスコアに掛ける係数（既定値 `0.5`）
+    pub no_hiragana_factor: f64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self {
+            hiragana: 1.0,
+            katakana: 0.7,
+            half_width_katakana: 0.5,
+            kanji: 0.5,
+            punctuation: 0.5,
+            full_width: 0.2,
+            no_hiragana_factor: 0.5,
+        }
+    }
+}
+
+/// 文字列の「日本語らしさ」を `0.0` から `1.0` のスコアで返します。
+///
+/// 各文字を [`classify`] で分類して [`ScoreWeights`] の既定の重みを付け、その平均を求めます。
+/// 英語などにはほとんど現れないひらがなを特に重視し、ひらがなを含まない文字列のスコアは半分になります。
+/// 空白と、英字以外の ASCII 文字（数字・記号）は平均の対象外です。対象の文字が無い場合は `0.0` を返します。
+///
+/// スパムフィルタなどの目安に使うことを想定した簡易的な指標です。
+/// **短い文字列ではスコアが大きくぶれます**（`東京` はひらがなを含まないため `0.25`、`の` だけで `1.0`）。
+/// 閾値で判定する場合は、ある程度の長さがある文字列に限って使用してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::japanese_score;
+///
+/// assert!(japanese_score("今日はいい天気ですね。") > 0.7);
+/// assert!(japanese_score("Hello, world!") < 0.1);
+/// ```
+pub fn japanese_score(input: &str) -> f64 {
+    japanese_score_with(input, ScoreWeights::default())
+}
+
+/// 重みを指定して、文字列の「日本語らしさ」を `0.0` から `1.0` のスコアで返します。
+///
+/// 計算方法は [`japanese_score`] を参照してください。結果は `0.0` から `1.0` の範囲に丸めます。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{japanese_score_with, ScoreWeights};
+///
+/// let weights = ScoreWeights {
+///     kanji: 1.0,
+///     no_hiragana_factor: 1.0,
+///     ..ScoreWeights::default()
+/// };
+/// assert_eq!(japanese_score_with("東京", weights), 1.0);
+/// ```
+pub fn japanese_score_with(input: &str, weights: ScoreWeights) -> f64 {
+    let mut total = 0.0;
+    let mut count = 0usize;
+    let mut has_hiragana = false;
+
+    for c in input.chars() {
+        if c.is_whitespace() || (c.is_ascii() && !c.is_ascii_alphabetic()) {
+            continue;
+        }
+        count += 1;
+        total += match classify(c) {
+            CharClass::Hiragana => {
+                has_hiragana = true;
+                weights.hiragana
+            }
+            CharClass::Katakana => weights.katakana,
+            CharClass::HalfWidthKatakana => weights.half_width_katakana,
+            CharClass::Kanji => weights.kanji,
+            CharClass::Punctuation => weights.punctuation,
+            CharClass::FullWidth => weights.full_width,
+            CharClass::Ascii | CharClass::Other => 0.0,
+        };
+    }
+
+    if count == 0 {
+        return 0.0;
+    }
+
+    let mut score = total / count as f64;
+    if !has_hiragana {
+        score *= weights.no_hiragana_factor;
+    }
+    score.clamp(0.0, 1.0)
+}
+
+/// [`text_features`] が返す、テキストの特徴量です。
+///
+/// 比率とエントロピーは、空文字列ではすべて `0.0` です。
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextFeatures {
+    /// 文字種ごとの文字数（[`count_character_types`] の結果）
+    pub types: CharacterTypes,
+    /// 文字数（`char` の数）
+    pub char_count: usize,
+    /// ひらがなの比率（`types.hiragana / char_count`）
+    pub hiragana_ratio: f64,
+    /// カタカナの比率（全角と半角の合計 `(types.katakana + types.half_width_katakana) / char_count`）
+    pub katakana_ratio: f64,
+    /// 漢字の比率（`types.kanji / char_count`）
+    pub kanji_ratio: f64,
+    /// ASCII 文字の比率（`types.ascii / char_count`）
+    pub ascii_ratio: f64,
+    /// 和文の約物の比率（`types.punctuation / char_count`）
+    pub punctuation_ratio: f64,
+    /// かなのラン（[`segment_by_script`] のひらがな・カタカナ・半角カタカナのまとまり）1つあたりの
+    /// 平均モーラ数（[`count_morae`]）。かなを含まない場合は `0.0` です。
+    pub average_mora_length: f64,
+    /// 文字の出現頻度のシャノンエントロピー（ビット）。`-Σ p(c) log2 p(c)` で、同じ文字の繰り返しが多いほど小さくなります。
+    pub char_entropy: f64,
+    /// [`CharClass`] の8種別の出現頻度のシャノンエントロピー（ビット）。文字種が混在しているほど大きく、最大は `3.0` です。
+    pub type_entropy: f64,
+}
+
+/// 機械学習の特徴量として使える、文字種の比率やエントロピーなどをまとめて計算します。
+///
+/// 各指標の定義は [`TextFeatures`] の各フィールドを参照してください。
+/// 文字の分類は [`classify`] に従います。外部クレートを使わずに計算できる、表層的な指標のみを扱います。
+/// 対数の計算に `std` を使うため、`std` feature が必要です。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::text_features;
+///
+/// let features = text_features("昨日テレビを見ました");
+/// assert_eq!(features.char_count, 10);
+/// assert_eq!(features.types.kanji, 3);
+/// assert_eq!(features.kanji_ratio, 0.3);
+/// assert_eq!(features.katakana_ratio, 0.3);
+/// assert!(features.type_entropy > 1.0);
+/// ```
+#[cfg(feature = "std")]
+pub fn text_features(input: &str) -> TextFeatures {
+    let types = count_character_types(input);
+    let char_count = input.chars().count();
+    let ratio = |count: usize| {
+        if char_count == 0 {
+            0.0
+        } else {
+            count as f64 / char_count as f64
+        }
+    };
+
+    let mut runs = 0usize;
+    let mut morae = 0usize;
+    for (class, run) in segment_by_script(input) {
+        if matches!(
+            class,
+            CharClass::Hiragana | CharClass::Katakana | CharClass::HalfWidthKatakana
+        ) {
+            runs += 1;
+            morae += count_morae(run);
+        }
+    }
+
+    let mut frequencies: alloc::collections::BTreeMap<char, usize> = Default::default();
+    for c in input.chars() {
+        *frequencies.entry(c).or_default() += 1;
+    }
+    let type_counts = [
+        types.hiragana,
+        types.katakana,
+        types.half_width_katakana,
+        types.kanji,
+        types.ascii,
+        types.full_width,
+        types.punctuation,
+        types.other,
+    ];
+
+    TextFeatures {
+        char_count,
+        hiragana_ratio: ratio(types.hiragana),
+        katakana_ratio: ratio(types.katakana + types.half_width_katakana),
+        kanji_ratio: ratio(types.kanji),
+        ascii_ratio: ratio(types.ascii),
+        punctuation_ratio: ratio(types.punctuation),
+        average_mora_length: if runs == 0 {
+            0.0
+        } else {
+            morae as f64 / runs as f64
+        },
+        char_entropy: entropy(frequencies.into_values(), char_count),
+        type_entropy: entropy(type_counts, char_count),
+        types,
+    }
+}
+
+/// 出現回数からシャノンエントロピー（ビット）を求めます（内部ヘルパー関数）。
+#[cfg(feature = "std")]
+fn entropy(counts: impl IntoIterator<Item = usize>, total: usize) -> f64 {
+    let entropy: f64 = counts
+        .into_iter()
+        .filter(|&count| count > 0)
+        .map(|count| {
+            let p = count as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum();
+    // 1種類だけの場合に -0.0 にならないようにする
+    entropy.max(0.0)
+}
+
+/// 文字列内の空白文字を正規化します（全角スペース、タブなどを半角スペースに統一）。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::normalize_whitespace;
+///
+/// assert_eq!(normalize_whitespace("Hello　World"), "Hello World");
+/// assert_eq!(normalize_whitespace("A\t\tB"), "A B");
+/// ```
+pub fn normalize_whitespace(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| {
+            if c.is_whitespace() || c == '　' {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// 全角スペース（U+3000）だけを半角スペースに変換します。
+///
+/// [`to_half_width`] と異なり、全角英数字・記号は全角のまま残します。
+/// 連続する空白の畳み込みも行いません。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::normalize_ideographic_space;
+///
+/// assert_eq!(normalize_ideographic_space("氏名　ＡＢＣ　１２３"), "氏名 ＡＢＣ １２３");
+/// ```
+pub fn normalize_ideographic_space(input: &str) -> String {
+    input.replace('　', " ")
+}
+
+/// 半角スペース（U+0020）だけを全角スペースに変換します。
+///
+/// [`normalize_ideographic_space`] の逆変換です。英数字・記号は半角のまま残します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::to_ideographic_space;
+///
+/// assert_eq!(to_ideographic_space("氏名 ABC 123"), "氏名　ABC　123");
+/// ```
+pub fn to_ideographic_space(input: &str) -> String {
+    input.replace(' ', "　")
+}
+
+/// 文字列の先頭と末尾から、空白と日本語の約物を取り除きます。
+///
+/// 取り除く文字は以下のとおりです。
+///
+/// - [`char::is_whitespace`] に該当する空白文字（半角スペース、全角スペース、タブ、改行など）
+/// - 和文の約物: 、。〃〈〉《》「」『』【】〔〕〖〗〘〙〚〛〜〝〞〟・
+/// - 全角の約物: ！，．：；？（）［］｛｝
+/// - 三点リーダ・二点リーダ: …‥
+///
+/// 借用したスライスを返すため、文字列の確保は行いません。
+/// 取り除く文字を指定する場合は [`trim_japanese_matches`] を使用してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::trim_japanese;
+///
+/// assert_eq!(trim_japanese("　「こんにちは」。 "), "こんにちは");
+/// assert_eq!(trim_japanese("・項目、"), "項目");
+/// ```
+pub fn trim_japanese(input: &str) -> &str {
+    input.trim_matches(is_trimmable_japanese)
+}
+
+/// 文字列の先頭から、空白と日本語の約物を取り除きます。
+///
+/// 取り除く文字は [`trim_japanese`] と同じです。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::trim_start_japanese;
+///
+/// assert_eq!(trim_start_japanese("　・項目。"), "項目。");
+/// ```
+pub fn trim_start_japanese(input: &str) -> &str {
+    input.trim_start_matches(is_trimmable_japanese)
+}
+
+/// 文字列の末尾から、空白と日本語の約物を取り除きます。
+///
+/// 取り除く文字は [`trim_japanese`] と同じです。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::trim_end_japanese;
+///
+/// assert_eq!(trim_end_japanese("「項目」。　"), "「項目");
+/// ```
+pub fn trim_end_japanese(input: &str) -> &str {
+    input.trim_end_matches(is_trimmable_japanese)
+}
+
+/// 文字列の先頭と末尾から、指定した文字を取り除きます。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::trim_japanese_matches;
+///
+/// assert_eq!(trim_japanese_matches("！！！重要！！！", &['！']), "重要");
+/// ```
+pub fn trim_japanese_matches<'a>(input: &'a str, chars: &[char]) -> &'a str {
+    input.trim_matches(|c| chars.contains(&c))
+}
+
+/// [`trim_japanese`] で取り除く文字かどうかを判定します（内部ヘルパー関数）。
+fn is_trimmable_japanese(c: char) -> bool {
+    c.is_whitespace()
+        || is_japanese_punctuation(c)
+        || matches!(
+            c,
+            '！' | '，' | '．' | '：' | '；' | '？' | '（' | '）' | '［' | '］' | '｛' | '｝' | '…' | '‥'
+        )
+}
+
+/// 各行の末尾の空白を取り除いたうえで、行ごとに変換関数を適用します。
+///
+/// - 末尾の空白は [`char::is_whitespace`] に該当する文字（全角スペースを含む）です
+/// - 改行コード（`\n` または `\r\n`）は元のまま保持します
+/// - 最終行に改行が無い場合は、出力の最終行にも改行を付けません
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{normalize_lines, to_hiragana};
+///
+/// assert_eq!(normalize_lines("カタカナ　\r\nテスト  \n", to_hiragana), "かたかな\r\nてすと\n");
+/// assert_eq!(normalize_lines("ア \nイ", to_hiragana), "あ\nい");
+/// ```
+pub fn normalize_lines(input: &str, f: impl Fn(&str) -> String) -> String {
+    let mut result = String::with_capacity(input.len());
+
+    for line in input.split_inclusive('\n') {
+        let (content, ending) = if let Some(content) = line.strip_suffix("\r\n") {
+            (content, "\r\n")
+        } else if let Some(content) = line.strip_suffix('\n') {
+            (content, "\n")
+        } else {
+            (line, "")
+        };
+
+        result.push_str(&f(content.trim_end()));
+        result.push_str(ending);
+    }
+
+    result
+}
+
+/// 半角カタカナを全角カタカナに変換します。
+///
+/// 濁点（゛）と半濁点（゜）も正しく結合されます。
+/// 半角の濁点・半濁点（U+FF9E, U+FF9F）に加えて、
+/// 結合用の濁点・半濁点（U+3099, U+309A）も結合の対象です。
+///
+/// 濁点・半濁点を付けられない文字（`ｱﾞ`、`ﾝﾞ` など）の後や文字列の先頭にある半角の濁点・半濁点は、
+/// 前の文字とは結合せず、全角の濁点（゛、U+309B）・半濁点（゜、U+309C）に変換します。
+/// 結合用の文字（U+3099）にすると前の文字と一体で表示されて `ア゙` のような別の文字に見えるため、
+/// 独立した記号として残します。一方、入力に含まれる結合用の濁点・半濁点は変換せずにそのまま残します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::half_width_katakana_to_full_width;
+///
+/// assert_eq!(half_width_katakana_to_full_width("ｶﾀｶﾅ"), "カタカナ");
+/// assert_eq!(half_width_katakana_to_full_width("ｶﾞｷﾞｸﾞｹﾞｺﾞ"), "ガギグゲゴ");
+/// assert_eq!(half_width_katakana_to_full_width("ﾊﾟﾋﾟﾌﾟﾍﾟﾎﾟ"), "パピプペポ");
+/// assert_eq!(half_width_katakana_to_full_width("ｱﾞ"), "ア゛");
+/// ```
+pub fn half_width_katakana_to_full_width(input: &str) -> String {
+    convert_half_width_katakana(input, false, false)
+}
+
+/// 半角カタカナを全角カタカナに変換します（空白をはさんだ濁点も結合する寛容モード）。
+///
+/// 古いシステムの中には、`ｶ ﾞ` のように基底の文字と濁点の間に空白を入れて送ってくるものがあります。
+/// この関数は [`half_width_katakana_to_full_width`] と同じ変換に加えて、次の規則で結合します。
+///
+/// - 基底の文字と濁点・半濁点の間にある半角スペース（U+0020）と全角スペース（U+3000）は、
+///   いくつ連続していても読み飛ばして結合し、その空白は出力しません
+/// - タブや改行など、それ以外の空白文字をはさんだ場合は結合しません
+/// - 結合できない組み合わせ（`ｱ ﾞ` → `ア ゛` など）や、濁点が続かない場合は空白をそのまま残します
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{half_width_katakana_to_full_width, half_width_katakana_to_full_width_lenient};
+///
+/// assert_eq!(half_width_katakana_to_full_width_lenient("ｶ ﾞｷﾞ"), "ガギ");
+/// assert_eq!(half_width_katakana_to_full_width("ｶ ﾞｷﾞ"), "カ ゛ギ");
+/// assert_eq!(half_width_katakana_to_full_width_lenient("ｱ ｲ"), "ア イ");
+/// ```
+pub fn half_width_katakana_to_full_width_lenient(input: &str) -> String {
+    convert_half_width_katakana(input, true, false)
+}
+
+/// 半角カタカナを全角カタカナに変換し、濁点・半濁点を結合用の文字として出力します（NFD 相当）。
+///
+/// [`half_width_katakana_to_full_width`] が `ｶﾞ` を合成済みの `ガ`（U+30AC）にするのに対し、
+/// この関数は基底の文字 `カ` と結合用濁点（U+3099）の2コードポイントを出力します。
+/// 半濁点は結合用半濁点（U+309A）になります。macOS のファイル名など、NFD で保存された
+/// 文字列と比較する場合に使います。
+///
+/// 濁点と結合できない文字（`ｱﾞ` など）の扱いや、入力に含まれる全角の合成済み文字は
+/// [`half_width_katakana_to_full_width`] と同じで、分解はしません。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{half_width_katakana_to_full_width, half_width_katakana_to_full_width_nfd};
+///
+/// assert_eq!(half_width_katakana_to_full_width_nfd("ｶﾞﾊﾟ"), "カ\u{3099}ハ\u{309A}");
+/// assert_ne!(
+///     half_width_katakana_to_full_width_nfd("ｶﾞ"),
+///     half_width_katakana_to_full_width("ｶﾞ")
+/// );
+/// ```
+pub fn half_width_katakana_to_full_width_nfd(input: &str) -> String {
+    convert_half_width_katakana(input, false, true)
+}
+
+/// 全角カタカナを半角カタカナに変換します。
+///
+/// [`half_width_katakana_to_full_width`] の逆変換です。濁音・半濁音は基底の文字と
+/// 半角の濁点（ﾞ）・半濁点（ﾟ）の2文字に分解します（`ガ` → `ｶﾞ`、`ヴ` → `ｳﾞ`、`ヷ` → `ﾜﾞ`）。
+/// 半角カタカナのブロックにある約物（。「」、・）と長音記号（ー）、全角の濁点・半濁点（゛゜）、
+/// 結合用の濁点・半濁点（U+3099, U+309A）も半角にします。
+///
+/// 対応する半角の無い文字（ヰ ヱ ヮ ヵ ヶ、ヸ ヹ、ヽ ヾ、拡張カタカナ（ㇰ など））と、
+/// ひらがな・漢字などのカタカナ以外の文字はそのまま残します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::full_width_katakana_to_half_width;
+///
+/// assert_eq!(full_width_katakana_to_half_width("カタカナ"), "ｶﾀｶﾅ");
+/// assert_eq!(full_width_katakana_to_half_width("ガッコウ「パン」"), "ｶﾞｯｺｳ｢ﾊﾟﾝ｣");
+/// assert_eq!(full_width_katakana_to_half_width("ヶ月"), "ヶ月");
+/// ```
+pub fn full_width_katakana_to_half_width(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+
+    for c in input.chars() {
+        if let Some(half) = half_width_katakana_of(c) {
+            result.push(half);
+            continue;
+        }
+
+        let mark = match (c, kana_voicing(c)) {
+            ('\u{3099}', _) => {
+                result.push('ﾞ');
+                continue;
+            }
+            ('\u{309A}', _) => {
+                result.push('ﾟ');
+                continue;
+            }
+            (_, Some(Voicing::Voiced)) => 'ﾞ',
+            (_, Some(Voicing::SemiVoiced)) => 'ﾟ',
+            _ => {
+                result.push(c);
+                continue;
+            }
+        };
+        let base = voicing_entry(c).map_or(c, |(base, _, _)| base);
+        match half_width_katakana_of(base) {
+            Some(half) => {
+                result.push(half);
+                result.push(mark);
+            }
+            None => result.push(c),
+        }
+    }
+
+    result
+}
+
+/// ひらがな・全角カタカナを半角カタカナに変換します。
+///
+/// [`to_katakana`] でカタカナにそろえてから [`full_width_katakana_to_half_width`] を適用します。
+/// 濁音・半濁音は半角の濁点・半濁点との2文字に分解されます。
+/// 対応する半角の無い文字の扱いは [`full_width_katakana_to_half_width`] と同じで、
+/// ひらがなの場合もカタカナに変換した文字（`ゐ` → `ヰ` など）のまま残します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::to_half_width_katakana;
+///
+/// assert_eq!(to_half_width_katakana("がっこう"), "ｶﾞｯｺｳ");
+/// assert_eq!(to_half_width_katakana("ぱんとコーヒー"), "ﾊﾟﾝﾄｺｰﾋｰ");
+/// ```
+pub fn to_half_width_katakana(input: &str) -> String {
+    full_width_katakana_to_half_width(&to_katakana(input))
+}
+
+/// 全角カタカナ・約物に対応する半角カタカナを返します（内部ヘルパー関数）。
+fn half_width_katakana_of(c: char) -> Option<char> {
+    HALF_WIDTH_KATAKANA_TABLE
+        .iter()
+        .position(|&full| full == c)
+        .and_then(|index| char::from_u32(0xFF61 + index as u32))
+}
+
+/// 濁点・半濁点の表記ゆれを、合成済みの1文字に正規化します。
+///
+/// 次の4つの表記をすべて合成済みの文字（`ガ`、U+30AC）にそろえます。
+///
+/// | 表記 | 例 |
+/// |------|----|
+/// | 半角カナ + 半角濁点 | `ｶﾞ` |
+/// | 全角カナ + 全角濁点（゛、U+309B） | `カ゛` |
+/// | 全角カナ + 結合用濁点（U+3099） | `カ\u{3099}` |
+/// | 合成済み | `ガ` |
+///
+/// 半濁点（ﾟ、゜、U+309A）も同様に `パ` などにそろえます。ひらがな（`か゛` → `が`）や、
+/// `ウ゛` → `ヴ`、`ワ゛` → `ヷ` のような [`tables::VOICING_KATAKANA`] にある組み合わせも対象です。
+/// 半角カタカナは濁点の有無にかかわらずすべて全角に変換します。
+/// 濁点・半濁点を付けられない文字（`ア゛` など）に続く場合は、濁点・半濁点をそのまま残します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::normalize_voiced_marks;
+///
+/// for input in ["ｶﾞ", "カ゛", "カ\u{3099}", "ガ"] {
+///     assert_eq!(normalize_voiced_marks(input), "ガ");
+/// }
+/// assert_eq!(normalize_voiced_marks("は゜ん"), "ぱん");
+/// ```
+pub fn normalize_voiced_marks(input: &str) -> String {
+    // 半角の濁点・半濁点は、半角カナと結合するか全角の濁点・半濁点になる
+    let full = half_width_katakana_to_full_width(input);
+
+    let mut result = String::with_capacity(full.len());
+    let mut chars = full.chars().peekable();
+    while let Some(c) = chars.next() {
+        let composed = chars.peek().and_then(|&mark| compose_voiced_mark(c, mark));
+        match composed {
+            Some(voiced) => {
+                result.push(voiced);
+                chars.next();
+            }
+            None => result.push(c),
+        }
+    }
+    result
+}
+
+/// 濁音・半濁音のかなを、基底のかなと結合用濁点・半濁点に分解します。
+///
+/// `が` は `か` + U+3099、`ぱ` は `は` + U+309A になります。`ゔ`・`ヴ`・`ヷ` なども
+/// [`tables::VOICING_HIRAGANA`] と [`tables::VOICING_KATAKANA`] に従って分解します。
+/// 濁音・半濁音以外の文字はそのまま残します。
+/// [`normalize_voiced_marks`] で元の合成済みの表記に戻せます。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{decompose_kana, normalize_voiced_marks};
+///
+/// assert_eq!(decompose_kana("がぱ"), "か\u{3099}は\u{309A}");
+/// assert_eq!(decompose_kana("ヴァ"), "ウ\u{3099}ァ");
+/// assert_eq!(normalize_voiced_marks(&decompose_kana("ガイド")), "ガイド");
+/// ```
+pub fn decompose_kana(input: &str) -> String {
+    let mut result = String::with_capacity(input.len().saturating_mul(2));
+    for c in input.chars() {
+        match voicing_entry(c) {
+            Some((base, voiced, _)) if voiced == c => {
+                result.push(base);
+                result.push('\u{3099}');
+            }
+            Some((base, _, semi_voiced)) if semi_voiced == Some(c) => {
+                result.push(base);
+                result.push('\u{309A}');
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// かなと後続の濁点・半濁点を合成します（内部ヘルパー関数）。
+///
+/// 濁点は ゛（U+309B）と結合用濁点（U+3099）、半濁点は ゜（U+309C）と結合用半濁点（U+309A）を対象とします。
+fn compose_voiced_mark(c: char, mark: char) -> Option<char> {
+    let (_, voiced, semi_voiced) = voicing_entry(c).filter(|&(base, _, _)| base == c)?;
+    match mark {
+        '゛' | '\u{3099}' => Some(voiced),
+        '゜' | '\u{309A}' => semi_voiced,
+        _ => None,
+    }
+}
+
+/// 半角カタカナを全角カタカナに変換します（内部ヘルパー関数）。
+///
+/// `lenient` が `true` の場合、基底の文字と濁点・半濁点の間の空白を読み飛ばして結合します。
+///
+/// 呼び出し元ごとに `lenient` と `decompose` を定数として展開させるため、常にインライン化します。
+#[inline(always)]
+fn convert_half_width_katakana(input: &str, lenient: bool, decompose: bool) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        if !is_half_width_katakana(c) {
+            result.push(c);
+            continue;
+        }
+
+        let index = c as usize - 0xFF61;
+
+        // 残りの文字列から濁点・半濁点の候補を覗く（寛容モードでは空白を読み飛ばす）
+        let rest = chars.as_str();
+        let rest = if lenient {
+            rest.trim_start_matches([' ', '　'])
+        } else {
+            rest
+        };
+
+        // 次の文字が濁点（ﾞ、U+3099）または半濁点（ﾟ、U+309A）なら結合を試みる。いずれも3バイト文字
+        let (combined, mark) = match rest.as_bytes().get(..3) {
+            Some([0xEF, 0xBE, 0x9E] | [0xE3, 0x82, 0x99]) => {
+                (HALF_WIDTH_KATAKANA_VOICED_TABLE[index], '\u{3099}')
+            }
+            Some([0xEF, 0xBE, 0x9F] | [0xE3, 0x82, 0x9A]) => {
+                (HALF_WIDTH_KATAKANA_SEMI_VOICED_TABLE[index], '\u{309A}')
+            }
+            _ => (None, '\u{3099}'),
+        };
+
+        match combined {
+            Some(_) if decompose => {
+                result.push(HALF_WIDTH_KATAKANA_TABLE[index]);
+                result.push(mark);
+                chars = rest[3..].chars();
+            }
+            Some(full) => {
+                result.push(full);
+                chars = rest[3..].chars();
+            }
+            None => result.push(HALF_WIDTH_KATAKANA_TABLE[index]),
+        }
+    }
+
+    result
+}
+
+/// 半角カタカナ（U+FF61-U+FF9F）から全角文字への対応表。
+///
+/// 半角カタカナのブロックは連続しているため、`U+FF61` からのオフセットで引けます。
+/// 結合できずに残った濁点（U+FF9E）と半濁点（U+FF9F）は、全角の濁点（゛、U+309B）と半濁点（゜、U+309C）にします。
+static HALF_WIDTH_KATAKANA_TABLE: [char; 63] = [
+    '。', '「', '」', '、', '・', 'ヲ', 'ァ', 'ィ', 'ゥ', 'ェ', 'ォ', 'ャ', 'ュ', 'ョ', 'ッ',
+    'ー', 'ア', 'イ', 'ウ', 'エ', 'オ', 'カ', 'キ', 'ク', 'ケ', 'コ', 'サ', 'シ', 'ス', 'セ',
+    'ソ', 'タ', 'チ', 'ツ', 'テ', 'ト', 'ナ', 'ニ', 'ヌ', 'ネ', 'ノ', 'ハ', 'ヒ', 'フ', 'ヘ',
+    'ホ', 'マ', 'ミ', 'ム', 'メ', 'モ', 'ヤ', 'ユ', 'ヨ', 'ラ', 'リ', 'ル', 'レ', 'ロ', 'ワ',
+    'ン', '゛', '゜',
+];
+
+/// 半角カタカナに濁点（U+FF9E, U+3099）が続いたときの結合結果。結合できない文字は `None` です。
+static HALF_WIDTH_KATAKANA_VOICED_TABLE: [Option<char>; 63] = [
+    None, None, None, None, None, None, None,
+    None, None, None, None, None, None, None,
+    None, None, None, None, Some('ヴ'), None, None,
+    Some('ガ'), Some('ギ'), Some('グ'), Some('ゲ'), Some('ゴ'), Some('ザ'), Some('ジ'),
+    Some('ズ'), Some('ゼ'), Some('ゾ'), Some('ダ'), Some('ヂ'), Some('ヅ'), Some('デ'),
+    Some('ド'), None, None, None, None, None, Some('バ'),
+    Some('ビ'), Some('ブ'), Some('ベ'), Some('ボ'), None, None, None,
+    None, None, None, None, None, None, None,
+    None, None, None, None, None, None, None,
+];
+
+/// 半角カタカナに半濁点（U+FF9F, U+309A）が続いたときの結合結果。結合できない文字は `None` です。
+static HALF_WIDTH_KATAKANA_SEMI_VOICED_TABLE: [Option<char>; 63] = [
+    None, None, None, None, None, None, None,
+    None, None, None, None, None, None, None,
+    None, None, None, None, None, None, None,
+    None, None, None, None, None, None, None,
+    None, None, None, None, None, None, None,
+    None, None, None, None, None, None, Some('パ'),
+    Some('ピ'), Some('プ'), Some('ペ'), Some('ポ'), None, None, None,
+    None, None, None, None, None, None, None,
+    None, None, None, None, None, None, None,
+];
+
+/// 長音記号を正規化します（ー、〜、～などを統一）。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::normalize_prolonged_sound;
+///
+/// assert_eq!(normalize_prolonged_sound("コーヒー"), "コーヒー");
+/// assert_eq!(normalize_prolonged_sound("コ〜ヒ〜"), "コーヒー");
+/// ```
+pub fn normalize_prolonged_sound(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| match c {
+            '〜' | '～' => 'ー',
+            _ => c,
+        })
+        .collect()
+}
+
+/// 連続する長音記号を1つに畳みます。
+///
+/// `ヤッホーーーー` のような伸ばし過ぎの表記を `ヤッホー` に正規化します。
+/// 長音符（ー）、半角長音符（ｰ）、波ダッシュ（〜）、全角チルダ（～）は混在していても
+/// 一続きの長音として扱い、先頭の1文字だけを残します。
+///
+/// 促音の畳み込みや残す文字数の指定は [`collapse_prolonged_sound_with`] を使用してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::collapse_prolonged_sound;
+///
+/// assert_eq!(collapse_prolonged_sound("ヤッホーーーー"), "ヤッホー");
+/// assert_eq!(collapse_prolonged_sound("すご〜〜〜い"), "すご〜い");
+/// ```
+pub fn collapse_prolonged_sound(input: &str) -> String {
+    collapse_prolonged_sound_with(input, CollapseOptions::default())
+}
+
+/// [`collapse_prolonged_sound_with`] の動作を指定するオプションです。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CollapseOptions {
+    /// 連続する促音（っ、ッ、ｯ。混在も可）も畳む場合は `true`。
+    pub collapse_repeats: bool,
+    /// 連続を畳んだときに残す文字数。これ以下の連続はそのまま残します。
+    /// `0` は `1` として扱います。
+    pub max_run: usize,
+}
+
+impl Default for CollapseOptions {
+    fn default() -> Self {
+        Self {
+            collapse_repeats: false,
+            max_run: 1,
+        }
+    }
+}
+
+/// オプションを指定して、連続する長音記号（と促音）を畳みます。
+///
+/// 連続の先頭から `max_run` 文字を残し、残りを取り除きます。
+/// 長音記号と促音は別々の連続として数えます（`ーっー` は3つの連続です）。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{collapse_prolonged_sound_with, CollapseOptions};
+///
+/// let opts = CollapseOptions {
+///     collapse_repeats: true,
+///     max_run: 2,
+/// };
+/// assert_eq!(collapse_prolonged_sound_with("やったっっっーーーー", opts), "やったっっーー");
+/// ```
+pub fn collapse_prolonged_sound_with(input: &str, opts: CollapseOptions) -> String {
+    let max_run = opts.max_run.max(1);
+    let mut result = String::with_capacity(input.len());
+    let mut run: Option<(bool, usize)> = None;
+
+    for c in input.chars() {
+        let is_prolonged = matches!(c, 'ー' | 'ｰ' | '〜' | '～');
+        let is_sokuon = opts.collapse_repeats && matches!(c, 'っ' | 'ッ' | 'ｯ');
+
+        if !is_prolonged && !is_sokuon {
+            run = None;
+            result.push(c);
+            continue;
+        }
+
+        let count = match run {
+            Some((prolonged, count)) if prolonged == is_prolonged => count + 1,
+            _ => 1,
+        };
+        run = Some((is_prolonged, count));
+        if count <= max_run {
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+/// ハイフン類を半角ハイフン（-）に統一します。
+///
+/// 以下の文字を半角ハイフン（U+002D）に変換します。
+///
+/// - ハイフン・ダッシュ類（U+2010-U+2015）
+/// - 全角ハイフンマイナス（U+FF0D）
+/// - 長音記号（U+30FC）
+///
+/// 住所の番地など、数字の区切りとして使われたハイフン類を揃える用途を想定しています。
+/// 長音記号も変換されるため、カタカナ語を含む文字列全体には適用しないでください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::normalize_hyphens;
+///
+/// assert_eq!(normalize_hyphens("１ー２ー３"), "１-２-３");
+/// assert_eq!(normalize_hyphens("1－2―3"), "1-2-3");
+/// ```
+pub fn normalize_hyphens(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| match c {
+            '\u{2010}'..='\u{2015}' | '\u{FF0D}' | '\u{30FC}' => '-',
+            _ => c,
+        })
+        .collect()
+}
+
+/// ダッシュ・ハイフン・長音記号を指定した文字に統一します。
+///
+/// 以下の文字をすべて `target` に変換します。
+///
+/// - ASCIIハイフンマイナス（U+002D）
+/// - ハイフン・ダッシュ類（U+2010-U+2015）
+/// - マイナス記号（U+2212）
+/// - 全角ハイフンマイナス（U+FF0D）
+/// - 長音記号（U+30FC）
+///
+/// 文脈を見ずに一括で変換します。カタカナ語の長音記号を残したい場合は
+/// [`normalize_dashes_with`] を使用してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::normalize_dashes;
+///
+/// assert_eq!(normalize_dashes("1－2‐3—4", '-'), "1-2-3-4");
+/// assert_eq!(normalize_dashes("A-B", 'ー'), "AーB");
+/// ```
+pub fn normalize_dashes(input: &str, target: char) -> String {
+    normalize_dashes_with(input, target, DashOptions::default())
+}
+
+/// [`normalize_dashes_with`] の動作を指定するオプションです。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DashOptions {
+    /// `true` の場合、カタカナ直後の長音記号（ー）は変換せずに残します。
+    pub preserve_prolonged_sound: bool,
+}
+
+/// オプションを指定してダッシュ・ハイフン・長音記号を統一します。
+///
+/// 変換対象の文字は [`normalize_dashes`] と同じです。
+/// `preserve_prolonged_sound` を有効にすると、カタカナ（または長音記号）の直後にある
+/// 長音記号は長音として使われているとみなして変換しません。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{normalize_dashes_with, DashOptions};
+///
+/// let opts = DashOptions { preserve_prolonged_sound: true };
+/// assert_eq!(normalize_dashes_with("コーヒー ４ー５", '-', opts), "コーヒー ４-５");
+/// ```
+pub fn normalize_dashes_with(input: &str, target: char, opts: DashOptions) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut prev: Option<char> = None;
+
+    for c in input.chars() {
+        let preserved = opts.preserve_prolonged_sound
+            && c == 'ー'
+            && matches!(prev, Some(p) if is_katakana(p) || p == 'ー');
+
+        if is_dash(c) && !preserved {
+            result.push(target);
+        } else {
+            result.push(c);
+        }
+        prev = Some(c);
+    }
+
+    result
+}
+
+/// OCR で長音記号（ー）がハイフンやダッシュと誤認された箇所を、長音記号に補正します。
+///
+/// ハイフン・ダッシュ類（-、‐‑‒–—―、−、－）を、次の条件をすべて満たす場合だけ長音記号に置き換えます。
+///
+/// - 直前がカタカナ、または長音記号（補正した結果を含む）であること
+/// - 直後がカタカナ（半角を含む）、長音記号・ハイフン・ダッシュ類、空白、約物（全角・半角）のいずれかか、
+///   文字列の末尾であること
+///
+/// 直前が半角カタカナの場合は半角の長音記号（ｰ）にします。
+/// そのため「コ-ヒ-」は「コーヒー」になり、「03-1234」のような数字に挟まれたハイフンや、
+/// 「ページ-3」「ロンドン—東京」のように英数字・漢字・ひらがなが続くハイフン・ダッシュはそのまま残ります。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::fix_ocr_prolonged_sound;
+///
+/// assert_eq!(fix_ocr_prolonged_sound("コ-ヒ-"), "コーヒー");
+/// assert_eq!(fix_ocr_prolonged_sound("ス—パ‐、03-1234"), "スーパー、03-1234");
+/// assert_eq!(fix_ocr_prolonged_sound("ロンドン—東京"), "ロンドン—東京");
+/// ```
+pub fn fix_ocr_prolonged_sound(input: &str) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut prev: Option<char> = None;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let mut output = c;
+        let at_word_end = chars.peek().is_none_or(|&n| {
+            is_katakana(n)
+                || is_half_width_katakana(n)
+                || is_dash(n)
+                || n.is_whitespace()
+                || n.is_ascii_punctuation()
+                || is_japanese_punctuation(n)
+                || (tables::FULL_WIDTH_ASCII_RANGE.contains(&n) && !is_alphanumeric_either_width(n))
+        });
+        if c != 'ー' && is_dash(c) && at_word_end {
+            match prev {
+                Some(p) if is_katakana(p) || p == 'ー' => output = 'ー',
+                Some(p) if is_half_width_katakana(p) && p != '･' => output = 'ｰ',
+                _ => {}
+            }
+        }
+        result.push(output);
+        prev = Some(output);
+    }
+
+    result
+}
+
+/// ダッシュ・ハイフン類かどうかを判定します（内部ヘルパー関数）。
+fn is_dash(c: char) -> bool {
+    matches!(c, '-' | '\u{2010}'..='\u{2015}' | '\u{2212}' | '\u{FF0D}' | '\u{30FC}')
+}
+
+/// 読点・句点の表記スタイルです。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PunctuationStyle {
+    /// 読点「、」と句点「。」を使う一般的な和文のスタイル
+    JaMaru,
+    /// 全角カンマ「，」と全角ピリオド「．」を使う論文・公用文のスタイル
+    Comma,
+}
+
+/// [`normalize_punctuation_style_with`] の動作を指定するオプションです。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PunctuationOptions {
+    /// `true` の場合、半角の `,` と `.` も変換対象に含めます。
+    pub include_half_width: bool,
+}
+
+/// 読点・句点の表記を指定したスタイルに統一します。
+///
+/// [`PunctuationStyle::JaMaru`] では「，．」を「、。」に、[`PunctuationStyle::Comma`] では
+/// 「、。」を「，．」に変換します。前後がともに数字（半角・全角）のカンマ・ピリオドは
+/// `１，２３４．５` のような数値の一部とみなして変換しません。
+///
+/// 半角の `,` `.` も対象にする場合は [`normalize_punctuation_style_with`] を使用してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{normalize_punctuation_style, PunctuationStyle};
+///
+/// assert_eq!(normalize_punctuation_style("はい，そうです．", PunctuationStyle::JaMaru), "はい、そうです。");
+/// assert_eq!(normalize_punctuation_style("はい、そうです。", PunctuationStyle::Comma), "はい，そうです．");
+/// ```
+pub fn normalize_punctuation_style(input: &str, style: PunctuationStyle) -> String {
+    normalize_punctuation_style_with(input, style, PunctuationOptions::default())
+}
+
+/// オプションを指定して読点・句点の表記を統一します。
+///
+/// `include_half_width` を有効にすると、半角の `,` `.` も変換対象に含めます。
+/// この場合も前後がともに数字のものは変換しないため、`1,234.5` はそのまま残ります。
+/// ただし `example.com` のような英文中のピリオドも変換されるため、和文だけを含む入力に使ってください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{normalize_punctuation_style_with, PunctuationOptions, PunctuationStyle};
+///
+/// let opts = PunctuationOptions { include_half_width: true };
+/// assert_eq!(
+///     normalize_punctuation_style_with("合計は1,234.5円です.", PunctuationStyle::JaMaru, opts),
+///     "合計は1,234.5円です。"
+/// );
+/// ```
+pub fn normalize_punctuation_style_with(
+    input: &str,
+    style: PunctuationStyle,
+    opts: PunctuationOptions,
+) -> String {
+    let mut result = String::with_capacity(input.len());
+    let mut prev: Option<char> = None;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let (is_comma, is_period) = match c {
+            '、' | '，' => (true, false),
+            '。' | '．' => (false, true),
+            ',' if opts.include_half_width => (true, false),
+            '.' if opts.include_half_width => (false, true),
+            _ => (false, false),
+        };
+        let in_number = matches!(prev, Some(p) if is_arabic_digit(p))
+            && matches!(chars.peek(), Some(&n) if is_arabic_digit(n));
+
+        if (is_comma || is_period) && !in_number {
+            result.push(match (style, is_comma) {
+                (PunctuationStyle::JaMaru, true) => '、',
+                (PunctuationStyle::JaMaru, false) => '。',
+                (PunctuationStyle::Comma, true) => '，',
+                (PunctuationStyle::Comma, false) => '．',
+            });
+        } else {
+            result.push(c);
+        }
+        prev = Some(c);
+    }
+
+    result
+}
+
+/// 繰り返し記号を展開します。
+///
+/// ひらがな・カタカナの繰り返し記号（ゝ、ゞ、ヽ、ヾ）を実際の文字に展開します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::expand_iteration_marks;
+///
+/// assert_eq!(expand_iteration_marks("いろゝ"), "いろろ");
+/// assert_eq!(expand_iteration_marks("かゞ"), "かが");
+/// ```
+pub fn expand_iteration_marks(input: &str) -> String {
+    let chars: Vec<char> = input.chars().collect();
+    let mut result = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        match c {
+            // ひらがな繰り返し記号（無声音）
+            'ゝ' => {
+                if i > 0 {
+                    result.push(chars[i - 1]);
+                } else {
+                    result.push(c);
+                }
+            }
+            // ひらがな繰り返し記号（濁音）
+            'ゞ' => {
+                if i > 0 {
+                    let prev = chars[i - 1];
+                    let voiced = add_dakuten(prev);
+                    result.push(voiced);
+                } else {
+                    result.push(c);
+                }
+            }
+            // カタカナ繰り返し記号（無声音）
+            'ヽ' => {
+                if i > 0 {
+                    result.push(chars[i - 1]);
+                } else {
+                    result.push(c);
+                }
+            }
+            // カタカナ繰り返し記号（濁音）
+            'ヾ' => {
+                if i > 0 {
+                    let prev = chars[i - 1];
+                    let voiced = add_dakuten(prev);
+                    result.push(voiced);
+                } else {
+                    result.push(c);
+                }
+            }
+            _ => result.push(c),
+        }
+    }
+
+    result
+}
+
+/// 小書きのかなを通常の大きさのかなに変換します。
+///
+/// ひらがな・全角カタカナの小書き（ぁぃぅぇぉ っ ゃゅょ ゎ ゕゖ とそのカタカナ）をすべて大書きにします。
+/// 拗音（ゃゅょ）を残すなど、対象を選ぶ場合は [`normalize_small_kana_selective`] を使用してください。
+/// 半角カタカナ（ｧ など）とカタカナ拡張（ㇰ など）は対象外です。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::normalize_small_kana;
+///
+/// assert_eq!(normalize_small_kana("きゃっと"), "きやつと");
+/// assert_eq!(normalize_small_kana("ファイル"), "フアイル");
+/// ```
+pub fn normalize_small_kana(input: &str) -> String {
+    normalize_small_kana_selective(input, SmallKana::ALL)
+}
+
+/// [`normalize_small_kana_selective`] で大書きにする小書きのかなを表すビットフラグです。
+///
+/// `|` で組み合わせて指定します。各フラグはひらがなとカタカナの両方を対象にします。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::SmallKana;
+///
+/// let targets = SmallKana::VOWELS | SmallKana::WA;
+/// assert!(targets.contains(SmallKana::WA));
+/// assert!(!targets.contains(SmallKana::YOON));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SmallKana(u8);
+
+impl SmallKana {
+    /// 小書きの母音（ぁぃぅぇぉ、ァィゥェォ）
+    pub const VOWELS: SmallKana = SmallKana(1);
+    /// 拗音の小書き（ゃゅょ、ャュョ）
+    pub const YOON: SmallKana = SmallKana(1 << 1);
+    /// 促音（っ、ッ）
+    pub const SOKUON: SmallKana = SmallKana(1 << 2);
+    /// 小書きのわ（ゎ、ヮ）
+    pub const WA: SmallKana = SmallKana(1 << 3);
+    /// 小書きのか・け（ゕゖ、ヵヶ）
+    pub const KA_KE: SmallKana = SmallKana(1 << 4);
+    /// すべての小書きのかな
+    pub const ALL: SmallKana = SmallKana(0b1_1111);
+
+    /// どの小書きも含まない空の集合を返します。
+    pub const fn empty() -> Self {
+        SmallKana(0)
+    }
+
+    /// `other` のフラグをすべて含むかどうかを返します。
+    pub const fn contains(self, other: SmallKana) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl core::ops::BitOr for SmallKana {
+    type Output = SmallKana;
+
+    fn bitor(self, rhs: SmallKana) -> SmallKana {
+        SmallKana(self.0 | rhs.0)
+    }
+}
+
+impl core::ops::BitOrAssign for SmallKana {
+    fn bitor_assign(&mut self, rhs: SmallKana) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// 指定した種類の小書きのかなだけを大書きにします。
+///
+/// 拗音（きゃ）の「ゃ」は残しつつ、「ファ」の「ァ」のような非拗音的な小書きだけを大書きにする、
+/// といった細かい制御に使います。`targets` に含まれない小書きはそのまま残します。
+/// 「ヷ」「ヸ」のような濁点付きの文字は小書きではないため対象外です。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{normalize_small_kana_selective, SmallKana};
+///
+/// assert_eq!(normalize_small_kana_selective("ふぁっしょん", SmallKana::VOWELS), "ふあっしょん");
+/// assert_eq!(
+///     normalize_small_kana_selective("ヴァッシュヮ", SmallKana::VOWELS | SmallKana::WA),
+///     "ヴアッシュワ"
+/// );
+/// ```
+pub fn normalize_small_kana_selective(input: &str, targets: SmallKana) -> String {
+    input
+        .chars()
+        .map(|c| {
+            let kind = match c {
+                'ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ' | 'ァ' | 'ィ' | 'ゥ' | 'ェ' | 'ォ' => SmallKana::VOWELS,
+                'ゃ' | 'ゅ' | 'ょ' | 'ャ' | 'ュ' | 'ョ' => SmallKana::YOON,
+                'っ' | 'ッ' => SmallKana::SOKUON,
+                'ゎ' | 'ヮ' => SmallKana::WA,
+                'ゕ' | 'ゖ' | 'ヵ' | 'ヶ' => SmallKana::KA_KE,
+                _ => return c,
+            };
+            if targets.contains(kind) {
+                to_large_kana(c)
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// 文字の対応表から組み立てる、データ駆動の文字置換器です。
+///
+/// 対応表はソート済みの配列として保持し、二分探索で引きます。
+/// 旧字体 → 新字体の一部など、利用者が定義した文字置換に使います。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::Converter;
+///
+/// let converter = Converter::from_pairs(&[('國', '国'), ('學', '学')]);
+/// assert_eq!(converter.convert("國學院"), "国学院");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Converter {
+    pairs: Vec<(char, char)>,
+}
+
+impl Converter {
+    /// 変換元と変換先の組の一覧から変換器を作ります。
+    ///
+    /// 同じ変換元が複数回現れた場合は、後の組が優先されます。
+    pub fn from_pairs(pairs: &[(char, char)]) -> Self {
+        let mut sorted = pairs.to_vec();
+        // 安定ソートなので、同じ変換元の組は元の順序のまま並ぶ
+        sorted.sort_by_key(|&(from, _)| from);
+        let mut deduped: Vec<(char, char)> = Vec::with_capacity(sorted.len());
+        for pair in sorted {
+            match deduped.last_mut() {
+                Some(last) if last.0 == pair.0 => *last = pair,
+                _ => deduped.push(pair),
+            }
+        }
+        Self { pairs: deduped }
+    }
+
+    /// 1文字を変換します。対応表に無い文字はそのまま返します。
+    ///
+    /// # 使用例
+    ///
+    /// ```
+    /// use japanese_text::Converter;
+    ///
+    /// let converter = Converter::from_pairs(&[('國', '国')]);
+    /// assert_eq!(converter.convert_char('國'), '国');
+    /// assert_eq!(converter.convert_char('家'), '家');
+    /// ```
+    pub fn convert_char(&self, c: char) -> char {
+        match self.pairs.binary_search_by_key(&c, |&(from, _)| from) {
+            Ok(index) => self.pairs[index].1,
+            Err(_) => c,
+        }
+    }
+
+    /// 文字列の各文字を対応表に従って変換します。
+    pub fn convert(&self, input: &str) -> String {
+        input.chars().map(|c| self.convert_char(c)).collect()
+    }
+}
+
+/// 旧字体の漢字を新字体に変換します。
+///
+/// 変換には [`tables::SHINJITAI_PAIRS`] を使います。常用漢字の代表的な旧字体のみを対象とし、
+/// 表に無い字はそのまま残します。古い文書の正規化に使います。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::to_shinjitai;
+///
+/// assert_eq!(to_shinjitai("國學院大學"), "国学院大学");
+/// assert_eq!(to_shinjitai("廣島縣"), "広島県");
+/// ```
+pub fn to_shinjitai(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| {
+            match tables::SHINJITAI_PAIRS.binary_search_by_key(&c, |&(old, _)| old) {
+                Ok(index) => tables::SHINJITAI_PAIRS[index].1,
+                Err(_) => c,
+            }
+        })
+        .collect()
+}
+
+/// CJK互換漢字を対応するCJK統合漢字に変換します。
+///
+/// 互換漢字（U+F900-U+FAFF、U+2F800-U+2FA1F）は見た目が統合漢字と同じでも別の符号位置のため、
+/// 検索で別の字として扱われます。この関数は Unicode の正規分解に従って統合漢字に置き換えます
+/// （NFC と同じ結果です）。対応表は [`tables::COMPAT_KANJI_PAIRS`] を参照してください。
+///
+/// 「﨑」（U+FA11）のように互換漢字のブロックにあっても統合漢字として扱われる字は変換しません。
+/// 「﨑」→「崎」のような異体字の統一には [`Converter`] を使用してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::normalize_compat_kanji;
+///
+/// // U+FA19（互換漢字の「神」）→ U+795E
+/// assert_eq!(normalize_compat_kanji("\u{FA19}社"), "神社");
+/// // U+2F8B2（互換漢字補助）→ U+6210
+/// assert_eq!(normalize_compat_kanji("\u{2F8B2}"), "\u{6210}");
+/// // 﨑（U+FA11）はそのまま
+/// assert_eq!(normalize_compat_kanji("\u{FA11}"), "\u{FA11}");
+/// ```
+pub fn normalize_compat_kanji(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| {
+            match tables::COMPAT_KANJI_PAIRS.binary_search_by_key(&c, |&(compat, _)| compat) {
+                Ok(index) => tables::COMPAT_KANJI_PAIRS[index].1,
+                Err(_) => c,
+            }
+        })
+        .collect()
+}
+
+/// Unicode 正規化形式 NFKC（互換分解の後に正規合成）に正規化します。
+///
+/// [`unicode-normalization`](https://docs.rs/unicode-normalization) クレートの薄いラッパーで、
+/// `unicode-norm` feature を有効にすると利用できます。
+///
+/// NFKC は全角英数字や半角カタカナに加えて、丸囲み文字（`①` → `1`）や組文字（`㈱` → `(株)`）、
+/// 単位記号（`㌔` → `キロ`）まで展開します。元の表記をできるだけ保ちたい場合は
+/// [`to_half_width`] や [`half_width_katakana_to_full_width`] を使用してください。
+/// 使い分けの目安は README の「Unicode 正規化（NFKC）との違い」を参照してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::nfkc;
+///
+/// assert_eq!(nfkc("ＡＢＣ１２３"), "ABC123");
+/// assert_eq!(nfkc("ｶﾞｲﾄﾞ"), "ガイド");
+/// assert_eq!(nfkc("①㈱"), "1(株)");
+/// ```
+#[cfg(feature = "unicode-norm")]
+pub fn nfkc(input: &str) -> String {
+    unicode_normalization::UnicodeNormalization::nfkc(input).collect()
+}
+
+/// Unicode 正規化形式 NFKD（互換分解）に正規化します。
+///
+/// `unicode-norm` feature を有効にすると利用できます。[`nfkc`] と異なり、濁音・半濁音は
+/// 基底のかなと結合用濁点・半濁点（U+3099, U+309A）に分解されたままになります。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::nfkd;
+///
+/// assert_eq!(nfkd("ｶﾞ"), "カ\u{3099}");
+/// ```
+#[cfg(feature = "unicode-norm")]
+pub fn nfkd(input: &str) -> String {
+    unicode_normalization::UnicodeNormalization::nfkd(input).collect()
+}
+
+/// Unicode 正規化形式 NFC（正規分解の後に正規合成）に正規化します。
+///
+/// `unicode-norm` feature を有効にすると利用できます。結合用濁点付きのかな（`か\u{3099}`）は
+/// 合成済みの文字（`が`）になります。全角英数字や半角カタカナは変換しません。
+/// CJK互換漢字は統合漢字に変換されます（[`normalize_compat_kanji`] と同じ対応です）。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::nfc;
+///
+/// assert_eq!(nfc("か\u{3099}"), "が");
+/// assert_eq!(nfc("ＡＢＣ"), "ＡＢＣ");
+/// ```
+#[cfg(feature = "unicode-norm")]
+pub fn nfc(input: &str) -> String {
+    unicode_normalization::UnicodeNormalization::nfc(input).collect()
+}
+
+/// Unicode 正規化形式 NFD（正規分解）に正規化します。
+///
+/// `unicode-norm` feature を有効にすると利用できます。濁音・半濁音は基底のかなと
+/// 結合用濁点・半濁点に分解されます（[`decompose_kana`] と同じ結果です）。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::nfd;
+///
+/// assert_eq!(nfd("がぱ"), "か\u{3099}は\u{309A}");
+/// ```
+#[cfg(feature = "unicode-norm")]
+pub fn nfd(input: &str) -> String {
+    unicode_normalization::UnicodeNormalization::nfd(input).collect()
+}
+
+/// かなの統一先を表す列挙型です。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KanaForm {
+    /// ひらがな
+    Hiragana,
+    /// 全角カタカナ
+    Katakana,
+}
+
+/// 全角カタカナ・半角カタカナ・ひらがなが混在した文字列を、指定したかなに統一します。
+///
+/// 半角カタカナを全角化（[`half_width_katakana_to_full_width`]）したうえで、
+/// [`to_katakana`] または [`to_hiragana`] を適用します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{unify_kana, KanaForm};
+///
+/// assert_eq!(unify_kana("ﾃﾞｰﾀとデータとでーた", KanaForm::Katakana), "データトデータトデータ");
+/// assert_eq!(unify_kana("ﾃﾞｰﾀとデータ", KanaForm::Hiragana), "でーたとでーた");
+/// ```
+pub fn unify_kana(input: &str, form: KanaForm) -> String {
+    let full = half_width_katakana_to_full_width(input);
+    match form {
+        KanaForm::Hiragana => to_hiragana(&full),
+        KanaForm::Katakana => to_katakana(&full),
+    }
+}
+
+/// 全角カタカナ・半角カタカナ・ひらがなが混在した文字列を、全角カタカナに統一します。
+///
+/// `unify_kana(input, KanaForm::Katakana)` と同じです。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::unify_katakana;
+///
+/// assert_eq!(unify_katakana("ﾊﾞｲｵﾘﾝとばいおりん"), "バイオリントバイオリン");
+/// ```
+pub fn unify_katakana(input: &str) -> String {
+    unify_kana(input, KanaForm::Katakana)
+}
+
+/// [`search_key_with`] で適用する正規化を指定するオプションです。
+///
+/// [`Default`] ではすべての正規化が有効です。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchKeyOptions {
+    /// 半角カタカナを全角カタカナに変換します（濁点・半濁点の結合を含む）。
+    pub half_width_katakana: bool,
+    /// カタカナをひらがなに変換します。
+    pub hiragana: bool,
+    /// 全角ASCII文字を半角に変換します。
+    pub half_width_ascii: bool,
+    /// 英字を小文字に変換します。
+    pub lowercase: bool,
+    /// 長音記号（ー）の扱いです。既定では [`ProlongedPolicy::ToVowel`] で、直前のかなの母音に展開します。
+    pub prolonged: ProlongedPolicy,
+    /// 濁音・半濁音を清音に変換します。
+    pub remove_voicing: bool,
+}
+
+impl Default for SearchKeyOptions {
+    fn default() -> Self {
+        SearchKeyOptions {
+            half_width_katakana: true,
+            hiragana: true,
+            half_width_ascii: true,
+            lowercase: true,
+            prolonged: ProlongedPolicy::ToVowel,
+            remove_voicing: true,
+        }
+    }
+}
+
+/// あいまい検索用のインデックスキーを生成します。
+///
+/// 全角・半角、大文字・小文字、ひらがな・カタカナ、濁点、長音の違いをすべて畳み込みます。
+/// `search_key_with(input, SearchKeyOptions::default())` と同じです。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::search_key;
+///
+/// assert_eq!(search_key("ﾊﾞｲｵﾘﾝ"), search_key("バイオリン"));
+/// assert_eq!(search_key("バイオリン"), search_key("ばいおりん"));
+/// assert_eq!(search_key("ＡＢＣ"), "abc");
+/// ```
+pub fn search_key(input: &str) -> String {
+    search_key_with(input, SearchKeyOptions::default())
+}
+
+/// 適用する正規化を指定して、あいまい検索用のインデックスキーを生成します。
+///
+/// 有効な正規化を以下の順で適用します。
+///
+/// 1. 半角カタカナ → 全角カタカナ（`half_width_katakana`）
+/// 2. カタカナ → ひらがな（`hiragana`）
+/// 3. 全角ASCII → 半角（`half_width_ascii`）
+/// 4. 大文字 → 小文字（`lowercase`）
+/// 5. 長音記号の母音展開または削除（`prolonged`）
+/// 6. 濁音・半濁音 → 清音（`remove_voicing`）
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{search_key_with, SearchKeyOptions};
+///
+/// let opts = SearchKeyOptions { remove_voicing: false, ..SearchKeyOptions::default() };
+/// assert_eq!(search_key_with("バイオリン", opts), "ばいおりん");
+/// ```
+pub fn search_key_with(input: &str, opts: SearchKeyOptions) -> String {
+    let mut key = String::from(input);
+    if opts.half_width_katakana {
+        key = half_width_katakana_to_full_width(&key);
+    }
+    if opts.hiragana {
+        key = to_hiragana(&key);
+    }
+    if opts.half_width_ascii {
+        key = to_half_width(&key);
+    }
+    if opts.lowercase {
+        key = key.to_lowercase();
+    }
+    match opts.prolonged {
+        ProlongedPolicy::Keep => {}
+        ProlongedPolicy::ToVowel => key = expand_prolonged_marks(&key),
+        ProlongedPolicy::Remove => key.retain(|c| c != 'ー'),
+    }
+    if opts.remove_voicing {
+        key = key.chars().map(remove_voicing).collect();
+    }
+    key
+}
+
+/// 漢数字（算用数字との混在を含む）を整数に変換します。
+///
+/// 以下の文字を解釈します。それ以外の文字を含む場合や空文字列の場合は `None` を返します。
+///
+/// - 数字: 〇 零 一 二 三 四 五 六 七 八 九、半角・全角の算用数字
+/// - 位取り: 十 百 千（直前の数字が無い場合は1倍）、万 億 兆
+/// - 桁区切り: `,` `，`（読み飛ばします）
+///
+/// `千二百三十四` のような位取りを使った表記と、`二〇二四` のような数字を並べた表記、
+/// `1万2000` のような算用数字と位取りの混在に対応します。`i64` の範囲を超える場合も `None` を返します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::kanji_to_arabic;
+///
+/// assert_eq!(kanji_to_arabic("千二百三十四"), Some(1234));
+/// assert_eq!(kanji_to_arabic("二〇二四"), Some(2024));
+/// assert_eq!(kanji_to_arabic("3億5000万"), Some(350_000_000));
+/// assert_eq!(kanji_to_arabic("百円"), None);
+/// ```
+#[cfg(feature = "numerals")]
+pub fn kanji_to_arabic(input: &str) -> Option<i64> {
+    if input.is_empty() {
+        return None;
+    }
+    let mut parser = NumberParser::default();
+    for c in input.chars() {
+        if !matches!(c, ',' | '，') {
+            parser.push(c)?;
+        }
+    }
+    parser.finish()
+}
+
+/// 文中の数値表現を抽出し、入力上の範囲と値の組を返します。
+///
+/// 半角・全角の算用数字、漢数字、算用数字と位取り（万 億 兆 など）の混在を解釈します。
+/// 数値の解釈は [`kanji_to_arabic`] と同じです。範囲は入力文字列のバイトオフセットで、
+/// 通貨記号や単位（`円`、`個` など）は含みません。
+///
+/// - 桁区切りのカンマ（`,` `，`）は、算用数字の後に3桁の数字が続く場合のみ数値の一部とみなします
+/// - 小数点（`.` `．`）の後に数字が続く場合は小数とみなし、**整数部のみ**を値として返します
+///   （範囲には小数部を含みます）
+/// - 負号（`-` `－` `−`）は、数値の直前にあり、さらにその前が数字・英字でない場合のみ数値の一部とみなします
+///   （`2024-01-01` や `A-1` のハイフンは負号になりません）
+/// - 漢数字は位取りの文字だけ（`万` など）では数値の開始とみなしません。
+///   「一般」の「一」のように、数値以外の意味で使われた漢数字も抽出される点に注意してください
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::extract_numbers;
+///
+/// let numbers = extract_numbers("１，２３４円と千二百三十四円と1234円");
+/// let values: Vec<i64> = numbers.iter().map(|(_, value)| *value).collect();
+/// assert_eq!(values, vec![1234, 1234, 1234]);
+/// assert_eq!(numbers[0].0, 0..15);
+/// ```
+#[cfg(feature = "numerals")]
+pub fn extract_numbers(input: &str) -> Vec<(core::ops::Range<usize>, i64)> {
+    let chars: Vec<(usize, char)> = input.char_indices().collect();
+    let mut numbers = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (start, c) = chars[i];
+        if numeral_digit(c).is_none() && !matches!(c, '十' | '百' | '千') {
+            i += 1;
+            continue;
+        }
+
+        // 負号
+        let negative = i >= 1
+            && matches!(chars[i - 1].1, '-' | '－' | '−')
+            && (i < 2 || !is_number_context(chars[i - 2].1));
+        let range_start = if negative { chars[i - 1].0 } else { start };
+
+        let mut parser = NumberParser::default();
+        let mut valid = true;
+        while i < chars.len() {
+            let c = chars[i].1;
+            let prev_is_arabic = i > 0 && is_arabic_digit(chars[i - 1].1);
+            if prev_is_arabic && matches!(c, ',' | '，') && is_digit_group(&chars[i + 1..]) {
+                i += 1;
+            } else if prev_is_arabic
+                && matches!(c, '.' | '．')
+                && chars.get(i + 1).is_some_and(|&(_, d)| is_arabic_digit(d))
+            {
+                // 小数部は読み飛ばす
+                i += 1;
+                while i < chars.len() && is_arabic_digit(chars[i].1) {
+                    i += 1;
+                }
+                break;
+            } else if numeral_digit(c).is_some() || matches!(c, '十' | '百' | '千' | '万' | '億' | '兆') {
+                if parser.push(c).is_none() {
+                    valid = false;
+                }
+                i += 1;
+            } else {
+                break;
+            }
+        }
+
+        let end = chars.get(i).map_or(input.len(), |&(offset, _)| offset);
+        if let Some(value) = parser.finish().filter(|_| valid) {
+            let value = if negative { -value } else { value };
+            numbers.push((range_start..end, value));
+        }
+    }
+
+    numbers
+}
+
+/// 漢数字・算用数字の解析の途中状態です（内部ヘルパー）。
+#[cfg(feature = "numerals")]
+#[derive(Default)]
+struct NumberParser {
+    /// 万・億・兆で確定した部分
+    total: i64,
+    /// 万未満の位取りで確定した部分
+    section: i64,
+    /// 位取りの前の数字の並び
+    digits: Option<i64>,
+}
+
+#[cfg(feature = "numerals")]
+impl NumberParser {
+    /// 1文字を読み込みます。解釈できない文字や桁あふれの場合は `None` を返します。
+    fn push(&mut self, c: char) -> Option<()> {
+        if let Some(d) = numeral_digit(c) {
+            self.digits = Some(self.digits.unwrap_or(0).checked_mul(10)?.checked_add(d)?);
+            return Some(());
+        }
+        match c {
+            '十' | '百' | '千' => {
+                let unit = match c {
+                    '十' => 10,
+                    '百' => 100,
+                    _ => 1000,
+                };
+                let value = self.digits.take().unwrap_or(1).checked_mul(unit)?;
+                self.section = self.section.checked_add(value)?;
+            }
+            '万' | '億' | '兆' => {
+                let unit: i64 = match c {
+                    '万' => 10_000,
+                    '億' => 100_000_000,
+                    _ => 1_000_000_000_000,
+                };
+                let section = self.section.checked_add(self.digits.take().unwrap_or(0))?;
+                let section = if section == 0 { 1 } else { section };
+                self.total = self.total.checked_add(section.checked_mul(unit)?)?;
+                self.section = 0;
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+
+    /// 読み込んだ数値を返します。
+    fn finish(self) -> Option<i64> {
+        self.total
+            .checked_add(self.section)?
+            .checked_add(self.digits.unwrap_or(0))
+    }
+}
+
+/// 算用数字・漢数字の数字1文字の値を返します（内部ヘルパー関数）。
+#[cfg(feature = "numerals")]
+fn numeral_digit(c: char) -> Option<i64> {
+    let digit = match c {
+        '0'..='9' => c as u32 - '0' as u32,
+        '０'..='９' => c as u32 - '０' as u32,
+        '〇' | '零' => 0,
+        '一' => 1,
+        '二' => 2,
+        '三' => 3,
+        '四' => 4,
+        '五' => 5,
+        '六' => 6,
+        '七' => 7,
+        '八' => 8,
+        '九' => 9,
+        _ => return None,
+    };
+    Some(i64::from(digit))
+}
+
+/// 半角・全角の算用数字かどうかを判定します（内部ヘルパー関数）。
+fn is_arabic_digit(c: char) -> bool {
+    matches!(c, '0'..='9' | '０'..='９')
+}
+
+/// 負号の前にあると負号とみなさない文字（数字・英字）かどうかを判定します（内部ヘルパー関数）。
+#[cfg(feature = "numerals")]
+fn is_number_context(c: char) -> bool {
+    numeral_digit(c).is_some() || c.is_ascii_alphabetic() || matches!(c, 'Ａ'..='Ｚ' | 'ａ'..='ｚ')
+}
+
+/// 桁区切りのカンマの後が、ちょうど3桁の算用数字かどうかを判定します（内部ヘルパー関数）。
+#[cfg(feature = "numerals")]
+fn is_digit_group(rest: &[(usize, char)]) -> bool {
+    rest.len() >= 3
+        && rest[..3].iter().all(|&(_, c)| is_arabic_digit(c))
+        && !rest.get(3).is_some_and(|&(_, c)| is_arabic_digit(c))
+}
+
+/// 中黒（・）で文字列を分割します。
+///
+/// 全角中黒（U+30FB）と半角中黒（U+FF65）の両方を区切りとして扱います。
+/// 連続する中黒や先頭・末尾の中黒によって生じる空の要素は含めません。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::split_nakaguro;
+///
+/// assert_eq!(split_nakaguro("ニューヨーク・タイムズ"), vec!["ニューヨーク", "タイムズ"]);
+/// assert_eq!(split_nakaguro("・アイス・・コーヒー･"), vec!["アイス", "コーヒー"]);
+/// ```
+pub fn split_nakaguro(input: &str) -> Vec<String> {
+    input
+        .split(['・', '･'])
+        .filter(|part| !part.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// [`split_sentences_with`] の動作を指定するオプションです。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SentenceOptions {
+    /// 括弧（「」『』（）()【】〈〉《》）の内側にある文末記号で分割しない場合は `true`。
+    /// 括弧の入れ子も考慮します。括弧の内側の文末記号の直後で括弧が閉じても、
+    /// そこでは分割しません（`「はい。」と答えた。` は1文になります）。
+    pub respect_brackets: bool,
+}
+
+/// 文末記号で文字列を文に分割します。
+///
+/// 句点（。｡）、感嘆符（！!）、疑問符（？?）を文末とみなします。
+/// 文末記号の連続（`！？`）と、直後の閉じ括弧（`。」`）は同じ文に含めます。
+/// 三点リーダ（…）や半角ピリオド（.）は文末とみなしません。
+/// 各文の前後の空白は取り除き、空の文は含めません。
+///
+/// 括弧内の句点（`「はい。そうです。」と答えた。`）でも分割するため、
+/// 括弧の内側で分割しない場合は [`split_sentences_with`] で `respect_brackets` を指定してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::split_sentences;
+///
+/// assert_eq!(
+///     split_sentences("今日は晴れ。明日は雨！本当？"),
+///     vec!["今日は晴れ。", "明日は雨！", "本当？"]
+/// );
+/// assert_eq!(split_sentences("えっ！？ 待って……"), vec!["えっ！？", "待って……"]);
+/// ```
+pub fn split_sentences(input: &str) -> Vec<String> {
+    split_sentences_with(input, SentenceOptions::default())
+}
+
+/// オプションを指定して、文末記号で文字列を文に分割します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{split_sentences_with, SentenceOptions};
+///
+/// let opts = SentenceOptions { respect_brackets: true };
+/// assert_eq!(
+///     split_sentences_with("「はい。そうです。」と答えた。次の文。", opts),
+///     vec!["「はい。そうです。」と答えた。", "次の文。"]
+/// );
+/// ```
+pub fn split_sentences_with(input: &str, opts: SentenceOptions) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    let mut start = 0;
+    let mut depth = 0usize;
+
+    while let Some((i, c)) = chars.next() {
+        if is_open_bracket(c) {
+            depth += 1;
+            continue;
+        }
+        if is_close_bracket(c) {
+            depth = depth.saturating_sub(1);
+            continue;
+        }
+        if !is_sentence_end(c) || (opts.respect_brackets && depth > 0) {
+            continue;
+        }
+
+        // 文末記号の連続と直後の閉じ括弧を同じ文に含める
+        let mut end = i + c.len_utf8();
+        while let Some(&(j, next)) = chars.peek() {
+            if is_sentence_end(next) {
+                end = j + next.len_utf8();
+            } else if is_close_bracket(next) {
+                depth = depth.saturating_sub(1);
+                end = j + next.len_utf8();
+            } else {
+                break;
+            }
+            chars.next();
+        }
+
+        push_sentence(&mut sentences, &input[start..end]);
+        start = end;
+    }
+
+    push_sentence(&mut sentences, &input[start..]);
+    sentences
+}
+
+/// 文末記号かどうかを判定します（内部ヘルパー関数）。
+fn is_sentence_end(c: char) -> bool {
+    matches!(c, '。' | '｡' | '！' | '!' | '？' | '?')
+}
+
+/// 開き括弧かどうかを判定します（内部ヘルパー関数）。
+fn is_open_bracket(c: char) -> bool {
+    matches!(c, '「' | '『' | '（' | '(' | '【' | '〈' | '《' | '｢')
+}
+
+/// 閉じ括弧かどうかを判定します（内部ヘルパー関数）。
+fn is_close_bracket(c: char) -> bool {
+    matches!(c, '」' | '』' | '）' | ')' | '】' | '〉' | '》' | '｣')
+}
+
+/// 前後の空白を取り除いた文を、空でなければ追加します（内部ヘルパー関数）。
+fn push_sentence(sentences: &mut Vec<String>, sentence: &str) {
+    let sentence = sentence.trim();
+    if !sentence.is_empty() {
+        sentences.push(String::from(sentence));
+    }
+}
+
+/// 文字列をモーラ（拍）単位に分割するイテレータを返します。
+///
+/// 各要素は元の文字列を借用したスライスです。区切り方は次のとおりです。
+///
+/// - かな1文字を1モーラとし、直後の小書きのかな（ゃゅょ ぁぃぅぇぉ ゎ とそのカタカナ・半角カタカナ）は
+///   前のかなと合わせて1モーラにします（`きゃ`、`ファ`、`ティ`）。
+/// - 促音（っ）・撥音（ん）・長音記号（ー）はそれぞれ独立した1モーラです。
+/// - 濁点・半濁点（゛゜、半角の ﾞﾟ、結合用の U+3099, U+309A）は前の文字に含めます。
+/// - 漢字・ASCII・記号などかな以外の文字は、読みがわからないため1文字を1要素として返します。
+///   文字列の先頭などにある前のかなの無い小書きのかなも1要素です。
+///
+/// モーラ数は [`count_morae`] で数えられます。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::morae;
+///
+/// assert_eq!(morae("きゃ").collect::<Vec<_>>(), vec!["きゃ"]);
+/// assert_eq!(morae("がっこう").collect::<Vec<_>>(), vec!["が", "っ", "こ", "う"]);
+/// assert_eq!(morae("ティーカップ").collect::<Vec<_>>(), vec!["ティ", "ー", "カ", "ッ", "プ"]);
+/// ```
+pub fn morae(input: &str) -> impl Iterator<Item = &str> + '_ {
+    let mut rest = input;
+
+    core::iter::from_fn(move || {
+        let mut chars = rest.char_indices();
+        let (_, first) = chars.next()?;
+        let mut end = first.len_utf8();
+        let first_is_kana = is_kana(first) || is_half_width_katakana(first);
+
+        for (i, c) in chars {
+            let attaches = is_voicing_mark(c) || (first_is_kana && is_yoon_small_kana(c));
+            if !attaches {
+                break;
+            }
+            end = i + c.len_utf8();
+        }
+
+        let (mora, remaining) = rest.split_at(end);
+        rest = remaining;
+        Some(mora)
+    })
+}
+
+/// 文字列のモーラ（拍）数を数えます。
+///
+/// [`morae`] の要素数と同じです。かな以外の文字は1文字を1つとして数えます。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::count_morae;
+///
+/// assert_eq!(count_morae("がっこう"), 4);
+/// assert_eq!(count_morae("しゃしん"), 3);
+/// assert_eq!(count_morae("コーヒー"), 4);
+/// ```
+pub fn count_morae(input: &str) -> usize {
+    morae(input).count()
+}
+
+/// 前のかなと合わせて1モーラになる小書きのかなかどうかを判定します（内部ヘルパー関数）。
+fn is_yoon_small_kana(c: char) -> bool {
+    matches!(
+        c,
+        'ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ' | 'ゃ' | 'ゅ' | 'ょ' | 'ゎ'
+            | 'ァ' | 'ィ' | 'ゥ' | 'ェ' | 'ォ' | 'ャ' | 'ュ' | 'ョ' | 'ヮ'
+            | 'ｧ' | 'ｨ' | 'ｩ' | 'ｪ' | 'ｫ' | 'ｬ' | 'ｭ' | 'ｮ'
+    )
+}
+
+/// 濁点・半濁点（全角・半角・結合用）かどうかを判定します（内部ヘルパー関数）。
+fn is_voicing_mark(c: char) -> bool {
+    matches!(c, '゛' | '゜' | 'ﾞ' | 'ﾟ' | '\u{3099}' | '\u{309A}')
+}
+
+/// 全角の括弧・かぎ括弧の対応が取れていない箇所を、位置とともに返します。
+///
+/// 対象は「」『』（）【】〔〕《》〈〉の7組です。括弧の入れ子を考慮し、次の文字を報告します。
+///
+/// - 対応する開き括弧の無い閉じ括弧
+/// - 閉じられないまま残った開き括弧
+/// - 外側の括弧が閉じられたときに、まだ閉じられていなかった内側の開き括弧（`「『」` の `『`）
+///
+/// 位置は入力文字列のバイトオフセットで、昇順に並びます。校正ツールでの検出を想定しており、修正は行いません。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::find_unbalanced_brackets;
+///
+/// assert!(find_unbalanced_brackets("「『引用』と（注）」").is_empty());
+/// assert_eq!(find_unbalanced_brackets("「文章』"), vec![(0, '「'), (9, '』')]);
+/// ```
+pub fn find_unbalanced_brackets(input: &str) -> Vec<(usize, char)> {
+    const PAIRS: [(char, char); 7] = [
+        ('「', '」'),
+        ('『', '』'),
+        ('（', '）'),
+        ('【', '】'),
+        ('〔', '〕'),
+        ('《', '》'),
+        ('〈', '〉'),
+    ];
+
+    let mut unbalanced = Vec::new();
+    let mut stack: Vec<(usize, char)> = Vec::new();
+
+    for (i, c) in input.char_indices() {
+        if PAIRS.iter().any(|&(open, _)| open == c) {
+            stack.push((i, c));
+        } else if let Some(&(open, _)) = PAIRS.iter().find(|&&(_, close)| close == c) {
+            match stack.iter().rposition(|&(_, o)| o == open) {
+                Some(index) => {
+                    unbalanced.extend(stack.drain(index..).skip(1));
+                }
+                None => unbalanced.push((i, c)),
+            }
+        }
+    }
+
+    unbalanced.extend(stack);
+    unbalanced.sort_unstable_by_key(|&(i, _)| i);
+    unbalanced
+}
+
+/// [`validate_kana`] が検出する、かなの表記上の問題の種類です。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IssueKind {
+    /// 語頭の促音（`っ`、`ッ`、`ｯ`）です。
+    LeadingSokuon,
+    /// イ段のかな以外に続く拗音の小書き（`ゃゅょ`、`ャュョ`、`ｬｭｮ`）です。
+    IsolatedYoon,
+    /// 語頭の長音記号（`ー`、`ｰ`）です。
+    LeadingProlonged,
+    /// 直前の長音記号に続く長音記号です。
+    RepeatedProlonged,
+}
+
+/// [`validate_kana`] が検出した問題です。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KanaIssue {
+    /// 問題のある文字の位置（入力文字列のバイトオフセット）です。
+    pub position: usize,
+    /// 問題の種類です。
+    pub kind: IssueKind,
+}
+
+/// 小書きのかなや長音記号が、表記上あり得ない位置に出現していないかを検査します。
+///
+/// 次の問題を、出現位置の昇順で返します。
+///
+/// | 種類 | 例 |
+/// |------|----|
+/// | [`IssueKind::LeadingSokuon`] | `っかい` の `っ` |
+/// | [`IssueKind::IsolatedYoon`] | `かゃ` の `ゃ` |
+/// | [`IssueKind::LeadingProlonged`] | `ーあ` の `ー` |
+/// | [`IssueKind::RepeatedProlonged`] | `あーー` の2つ目の `ー` |
+///
+/// 「語頭」は直前の文字がかな（長音記号を含む）でない位置です。拗音の小書きは、子音を伴うイ段のかな
+/// （`き` `し` `ち` など。`い` `ぃ` `ゐ` は除く）と、外来語の表記に使う `て` `で` `ふ` `ゔ`（`テュ`、`フュ` など）の後を正しい位置とします。
+/// ひらがな・全角カタカナ・半角カタカナが対象で、濁点・半濁点は直前のかなと一体として扱います。
+///
+/// 表記上の妥当性だけを検査し、語として存在するかなどの意味的な正しさは問いません。
+/// 修正は行わないため、フォーム入力の検証などで位置を示す用途を想定しています。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{validate_kana, IssueKind, KanaIssue};
+///
+/// assert!(validate_kana("きゃっと").is_empty());
+/// assert_eq!(
+///     validate_kana("っかゃ"),
+///     vec![
+///         KanaIssue { position: 0, kind: IssueKind::LeadingSokuon },
+///         KanaIssue { position: 6, kind: IssueKind::IsolatedYoon },
+///     ]
+/// );
+/// ```
+pub fn validate_kana(input: &str) -> Vec<KanaIssue> {
+    let mut issues = Vec::new();
+    // 濁点・半濁点を除いた直前の文字（ひらがなに統一したもの）
+    let mut previous: Option<char> = None;
+
+    for (position, c) in input.char_indices() {
+        if is_voicing_mark(c) {
+            continue;
+        }
+        let kana = hiragana_char(full_width_kana_of(c));
+        let previous_is_kana = previous.is_some_and(|p| is_kana(p) || p == 'ー');
+
+        let kind = match kana {
+            'っ' if !previous_is_kana => Some(IssueKind::LeadingSokuon),
+            'ゃ' | 'ゅ' | 'ょ' => {
+                let valid = previous.is_some_and(|p| {
+                    matches!(p, 'て' | 'で' | 'ふ' | 'ゔ')
+                        || (hiragana_vowel(p) == Some('い') && !matches!(p, 'い' | 'ぃ' | 'ゐ'))
+                });
+                (!valid).then_some(IssueKind::IsolatedYoon)
+            }
+            'ー' if previous == Some('ー') => Some(IssueKind::RepeatedProlonged),
+            'ー' if !previous_is_kana => Some(IssueKind::LeadingProlonged),
+            _ => None,
+        };
+        if let Some(kind) = kind {
+            issues.push(KanaIssue { position, kind });
+        }
+        previous = Some(kana);
+    }
+
+    issues
+}
+
+/// 半角カタカナ1文字を全角に変換し、それ以外の文字はそのまま返します（内部ヘルパー関数）。
+fn full_width_kana_of(c: char) -> char {
+    if is_half_width_katakana(c) {
+        HALF_WIDTH_KATAKANA_TABLE[c as usize - 0xFF61]
+    } else {
+        c
+    }
+}
+
+/// カタカナの連続を外来語候補として抽出します。
+///
+/// 長音符（ー）と中黒（・）を含むカタカナの連続を1語として扱います。
+/// 長音符・中黒で始まる部分と、語末の中黒は語に含めません。
+/// 半角カタカナは対象外です。最小長や半角カタカナの扱いを指定する場合は
+/// [`extract_katakana_words_with`] を使用してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::extract_katakana_words;
+///
+/// assert_eq!(
+///     extract_katakana_words("ニューヨーク・タイムズでコーヒーを飲む"),
+///     vec!["ニューヨーク・タイムズ", "コーヒー"]
+/// );
+/// ```
+pub fn extract_katakana_words(input: &str) -> Vec<String> {
+    extract_katakana_words_with(input, KatakanaWordOptions::default())
+}
+
+/// [`extract_katakana_words_with`] の動作を指定するオプションです。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KatakanaWordOptions {
+    /// 抽出する語の最小文字数（長音符・中黒を含む）。
+    /// `2` を指定すると「3ヶ月」の「ヶ」のような1文字のカタカナを除外できます。
+    pub min_len: usize,
+    /// 半角カタカナの連続も抽出する場合は `true`。
+    /// 半角カタカナは全角に変換せず、入力のまま返します。
+    pub include_half_width: bool,
+}
+
+impl Default for KatakanaWordOptions {
+    fn default() -> Self {
+        Self {
+            min_len: 1,
+            include_half_width: false,
+        }
+    }
+}
+
+/// オプションを指定して、カタカナの連続を外来語候補として抽出します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{extract_katakana_words_with, KatakanaWordOptions};
+///
+/// let opts = KatakanaWordOptions {
+///     min_len: 2,
+///     include_half_width: true,
+/// };
+/// assert_eq!(
+///     extract_katakana_words_with("3ヶ月ｺｰﾋｰとケーキ", opts),
+///     vec!["ｺｰﾋｰ", "ケーキ"]
+/// );
+/// ```
+pub fn extract_katakana_words_with(input: &str, opts: KatakanaWordOptions) -> Vec<String> {
+    let mut words = Vec::new();
+    // 現在の語の開始位置と、語に含める最後の文字の終端位置
+    let mut current: Option<(usize, usize)> = None;
+
+    for (i, c) in input.char_indices() {
+        let end = i + c.len_utf8();
+        let is_letter = is_katakana(c)
+            || matches!(c, '\u{30F7}'..='\u{30FA}')
+            || (opts.include_half_width && matches!(c, '\u{FF66}'..='\u{FF6F}' | '\u{FF71}'..='\u{FF9D}'));
+        let is_prolonged = c == 'ー'
+            || (opts.include_half_width && matches!(c, '\u{FF70}' | '\u{FF9E}' | '\u{FF9F}'));
+        let is_dot = c == '・' || (opts.include_half_width && c == '･');
+
+        match current.as_mut() {
+            Some((_, last)) if is_letter || is_prolonged => *last = end,
+            Some(_) if is_dot => {}
+            None if is_letter => current = Some((i, end)),
+            None => {}
+            Some(&mut (start, last)) => {
+                push_katakana_word(&mut words, &input[start..last], opts.min_len);
+                current = if is_letter { Some((i, end)) } else { None };
+            }
+        }
+    }
+
+    if let Some((start, last)) = current {
+        push_katakana_word(&mut words, &input[start..last], opts.min_len);
+    }
+
+    words
+}
+
+/// 最小文字数を満たす語を追加します（内部ヘルパー関数）。
+fn push_katakana_word(words: &mut Vec<String>, word: &str, min_len: usize) {
+    if word.chars().count() >= min_len {
+        words.push(String::from(word));
+    }
+}
+
+/// [`extract_hiragana_with`] などの抽出関数で、対象の文字種に加えて残す記号を指定するオプションです。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExtractOptions {
+    /// 長音符（ー）も残す場合は `true`。
+    pub include_prolonged_sound: bool,
+    /// 中黒（・）も残す場合は `true`。
+    pub include_middle_dot: bool,
+    /// 繰り返し記号も残す場合は `true`。
+    /// ひらがなでは ゝ ゞ、カタカナでは ヽ ヾ、漢字では 々 を対象とします。
+    pub include_iteration_marks: bool,
+}
+
+/// 文字列からひらがなだけを抜き出します。
+///
+/// [`is_hiragana`] を満たす文字を、元の順序のまま連結して返します。
+/// ふりがな欄から仮名だけを取り出す用途などに使います。
+/// 長音符や繰り返し記号も残す場合は [`extract_hiragana_with`] を使用してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::extract_hiragana;
+///
+/// assert_eq!(extract_hiragana("漢字かなカナ混じり"), "かなじり");
+/// ```
+pub fn extract_hiragana(input: &str) -> String {
+    extract_hiragana_with(input, ExtractOptions::default())
+}
+
+/// オプションを指定して、文字列からひらがなだけを抜き出します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{extract_hiragana_with, ExtractOptions};
+///
+/// let opts = ExtractOptions {
+///     include_prolonged_sound: true,
+///     ..ExtractOptions::default()
+/// };
+/// assert_eq!(extract_hiragana_with("らーめん（拉麺）", opts), "らーめん");
+/// ```
+pub fn extract_hiragana_with(input: &str, opts: ExtractOptions) -> String {
+    extract_chars(input, opts, is_hiragana, &['ゝ', 'ゞ'])
+}
+
+/// 文字列から全角カタカナだけを抜き出します。
+///
+/// [`is_katakana`] を満たす文字を、元の順序のまま連結して返します。半角カタカナは対象外です。
+/// 語の区切りを保ったまま抽出する場合は [`extract_katakana_words`] を使用してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::extract_katakana;
+///
+/// assert_eq!(extract_katakana("カタカナとひらがな"), "カタカナ");
+/// ```
+pub fn extract_katakana(input: &str) -> String {
+    extract_katakana_with(input, ExtractOptions::default())
+}
+
+/// オプションを指定して、文字列から全角カタカナだけを抜き出します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{extract_katakana_with, ExtractOptions};
+///
+/// let opts = ExtractOptions {
+///     include_prolonged_sound: true,
+///     include_middle_dot: true,
+///     ..ExtractOptions::default()
+/// };
+/// assert_eq!(extract_katakana_with("ジョン・スミスさん", opts), "ジョン・スミス");
+/// ```
+pub fn extract_katakana_with(input: &str, opts: ExtractOptions) -> String {
+    extract_chars(input, opts, is_katakana, &['ヽ', 'ヾ'])
+}
+
+/// 文字列から漢字だけを抜き出します。
+///
+/// [`is_kanji`] を満たす文字を、元の順序のまま連結して返します。難読語リストの作成などに使います。
+/// 踊り字（々）も残す場合は [`extract_kanji_with`] で `include_iteration_marks` を指定してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::extract_kanji;
+///
+/// assert_eq!(extract_kanji("東京都に住む"), "東京都住");
+/// ```
+pub fn extract_kanji(input: &str) -> String {
+    extract_kanji_with(input, ExtractOptions::default())
+}
+
+/// オプションを指定して、文字列から漢字だけを抜き出します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{extract_kanji_with, ExtractOptions};
+///
+/// let opts = ExtractOptions {
+///     include_iteration_marks: true,
+///     ..ExtractOptions::default()
+/// };
+/// assert_eq!(extract_kanji_with("佐々木さん", opts), "佐々木");
+/// ```
+pub fn extract_kanji_with(input: &str, opts: ExtractOptions) -> String {
+    extract_chars(input, opts, is_kanji, &['々'])
+}
+
+/// 条件を満たす文字とオプションで指定した記号だけを残します（内部ヘルパー関数）。
+fn extract_chars(
+    input: &str,
+    opts: ExtractOptions,
+    predicate: impl Fn(char) -> bool,
+    iteration_marks: &[char],
+) -> String {
+    let mut result = String::from(input);
+    result.retain(|c| {
+        predicate(c)
+            || (opts.include_prolonged_sound && c == 'ー')
+            || (opts.include_middle_dot && c == '・')
+            || (opts.include_iteration_marks && iteration_marks.contains(&c))
+    });
+    result
+}
+
+/// 検索インデックス用に、表記ゆれをまとめて正規化します。
+///
+/// 以下の変換を、この順で適用します。
+///
+/// 1. 全角ASCII → 半角（[`to_half_width`]）
+/// 2. 半角カタカナ → 全角カタカナ（[`half_width_katakana_to_full_width`]）
+/// 3. カタカナ → ひらがな（[`to_hiragana`]）
+/// 4. 空白の正規化・連続空白の畳み込み（[`normalize_whitespace`]）
+/// 5. 長音記号・波ダッシュの統一（[`normalize_prolonged_sound`]）
+/// 6. 繰り返し記号の展開（[`expand_iteration_marks`]）
+///
+/// 全角チルダ（～, U+FF5E）は手順1で半角チルダ（~）になるため、長音記号には統一されません。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::normalize_for_search;
+///
+/// assert_eq!(normalize_for_search("ｺｰﾋｰ"), normalize_for_search("コーヒー"));
+/// assert_eq!(normalize_for_search("ＡＢＣ　　テスト"), "ABC てすと");
+/// ```
+pub fn normalize_for_search(input: &str) -> String {
+    let text = to_half_width(input);
+    let text = half_width_katakana_to_full_width(&text);
+    let text = to_hiragana(&text);
+    let text = normalize_whitespace(&text);
+    let text = normalize_prolonged_sound(&text);
+    expand_iteration_marks(&text)
+}
+
+/// [`normalize_with_report`] で、変換の種類ごとに変換された文字数を集計した結果です。
+///
+/// 各カウンタは「変換（または削除）された入力側の文字数」です。ただし半角カタカナは、
+/// 濁点・半濁点と結合した組を変換後の1文字として数えます。
+/// 別の正規化の結果と `+` で足し合わせて、複数の処理の集計にも使えます。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NormalizationReport {
+    /// 全角ASCII・全角スペースを半角にした文字数
+    pub fullwidth_to_halfwidth: usize,
+    /// 半角カタカナから変換した全角の文字数（`ｶﾞ` → `ガ` は1文字と数える）
+    pub halfwidth_katakana_to_fullwidth: usize,
+    /// ひらがなにしたカタカナの文字数
+    pub katakana_to_hiragana: usize,
+    /// 半角スペース以外から置き換えた空白と、畳み込み・前後の除去で取り除いた空白の文字数
+    pub whitespace: usize,
+    /// 長音記号に統一した波ダッシュ・チルダの文字数
+    pub prolonged_sound: usize,
+    /// 展開した繰り返し記号の文字数
+    pub iteration_marks: usize,
+}
+
+impl NormalizationReport {
+    /// すべてのカウンタの合計を返します。
+    pub fn total(&self) -> usize {
+        self.fullwidth_to_halfwidth
+            + self.halfwidth_katakana_to_fullwidth
+            + self.katakana_to_hiragana
+            + self.whitespace
+            + self.prolonged_sound
+            + self.iteration_marks
+    }
+}
+
+impl core::ops::Add for NormalizationReport {
+    type Output = NormalizationReport;
+
+    fn add(self, rhs: NormalizationReport) -> NormalizationReport {
+        NormalizationReport {
+            fullwidth_to_halfwidth: self.fullwidth_to_halfwidth + rhs.fullwidth_to_halfwidth,
+            halfwidth_katakana_to_fullwidth: self.halfwidth_katakana_to_fullwidth
+                + rhs.halfwidth_katakana_to_fullwidth,
+            katakana_to_hiragana: self.katakana_to_hiragana + rhs.katakana_to_hiragana,
+            whitespace: self.whitespace + rhs.whitespace,
+            prolonged_sound: self.prolonged_sound + rhs.prolonged_sound,
+            iteration_marks: self.iteration_marks + rhs.iteration_marks,
+        }
+    }
+}
+
+/// [`normalize_for_search`] と同じ正規化を行い、変換の種類ごとの文字数を併せて返します。
+///
+/// データクレンジングのログに「今回の正規化で全角を何文字直したか」などを記録する用途を想定しています。
+/// 正規化後の文字列は [`normalize_for_search`] の結果と常に一致します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::normalize_with_report;
+///
+/// let (text, report) = normalize_with_report("ＡＢＣ　ｺｰﾋｰ");
+/// assert_eq!(text, "ABC こーひー");
+/// assert_eq!(report.fullwidth_to_halfwidth, 4);
+/// assert_eq!(report.halfwidth_katakana_to_fullwidth, 4);
+/// assert_eq!(report.katakana_to_hiragana, 2);
+/// ```
+pub fn normalize_with_report(input: &str) -> (String, NormalizationReport) {
+    let mut report = NormalizationReport::default();
+
+    let text = to_half_width(input);
+    report.fullwidth_to_halfwidth = count_changed_chars(input, &text);
+
+    let converted = half_width_katakana_to_full_width(&text);
+    let count_half_width = |s: &str| s.chars().filter(|&c| is_half_width_katakana(c)).count();
+    // 変換された半角カタカナの数から、濁点・半濁点の結合で減った文字数を引く
+    report.halfwidth_katakana_to_fullwidth = count_half_width(&text) - count_half_width(&converted)
+        - (text.chars().count() - converted.chars().count());
+    let text = converted;
+
+    let converted = to_hiragana(&text);
+    report.katakana_to_hiragana = count_changed_chars(&text, &converted);
+    let text = converted;
+
+    report.whitespace = count_normalized_whitespace(&text);
+    let text = normalize_whitespace(&text);
+
+    let converted = normalize_prolonged_sound(&text);
+    report.prolonged_sound = count_changed_chars(&text, &converted);
+    let text = converted;
+
+    let converted = expand_iteration_marks(&text);
+    report.iteration_marks = count_changed_chars(&text, &converted);
+
+    (converted, report)
+}
+
+/// 1文字ずつ対応する変換の前後で、異なる文字の数を数えます（内部ヘルパー関数）。
+fn count_changed_chars(before: &str, after: &str) -> usize {
+    before
+        .chars()
+        .zip(after.chars())
+        .filter(|(b, a)| b != a)
+        .count()
+}
+
+/// [`normalize_whitespace`] で置き換え・削除される空白の数を数えます（内部ヘルパー関数）。
+fn count_normalized_whitespace(text: &str) -> usize {
+    let is_space = |c: char| c.is_whitespace() || c == '　';
+    let chars: Vec<char> = text.chars().collect();
+    let mut count = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        if !is_space(chars[i]) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && is_space(chars[i]) {
+            i += 1;
+        }
+        let run = i - start;
+        if start == 0 || i == chars.len() {
+            // 先頭・末尾の空白はすべて取り除かれる
+            count += run;
+        } else {
+            // 連続の先頭だけが半角スペースとして残る
+            count += run - 1 + usize::from(chars[start] != ' ');
+        }
+    }
+
+    count
+}
+
+/// 文字列変換を値として表す列挙型です。
+///
+/// [`apply`] / [`apply_all`] で適用します。[`FromStr`](core::str::FromStr) を実装しているため、
+/// 設定ファイルに書いた変換名（`"HalfWidth"` など、バリアント名そのもの）から作れます。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Transform {
+    /// [`to_half_width`]
+    HalfWidth,
+    /// [`to_full_width`]
+    FullWidth,
+    /// [`to_hiragana`]
+    ToHiragana,
+    /// [`to_katakana`]
+    ToKatakana,
+    /// [`half_width_katakana_to_full_width`]
+    HalfWidthKatakanaToFullWidth,
+    /// [`to_upper_wide`]
+    ToUpperWide,
+    /// [`to_lower_wide`]
+    ToLowerWide,
+    /// [`normalize_whitespace`]
+    NormalizeWhitespace,
+    /// [`normalize_ideographic_space`]
+    NormalizeIdeographicSpace,
+    /// [`normalize_prolonged_sound`]
+    NormalizeProlongedSound,
+    /// [`normalize_hyphens`]
+    NormalizeHyphens,
+    /// [`expand_iteration_marks`]
+    ExpandIterationMarks,
+    /// [`strip_emoji`]
+    StripEmoji,
+}
+
+impl Transform {
+    /// すべての変換の一覧です。
+    pub const ALL: [Transform; 13] = [
+        Transform::HalfWidth,
+        Transform::FullWidth,
+        Transform::ToHiragana,
+        Transform::ToKatakana,
+        Transform::HalfWidthKatakanaToFullWidth,
+        Transform::ToUpperWide,
+        Transform::ToLowerWide,
+        Transform::NormalizeWhitespace,
+        Transform::NormalizeIdeographicSpace,
+        Transform::NormalizeProlongedSound,
+        Transform::NormalizeHyphens,
+        Transform::ExpandIterationMarks,
+        Transform::StripEmoji,
+    ];
+
+    /// 変換名（バリアント名）を返します。[`FromStr`](core::str::FromStr) で読み戻せます。
+    pub fn name(self) -> &'static str {
+        match self {
+            Transform::HalfWidth => "HalfWidth",
+            Transform::FullWidth => "FullWidth",
+            Transform::ToHiragana => "ToHiragana",
+            Transform::ToKatakana => "ToKatakana",
+            Transform::HalfWidthKatakanaToFullWidth => "HalfWidthKatakanaToFullWidth",
+            Transform::ToUpperWide => "ToUpperWide",
+            Transform::ToLowerWide => "ToLowerWide",
+            Transform::NormalizeWhitespace => "NormalizeWhitespace",
+            Transform::NormalizeIdeographicSpace => "NormalizeIdeographicSpace",
+            Transform::NormalizeProlongedSound => "NormalizeProlongedSound",
+            Transform::NormalizeHyphens => "NormalizeHyphens",
+            Transform::ExpandIterationMarks => "ExpandIterationMarks",
+            Transform::StripEmoji => "StripEmoji",
+        }
+    }
+}
+
+impl core::fmt::Display for Transform {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// 変換名のパースに失敗したことを表すエラーです。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTransformError {
+    name: String,
+}
+
+impl core::fmt::Display for ParseTransformError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "unknown transform name: {:?}", self.name)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseTransformError {}
+
+impl core::str::FromStr for Transform {
+    type Err = ParseTransformError;
+
+    /// 変換名（バリアント名、大文字・小文字を区別）から変換を作ります。
+    ///
+    /// # 使用例
+    ///
+    /// ```
+    /// use japanese_text::Transform;
+    ///
+    /// assert_eq!("HalfWidth".parse(), Ok(Transform::HalfWidth));
+    /// assert!("Unknown".parse::<Transform>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Transform::ALL
+            .into_iter()
+            .find(|transform| transform.name() == s)
+            .ok_or_else(|| ParseTransformError { name: String::from(s) })
+    }
+}
+
+/// 変換を1つ適用します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{apply, Transform};
+///
+/// assert_eq!(apply(Transform::ToHiragana, "カタカナ"), "かたかな");
+/// ```
+pub fn apply(transform: Transform, input: &str) -> String {
+    match transform {
+        Transform::HalfWidth => to_half_width(input),
+        Transform::FullWidth => to_full_width(input),
+        Transform::ToHiragana => to_hiragana(input),
+        Transform::ToKatakana => to_katakana(input),
+        Transform::HalfWidthKatakanaToFullWidth => half_width_katakana_to_full_width(input),
+        Transform::ToUpperWide => to_upper_wide(input),
+        Transform::ToLowerWide => to_lower_wide(input),
+        Transform::NormalizeWhitespace => normalize_whitespace(input),
+        Transform::NormalizeIdeographicSpace => normalize_ideographic_space(input),
+        Transform::NormalizeProlongedSound => normalize_prolonged_sound(input),
+        Transform::NormalizeHyphens => normalize_hyphens(input),
+        Transform::ExpandIterationMarks => expand_iteration_marks(input),
+        Transform::StripEmoji => strip_emoji(input),
+    }
+}
+
+/// 変換を先頭から順に適用します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{apply_all, Transform};
+///
+/// let transforms: Vec<Transform> = ["HalfWidth", "ToHiragana"]
+///     .iter()
+///     .map(|name| name.parse().unwrap())
+///     .collect();
+/// assert_eq!(apply_all(&transforms, "ＡＢＣカタカナ"), "ABCかたかな");
+/// ```
+pub fn apply_all(transforms: &[Transform], input: &str) -> String {
+    let mut text = String::from(input);
+    for &transform in transforms {
+        text = apply(transform, &text);
+    }
+    text
+}
+
+/// 文字列型に変換をメソッドとして適用するトレイトです。
+///
+/// `AsRef<str>` を実装するすべての型（`str`、`String`、`Cow<str>`、`Box<str>` など）に実装されているため、
+/// 文字列の型を問わないジェネリックなパイプラインを組めます。
+/// 変換の種別は [`Transform`] で指定し、結果は [`apply`] / [`apply_all`] と同じです。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{Normalize, Transform};
+///
+/// assert_eq!("ＡＢＣ".normalize(Transform::HalfWidth), "ABC");
+///
+/// fn to_search_text<T: Normalize>(input: T) -> String {
+///     input.normalize_all(&[Transform::HalfWidth, Transform::ToHiragana])
+/// }
+/// assert_eq!(to_search_text(String::from("ＡＢＣカナ")), "ABCかな");
+/// ```
+pub trait Normalize {
+    /// 変換を1つ適用します。
+    fn normalize(&self, transform: Transform) -> String;
+
+    /// 変換を先頭から順に適用します。
+    fn normalize_all(&self, transforms: &[Transform]) -> String;
+}
+
+impl<T: AsRef<str> + ?Sized> Normalize for T {
+    fn normalize(&self, transform: Transform) -> String {
+        apply(transform, self.as_ref())
+    }
+
+    fn normalize_all(&self, transforms: &[Transform]) -> String {
+        apply_all(transforms, self.as_ref())
+    }
+}
+
+/// 変換を1つ適用し、変換後の各バイトが元の文字列のどの位置から来たかを返します。
+///
+/// 戻り値の `Vec<usize>` は**バイト単位**の対応表で、長さは変換後の文字列のバイト長 + 1 です。
+/// `mapping[i]` は変換後の `i` バイト目を生成した元の文字（または文字のまとまり）の先頭の**バイトオフセット**、
+/// 末尾の要素は元の文字列のバイト長です。変換後の範囲 `start..end` は、
+/// 元の文字列の `mapping[start]..mapping[end]` に対応します（いずれも文字境界になります）。
+///
+/// 半角カナ → 全角のように長さが変わる変換でも、変換後のすべてのバイトが元の位置を持ちます。
+/// 半角カタカナと後続の濁点・半濁点（`ｶﾞ` → `ガ`）のように複数の文字から1文字が作られる場合は、
+/// 先頭の文字の位置に対応付けます。[`normalize_whitespace`] で連続する空白が1つにまとめられた場合は、
+/// 最初の空白の位置に対応付けます。取り除かれた文字（絵文字や前後の空白）に対応する位置はありません。
+///
+/// 変換結果は [`apply`] と同じです。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{normalize_with_mapping, Transform};
+///
+/// let input = "ｶﾞｲﾄﾞ ABC";
+/// let (output, mapping) = normalize_with_mapping(input, Transform::HalfWidthKatakanaToFullWidth);
+/// assert_eq!(output, "ガイド ABC");
+///
+/// // 変換後の "ABC" を元の文字列の位置に戻す
+/// let start = output.find("ABC").unwrap();
+/// let end = start + "ABC".len();
+/// assert_eq!(&input[mapping[start]..mapping[end]], "ABC");
+/// ```
+pub fn normalize_with_mapping(input: &str, transform: Transform) -> (String, Vec<usize>) {
+    let mut output = String::with_capacity(input.len());
+    let mut mapping = Vec::with_capacity(input.len() + 1);
+
+    let mut push = |offset: usize, text: &str| {
+        output.push_str(text);
+        mapping.extend(core::iter::repeat_n(offset, text.len()));
+    };
+
+    match transform {
+        Transform::HalfWidthKatakanaToFullWidth => {
+            // 濁点・半濁点は直前の文字と合わせて1つのまとまりとして変換する
+            let mut chars = input.char_indices().peekable();
+            while let Some((offset, c)) = chars.next() {
+                let mut end = offset + c.len_utf8();
+                if is_half_width_katakana(c) {
+                    if let Some(&(mark, m @ ('ﾞ' | 'ﾟ' | '\u{3099}' | '\u{309A}'))) = chars.peek() {
+                        end = mark + m.len_utf8();
+                        chars.next();
+                    }
+                }
+                push(offset, &apply(transform, &input[offset..end]));
+            }
+        }
+        Transform::ExpandIterationMarks => {
+            // 繰り返し記号の展開は直前の文字に依存するが、文字数は変わらない
+            let expanded = expand_iteration_marks(input);
+            let mut buf = [0; 4];
+            for ((offset, _), c) in input.char_indices().zip(expanded.chars()) {
+                push(offset, c.encode_utf8(&mut buf));
+            }
+        }
+        Transform::NormalizeWhitespace => {
+            let mut pending = None;
+            let mut started = false;
+            let mut buf = [0; 4];
+            for (offset, c) in input.char_indices() {
+                if c.is_whitespace() {
+                    if started && pending.is_none() {
+                        pending = Some(offset);
+                    }
+                    continue;
+                }
+                if let Some(space) = pending.take() {
+                    push(space, " ");
+                }
+                push(offset, c.encode_utf8(&mut buf));
+                started = true;
+            }
+        }
+        Transform::StripEmoji => {
+            let mut buf = [0; 4];
+            for (offset, c) in non_emoji_char_indices(input) {
+                push(offset, c.encode_utf8(&mut buf));
+            }
+        }
+        // 残りの変換は1文字ごとに独立している
+        _ => {
+            let mut buf = [0; 4];
+            for (offset, c) in input.char_indices() {
+                push(offset, &apply(transform, c.encode_utf8(&mut buf)));
+            }
+        }
+    }
+
+    mapping.push(input.len());
+    (output, mapping)
+}
+
+/// 五十音順に並べ替えるためのソートキーを生成します。
+///
+/// 単純なコードポイント比較では、濁音・半濁音や小書きのかなが清音と離れた位置に並んでしまいます。
+/// この関数は以下の正規化を順に適用し、読みが自然な順序で並ぶキーを返します。
+///
+/// 1. 半角カタカナ → 全角カタカナ
+/// 2. カタカナ → ひらがな（長音記号は直前の母音に展開）
+/// 3. 濁音・半濁音 → 清音（例: `が` → `か`、`ぱ` → `は`）
+/// 4. 小書きのかな → 通常のかな（例: `ゃ` → `や`、`っ` → `つ`）
+///
+/// キーは並べ替えにのみ使い、表示には元の文字列を使用してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::reading_sort_key;
+///
+/// assert_eq!(reading_sort_key("ガッコー"), "かつこう");
+///
+/// let mut words = vec!["はし", "ばら", "ぱん", "はな"];
+/// words.sort_by_key(|w| reading_sort_key(w));
+/// assert_eq!(words, vec!["はし", "はな", "ばら", "ぱん"]);
+/// ```
+pub fn reading_sort_key(input: &str) -> String {
+    let text = half_width_katakana_to_full_width(input);
+    let text = to_hiragana_with(
+        &text,
+        HiraganaOptions {
+            prolonged: ProlongedPolicy::ToVowel,
+            ..Default::default()
+        },
+    );
+    text.chars()
+        .map(|c| to_large_kana(remove_voicing(c)))
+        .collect()
+}
+
+/// 日本語辞書順（JIS X 4061 風）に並べ替えるための比較用キーを生成します。
+///
+/// キーは以下の3段階の比較順位を1つの文字列に連結したもので、キー同士を `Ord` で比較すると
+/// 辞書順に近い並びになります。
+///
+/// 1. **読み**: かなをひらがなに統一し、濁音・半濁音を清音に、小書きのかなを通常のかなに、
+///    長音記号を直前の母音（お段は「う」）に置き換えたもの（[`reading_sort_key`] と同じ）
+/// 2. **濁点**: 読みが同じ場合は、清音 → 濁音 → 半濁音 の順（`はは` < `はば` < `はぱ`）
+/// 3. **大きさ・長音**: それも同じ場合は、小書き → 通常 → 長音記号 の順（`やっつ` < `やつつ`、`かあ` < `かー`）
+///
+/// 結合用の濁点・半濁点（U+3099, U+309A）は直前の文字の濁点として扱うため、
+/// NFD で分解された入力も合成済みの入力と同じキーになります。
+/// ひらがなとカタカナは区別しません。
+///
+/// 各段階は U+0000 で区切られます。キーは比較専用で、表示には元の文字列を使用してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::sort_key;
+///
+/// let mut words = vec!["ぱん", "ハン", "ばん", "はれ", "ばら"];
+/// words.sort_by_key(|w| sort_key(w));
+/// assert_eq!(words, vec!["ばら", "はれ", "ハン", "ばん", "ぱん"]);
+///
+/// assert_eq!(sort_key("が"), sort_key("か\u{3099}"));
+/// assert!(sort_key("ヴァイオリン") < sort_key("バイオリン"));
+/// ```
+pub fn sort_key(input: &str) -> String {
+    let text = half_width_katakana_to_full_width(input);
+    let mut reading = String::with_capacity(text.len());
+    let mut voicing: Vec<char> = Vec::new();
+    let mut size: Vec<char> = Vec::new();
+    let mut last_vowel: Option<char> = None;
+
+    for c in text.chars() {
+        match c {
+            '\u{3099}' | '\u{309A}' => {
+                if let Some(last) = voicing.last_mut() {
+                    *last = if c == '\u{3099}' { '1' } else { '2' };
+                }
+                continue;
+            }
+            'ー' => {
+                let vowel = match last_vowel {
+                    Some('お') => 'う',
+                    Some(v) => v,
+                    None => c,
+                };
+                reading.push(vowel);
+                voicing.push('0');
+                size.push('2');
+                continue;
+            }
+            _ => {}
+        }
+
+        let c = hiragana_char(c);
+        let base = remove_voicing(c);
+        let large = to_large_kana(base);
+
+        reading.push(large);
+        voicing.push(match c {
+            _ if base == c => '0',
+            'ぱ' | 'ぴ' | 'ぷ' | 'ぺ' | 'ぽ' => '2',
+            _ => '1',
+        });
+        size.push(if large == base { '1' } else { '0' });
+        last_vowel = hiragana_vowel(c);
+    }
+
+    let mut key = reading;
+    key.push('\u{0}');
+    key.extend(voicing);
+    key.push('\u{0}');
+    key.extend(size);
+    key
+}
+
+/// 幅・大文字小文字・ひらがなカタカナの違いを無視して比較するためのキーを生成します。
+///
+/// 以下の変換を、この順で適用します。
+///
+/// 1. 全角ASCII → 半角（[`to_half_width`]）
+/// 2. 半角カタカナ → 全角カタカナ（[`half_width_katakana_to_full_width`]）
+/// 3. カタカナ → ひらがな（[`to_hiragana`]）
+/// 4. 大文字 → 小文字（[`str::to_lowercase`]）
+/// 5. 空白の正規化・連続空白の畳み込み（[`normalize_whitespace`]）
+///
+/// キーは比較専用です。並べ替えでは元の文字列を残したまま、
+/// [`slice::sort_by_cached_key`] などでキーだけを別に持たせてください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::normalize_for_compare;
+///
+/// assert_eq!(normalize_for_compare("Ａpple"), normalize_for_compare("apple"));
+/// assert_eq!(normalize_for_compare("ﾃｽﾄ　　ＤＡＴＡ"), "てすと data");
+///
+/// let mut files = vec!["banana", "Ａpple", "Cherry"];
+/// files.sort_by_cached_key(|name| normalize_for_compare(name));
+/// assert_eq!(files, vec!["Ａpple", "banana", "Cherry"]);
+/// ```
+pub fn normalize_for_compare(input: &str) -> String {
+    let text = to_half_width(input);
+    let text = half_width_katakana_to_full_width(&text);
+    let text = to_hiragana(&text).to_lowercase();
+    normalize_whitespace(&text)
+}
+
+/// 五十音の行を表す列挙型です。
+///
+/// [`kana_group`] と [`heading_group`] の戻り値として使われます。
+/// 五十音順（あ行 → わ行）で比較できます。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum KanaGroup {
+    /// あ行（ヴを含む）
+    A,
+    /// か行（ゕ ゖ を含む）
+    Ka,
+    /// さ行
+    Sa,
+    /// た行
+    Ta,
+    /// な行
+    Na,
+    /// は行
+    Ha,
+    /// ま行
+    Ma,
+    /// や行
+    Ya,
+    /// ら行
+    Ra,
+    /// わ行（ゐ ゑ を ん を含む）
+    Wa,
+}
+
+/// かなが属する五十音の行を返します。
+///
+/// ひらがな・カタカナ・半角カタカナを対象とし、濁音・半濁音は清音の行（`が` → か行、`ぱ` → は行）、
+/// 小書きのかなは通常のかなの行（`ゃ` → や行、`っ` → た行）に分類します。
+/// 「ん」はわ行に含めます。かな以外の文字や、長音記号・繰り返し記号には `None` を返します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{kana_group, KanaGroup};
+///
+/// assert_eq!(kana_group('が'), Some(KanaGroup::Ka));
+/// assert_eq!(kana_group('パ'), Some(KanaGroup::Ha));
+/// assert_eq!(kana_group('ｬ'), Some(KanaGroup::Ya));
+/// assert_eq!(kana_group('漢'), None);
+/// ```
+pub fn kana_group(c: char) -> Option<KanaGroup> {
+    let c = if is_half_width_katakana(c) {
+        HALF_WIDTH_KATAKANA_TABLE[c as usize - 0xFF61]
+    } else {
+        c
+    };
+
+    let group = match to_large_kana(remove_voicing(hiragana_char(c))) {
+        'あ' | 'い' | 'う' | 'え' | 'お' => KanaGroup::A,
+        'か' | 'き' | 'く' | 'け' | 'こ' => KanaGroup::Ka,
+        'さ' | 'し' | 'す' | 'せ' | 'そ' => KanaGroup::Sa,
+        'た' | 'ち' | 'つ' | 'て' | 'と' => KanaGroup::Ta,
+        'な' | 'に' | 'ぬ' | 'ね' | 'の' => KanaGroup::Na,
+        'は' | 'ひ' | 'ふ' | 'へ' | 'ほ' => KanaGroup::Ha,
+        'ま' | 'み' | 'む' | 'め' | 'も' => KanaGroup::Ma,
+        'や' | 'ゆ' | 'よ' => KanaGroup::Ya,
+        'ら' | 'り' | 'る' | 'れ' | 'ろ' => KanaGroup::Ra,
+        'わ' | 'ゐ' | 'ゑ' | 'を' | 'ん' | 'ヷ' | 'ヸ' | 'ヹ' | 'ヺ' => KanaGroup::Wa,
+        _ => return None,
+    };
+    Some(group)
+}
+
+/// 文字列の先頭の文字から、見出しに使う五十音の行を返します。
+///
+/// 名簿の「あ行・か行…」のタブ分けなどに使います。先頭の文字がかなでない場合や、
+/// 空文字列の場合は `None` を返します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{heading_group, KanaGroup};
+///
+/// assert_eq!(heading_group("さとう"), Some(KanaGroup::Sa));
+/// assert_eq!(heading_group("ガーデン"), Some(KanaGroup::Ka));
+/// assert_eq!(heading_group("佐藤"), None);
+/// ```
+pub fn heading_group(input: &str) -> Option<KanaGroup> {
+    input.chars().next().and_then(kana_group)
+}
+
+/// 五十音の段（母音）を表す列挙型です。
+///
+/// 五十音順（あ段 → お段）で比較できます。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Vowel {
+    /// あ段
+    A,
+    /// い段
+    I,
+    /// う段
+    U,
+    /// え段
+    E,
+    /// お段
+    O,
+}
+
+/// かな1文字を五十音の行と段に分解した結果です。
+///
+/// [`kana_to_gojuon`] の戻り値として使われます。濁音・半濁音は清音の行と段に
+/// [`Voicing`] を組み合わせて、小書きのかなは通常のかなの行と段に `small` を組み合わせて表します。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Gojuon {
+    /// 行（「が」「ぱ」は清音の行、「ゔ」はあ行、「ゐ」「ゑ」「を」はわ行）
+    pub row: KanaGroup,
+    /// 段
+    pub vowel: Vowel,
+    /// 清音・濁音・半濁音の区別
+    pub voicing: Voicing,
+    /// 小書きのかな（ぁ、っ、ゃ、ゎ、ゕ など）かどうか
+    pub small: bool,
+}
+
+/// かな1文字を五十音の行と段に分解します。
+///
+/// ひらがな・カタカナ・半角カタカナを対象とします。
+///
+/// - 濁音・半濁音は清音の行と段に `voicing` を付けて表します（`ぱ` → は行・あ段・[`Voicing::SemiVoiced`]）。
+/// - 小書きのかなは通常のかなの行と段に `small: true` を付けて表します（`っ` → た行・う段、`ゃ` → や行・あ段）。
+/// - 拗音（きゃ）は2文字として、それぞれを分解してください。
+/// - 段を持たない「ん」と、長音記号・繰り返し記号・かな以外の文字には `None` を返します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{kana_to_gojuon, Gojuon, KanaGroup, Voicing, Vowel};
+///
+/// assert_eq!(
+///     kana_to_gojuon('ぱ'),
+///     Some(Gojuon { row: KanaGroup::Ha, vowel: Vowel::A, voicing: Voicing::SemiVoiced, small: false })
+/// );
+/// assert_eq!(kana_to_gojuon('ッ').map(|g| (g.row, g.vowel, g.small)), Some((KanaGroup::Ta, Vowel::U, true)));
+/// assert_eq!(kana_to_gojuon('ん'), None);
+/// assert_eq!(kana_to_gojuon('ー'), None);
+/// ```
+pub fn kana_to_gojuon(c: char) -> Option<Gojuon> {
+    let c = if is_half_width_katakana(c) {
+        HALF_WIDTH_KATAKANA_TABLE[c as usize - 0xFF61]
+    } else {
+        c
+    };
+
+    let voicing = kana_voicing(c)?;
+    let base = hiragana_char(voicing_entry(c).map_or(c, |(base, _, _)| base));
+    let large = to_large_kana(base);
+    let row = kana_group(large)?;
+    let vowel = match hiragana_vowel(large)? {
+        'あ' => Vowel::A,
+        'い' => Vowel::I,
+        'う' => Vowel::U,
+        'え' => Vowel::E,
+        _ => Vowel::O,
+    };
+
+    Some(Gojuon {
+        row,
+        vowel,
+        voicing,
+        small: large != base,
+    })
+}
+
+/// 五十音の行と段からかな1文字を組み立てます。
+///
+/// [`kana_to_gojuon`] の逆変換です。`form` でひらがな・全角カタカナのどちらで出力するかを指定します。
+/// 存在しない組み合わせ（や行のい段・え段、濁音の無い行の濁音、小書きの無いかななど）には `None` を返します。
+/// わ行の濁音（ヷ ヸ ヹ ヺ）はカタカナにのみ存在します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{gojuon_to_kana, Gojuon, KanaForm, KanaGroup, Voicing, Vowel};
+///
+/// // 「書く」の活用語尾（か行の各段）を生成する
+/// let endings: String = [Vowel::A, Vowel::I, Vowel::U, Vowel::E, Vowel::O]
+///     .iter()
+///     .filter_map(|&vowel| {
+///         let g = Gojuon { row: KanaGroup::Ka, vowel, voicing: Voicing::Plain, small: false };
+///         gojuon_to_kana(&g, KanaForm::Hiragana)
+///     })
+///     .collect();
+/// assert_eq!(endings, "かきくけこ");
+///
+/// let yi = Gojuon { row: KanaGroup::Ya, vowel: Vowel::I, voicing: Voicing::Plain, small: false };
+/// assert_eq!(gojuon_to_kana(&yi, KanaForm::Hiragana), None);
+/// ```
+pub fn gojuon_to_kana(g: &Gojuon, form: KanaForm) -> Option<char> {
+    let column = match g.vowel {
+        Vowel::A => 0,
+        Vowel::I => 1,
+        Vowel::U => 2,
+        Vowel::E => 3,
+        Vowel::O => 4,
+    };
+    let row: [Option<char>; 5] = match g.row {
+        KanaGroup::A => [Some('あ'), Some('い'), Some('う'), Some('え'), Some('お')],
+        KanaGroup::Ka => [Some('か'), Some('き'), Some('く'), Some('け'), Some('こ')],
+        KanaGroup::Sa => [Some('さ'), Some('し'), Some('す'), Some('せ'), Some('そ')],
+        KanaGroup::Ta => [Some('た'), Some('ち'), Some('つ'), Some('て'), Some('と')],
+        KanaGroup::Na => [Some('な'), Some('に'), Some('ぬ'), Some('ね'), Some('の')],
+        KanaGroup::Ha => [Some('は'), Some('ひ'), Some('ふ'), Some('へ'), Some('ほ')],
+        KanaGroup::Ma => [Some('ま'), Some('み'), Some('む'), Some('め'), Some('も')],
+        KanaGroup::Ya => [Some('や'), None, Some('ゆ'), None, Some('よ')],
+        KanaGroup::Ra => [Some('ら'), Some('り'), Some('る'), Some('れ'), Some('ろ')],
+        KanaGroup::Wa => [Some('わ'), Some('ゐ'), None, Some('ゑ'), Some('を')],
+    };
+
+    let mut c = row[column]?;
+    if g.small {
+        c = to_small_kana(c)?;
+    }
+    if form == KanaForm::Katakana {
+        c = katakana_char(c);
+    }
+
+    match g.voicing {
+        Voicing::Plain => Some(c),
+        Voicing::Voiced => compose_voiced_mark(c, '\u{3099}'),
+        Voicing::SemiVoiced => compose_voiced_mark(c, '\u{309A}'),
+    }
+}
+
+/// ひらがなを小書きのかなに変換します。小書きの無いかなは `None` を返します（内部ヘルパー関数）。
+fn to_small_kana(c: char) -> Option<char> {
+    let small = match c {
+        'あ' => 'ぁ', 'い' => 'ぃ', 'う' => 'ぅ', 'え' => 'ぇ', 'お' => 'ぉ',
+        'つ' => 'っ', 'や' => 'ゃ', 'ゆ' => 'ゅ', 'よ' => 'ょ', 'わ' => 'ゎ',
+        'か' => 'ゕ', 'け' => 'ゖ',
+        _ => return None,
+    };
+    Some(small)
+}
+
+/// [`apply_rendaku_with`] の動作を指定するオプションです。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RendakuOptions {
+    /// ライマンの法則を適用する場合は `true`。
+    ///
+    /// 2語目の2文字目以降に濁音（が・ざ・だ・ば行、ゔ）が含まれる場合は連濁させません
+    /// （`おお` + `かぜ` は `おおがぜ` ではなく `おおかぜ`）。
+    pub lymans_law: bool,
+}
+
+impl Default for RendakuOptions {
+    fn default() -> Self {
+        Self { lymans_law: true }
+    }
+}
+
+/// 2つの語の読みを、連濁させて結合します。
+///
+/// **この関数は簡易的なヒューリスティックです。** 実際の連濁は語ごとに慣用で決まり、
+/// 漢語・外来語や並列構造（`やまかわ`）では起きないことが多いなど、規則だけでは判定できません。
+/// 辞書に基づく正確な読みが必要な場合は、この関数の結果をそのまま使わないでください。
+///
+/// 2語目の先頭がか・さ・た・は行の清音（ひらがな・全角カタカナ）の場合に、
+/// 濁音（は行はば行）に変えて結合します。それ以外の文字で始まる場合はそのまま結合します。
+/// 既定ではライマンの法則（[`RendakuOptions::lymans_law`]）を適用し、
+/// 2語目に濁音が含まれていれば連濁させません。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::apply_rendaku;
+///
+/// assert_eq!(apply_rendaku("て", "かみ"), "てがみ");
+/// assert_eq!(apply_rendaku("はな", "ひ"), "はなび");
+/// // ライマンの法則：2語目に濁音があるので連濁しない
+/// assert_eq!(apply_rendaku("おお", "かぜ"), "おおかぜ");
+/// ```
+pub fn apply_rendaku(first: &str, second: &str) -> String {
+    apply_rendaku_with(first, second, RendakuOptions::default())
+}
+
+/// オプションを指定して、2つの語の読みを連濁させて結合します。
+///
+/// [`apply_rendaku`] と同様に、簡易的なヒューリスティックです。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{apply_rendaku_with, RendakuOptions};
+///
+/// let opts = RendakuOptions { lymans_law: false };
+/// assert_eq!(apply_rendaku_with("おお", "かぜ", opts), "おおがぜ");
+/// ```
+pub fn apply_rendaku_with(first: &str, second: &str, opts: RendakuOptions) -> String {
+    let mut result = String::with_capacity(first.len() + second.len());
+    result.push_str(first);
+
+    let mut chars = second.chars();
+    let Some(head) = chars.next() else {
+        return result;
+    };
+    let rest = chars.as_str();
+
+    let blocked = opts.lymans_law
+        && rest.chars().any(|c| {
+            tables::VOICED_HIRAGANA.contains(&c) || tables::VOICED_KATAKANA.contains(&c)
+        });
+    let voiceable = matches!(
+        kana_group(head),
+        Some(KanaGroup::Ka | KanaGroup::Sa | KanaGroup::Ta | KanaGroup::Ha)
+    );
+
+    if voiceable && !blocked {
+        result.push(add_dakuten(head));
+    } else {
+        result.push(head);
+    }
+    result.push_str(rest);
+    result
+}
+
+/// かなをタイピングの打鍵列（ローマ字入力）に変換します。
+///
+/// 入力をかなの単位（拗音・促音を含む）に区切り、単位ごとに許容する打鍵の一覧を返します。
+/// 各一覧の先頭はヘボン式に基づく代表的な打鍵です。カタカナ・半角カタカナはひらがなとして扱います。
+///
+/// 許容する打鍵の方式は以下のとおりです。
+///
+/// - ヘボン式（`shi`、`chi`、`tsu`、`fu`、`ja` など）
+/// - 訓令式・日本式（`si`、`ti`、`tu`、`hu`、`zya` など）
+/// - `c` / `q` を使う表記（`ca`、`cu`、`qu`、`ci`、`ce`、`co`）
+/// - 小書きのかなの単独入力（`l` または `x` を前置。`la`、`xya`、`ltu`、`xtsu` など）と、
+///   拗音を「基底のかな + 小書きのかな」に分けた入力（`kilya` など）
+///
+/// 促音・撥音・記号は次のように扱います。
+///
+/// - 促音（っ）は後続の単位とまとめて1単位とし、後続の子音を重ねる打鍵（`kka`）と、
+///   小書きの単独入力を前置した打鍵（`ltuka`）を返します。後続が母音・記号の場合や末尾の場合は単独で扱います
+/// - 撥音（ん）は `nn`、`xn` に加えて、後続の単位が母音・や行・な行以外で始まる場合に限り `n` も許容します（末尾では許容しません）
+/// - 長音符（ー）は `-`、読点（、）は `,`、句点（。）は `.` です。それ以外のかな以外の文字はそのまま1単位になります
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::to_typing_sequence;
+///
+/// let units = to_typing_sequence("しっか");
+/// assert_eq!(units[0], vec!["shi", "si", "ci"]);
+/// assert_eq!(units[1][..2], ["kka", "cca"]);
+/// assert!(units[1].contains(&"ltuka".to_string()));
+///
+/// assert_eq!(to_typing_sequence("ほんと")[1], vec!["nn", "n", "xn"]);
+/// ```
+#[cfg(feature = "romaji")]
+pub fn to_typing_sequence(input: &str) -> Vec<Vec<String>> {
+    let text = to_hiragana(&half_width_katakana_to_full_width(input));
+    let chars: Vec<char> = text.chars().collect();
+
+    // かなの単位（拗音はまとめる）と、その打鍵の一覧に区切る
+    let mut units: Vec<(char, Vec<String>)> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+
+        if let Some(&small) = chars.get(i + 1) {
+            let mut pair = String::new();
+            pair.push(c);
+            pair.push(small);
+            if let Some(combined) = typing_keys(&pair) {
+                let mut keys: Vec<String> = combined.iter().map(|&k| String::from(k)).collect();
+                // 基底のかな + 小書きのかな に分けた入力
+                let base = typing_keys_of_char(c).unwrap_or(&[]);
+                let small = typing_keys_of_char(small).unwrap_or(&[]);
+                for b in base {
+                    for s in small {
+                        keys.push(alloc::format!("{}{}", b, s));
+                    }
+                }
+                units.push((c, keys));
+                i += 2;
+                continue;
+            }
+        }
+
+        let keys = match typing_keys_of_char(c) {
+            Some(keys) => keys.iter().map(|&k| String::from(k)).collect(),
+            None => alloc::vec![match c {
+                'ー' => String::from("-"),
+                '、' => String::from(","),
+                '。' => String::from("."),
+                _ => String::from(c),
+            }],
+        };
+        units.push((c, keys));
+        i += 1;
+    }
+
+    // 促音と撥音を後続の単位に応じて展開する
+    let mut result = Vec::with_capacity(units.len());
+    let mut i = 0;
+    while i < units.len() {
+        let (c, keys) = &units[i];
+        let next = units.get(i + 1);
+        let next_head = next.and_then(|(_, keys)| keys[0].chars().next());
+
+        match (c, next) {
+            ('っ', Some((next_c, next_keys)))
+                if *next_c != 'っ'
+                    && next_head.is_some_and(|h| h.is_ascii_alphabetic() && !is_vowel_key(h)) =>
+            {
+                // 子音を重ねる打鍵（kka）と、小書きの単独入力を前置した打鍵（ltuka）
+                let mut merged: Vec<String> = next_keys
+                    .iter()
+                    .filter(|k| !k.starts_with(['a', 'i', 'u', 'e', 'o', 'n', 'l', 'x']))
+                    .map(|k| alloc::format!("{}{}", &k[..1], k))
+                    .collect();
+                for small in keys {
+                    for k in next_keys {
+                        merged.push(alloc::format!("{}{}", small, k));
+                    }
+                }
+                result.push(merged);
+                i += 2;
+                continue;
+            }
+            ('ん', _) => {
+                let mut n_keys = alloc::vec![String::from("nn")];
+                if next_head.is_some_and(|h| !is_vowel_key(h) && h != 'y') {
+                    n_keys.push(String::from("n"));
+                }
+                n_keys.push(String::from("xn"));
+                result.push(n_keys);
+            }
+            _ => result.push(keys.clone()),
+        }
+        i += 1;
+    }
+
+    result
+}
+
+/// 撥音の `n` を単独で打てない打鍵の先頭文字（母音と `n`）かどうかを判定します（内部ヘルパー関数）。
+#[cfg(feature = "romaji")]
+fn is_vowel_key(c: char) -> bool {
+    matches!(c, 'a' | 'i' | 'u' | 'e' | 'o' | 'n')
+}
+
+/// かな1文字の打鍵の一覧を返します（内部ヘルパー関数）。
+#[cfg(feature = "romaji")]
+fn typing_keys_of_char(c: char) -> Option<&'static [&'static str]> {
+    let mut buf = [0u8; 4];
+    typing_keys(c.encode_utf8(&mut buf))
+}
+
+/// かな（1文字または拗音の2文字）の打鍵の一覧を返します（内部ヘルパー関数）。
+#[cfg(feature = "romaji")]
+fn typing_keys(kana: &str) -> Option<&'static [&'static str]> {
+    TYPING_TABLE
+        .iter()
+        .find(|&&(k, _)| k == kana)
+        .map(|&(_, keys)| keys)
+}
+
+/// かなと打鍵の対応表です。各打鍵の一覧の先頭が代表的な打鍵です。
+#[cfg(feature = "romaji")]
+const TYPING_TABLE: &[(&str, &[&str])] = &[
+    ("あ", &["a"]), ("い", &["i", "yi"]), ("う", &["u", "wu", "whu"]), ("え", &["e"]), ("お", &["o"]),
+    ("か", &["ka", "ca"]), ("き", &["ki"]), ("く", &["ku", "cu", "qu"]), ("け", &["ke"]), ("こ", &["ko", "co"]),
+    ("さ", &["sa"]), ("し", &["shi", "si", "ci"]), ("す", &["su"]), ("せ", &["se", "ce"]), ("そ", &["so"]),
+    ("た", &["ta"]), ("ち", &["chi", "ti"]), ("つ", &["tsu", "tu"]), ("て", &["te"]), ("と", &["to"]),
+    ("な", &["na"]), ("に", &["ni"]), ("ぬ", &["nu"]), ("ね", &["ne"]), ("の", &["no"]),
+    ("は", &["ha"]), ("ひ", &["hi"]), ("ふ", &["fu", "hu"]), ("へ", &["he"]), ("ほ", &["ho"]),
+    ("ま", &["ma"]), ("み", &["mi"]), ("む", &["mu"]), ("め", &["me"]), ("も", &["mo"]),
+    ("や", &["ya"]), ("ゆ", &["yu"]), ("よ", &["yo"]),
+    ("ら", &["ra"]), ("り", &["ri"]), ("る", &["ru"]), ("れ", &["re"]), ("ろ", &["ro"]),
+    ("わ", &["wa"]), ("ゐ", &["wi"]), ("ゑ", &["we"]), ("を", &["wo"]), ("ん", &["nn", "xn"]),
+    ("が", &["ga"]), ("ぎ", &["gi"]), ("ぐ", &["gu"]), ("げ", &["ge"]), ("ご", &["go"]),
+    ("ざ", &["za"]), ("じ", &["ji", "zi"]), ("ず", &["zu"]), ("ぜ", &["ze"]), ("ぞ", &["zo"]),
+    ("だ", &["da"]), ("ぢ", &["di"]), ("づ", &["du"]), ("で", &["de"]), ("ど", &["do"]),
+    ("ば", &["ba"]), ("び", &["bi"]), ("ぶ", &["bu"]), ("べ", &["be"]), ("ぼ", &["bo"]),
+    ("ぱ", &["pa"]), ("ぴ", &["pi"]), ("ぷ", &["pu"]), ("ぺ", &["pe"]), ("ぽ", &["po"]),
+    ("ゔ", &["vu"]),
+    ("ぁ", &["la", "xa"]), ("ぃ", &["li", "xi"]), ("ぅ", &["lu", "xu"]), ("ぇ", &["le", "xe"]), ("ぉ", &["lo", "xo"]),
+    ("ゃ", &["lya", "xya"]), ("ゅ", &["lyu", "xyu"]), ("ょ", &["lyo", "xyo"]),
+    ("っ", &["ltu", "xtu", "ltsu", "xtsu"]), ("ゎ", &["lwa", "xwa"]), ("ゕ", &["lka", "xka"]), ("ゖ", &["lke", "xke"]),
+    ("きゃ", &["kya"]), ("きゅ", &["kyu"]), ("きょ", &["kyo"]),
+    ("しゃ", &["sha", "sya"]), ("しゅ", &["shu", "syu"]), ("しょ", &["sho", "syo"]), ("しぇ", &["she", "sye"]),
+    ("ちゃ", &["cha", "tya", "cya"]), ("ちゅ", &["chu", "tyu", "cyu"]), ("ちょ", &["cho", "tyo", "cyo"]), ("ちぇ", &["che", "tye", "cye"]),
+    ("にゃ", &["nya"]), ("にゅ", &["nyu"]), ("にょ", &["nyo"]),
+    ("ひゃ", &["hya"]), ("ひゅ", &["hyu"]), ("ひょ", &["hyo"]),
+    ("みゃ", &["mya"]), ("みゅ", &["myu"]), ("みょ", &["myo"]),
+    ("りゃ", &["rya"]), ("りゅ", &["ryu"]), ("りょ", &["ryo"]),
+    ("ぎゃ", &["gya"]), ("ぎゅ", &["gyu"]), ("ぎょ", &["gyo"]),
+    ("じゃ", &["ja", "zya", "jya"]), ("じゅ", &["ju", "zyu", "jyu"]), ("じょ", &["jo", "zyo", "jyo"]), ("じぇ", &["je", "zye", "jye"]),
+    ("ぢゃ", &["dya"]), ("ぢゅ", &["dyu"]), ("ぢょ", &["dyo"]),
+    ("びゃ", &["bya"]), ("びゅ", &["byu"]), ("びょ", &["byo"]),
+    ("ぴゃ", &["pya"]), ("ぴゅ", &["pyu"]), ("ぴょ", &["pyo"]),
+    ("てぃ", &["thi"]), ("てゅ", &["thu"]), ("でぃ", &["dhi"]), ("でゅ", &["dhu"]),
+    ("とぅ", &["twu"]), ("どぅ", &["dwu"]),
+    ("ふぁ", &["fa"]), ("ふぃ", &["fi"]), ("ふぇ", &["fe"]), ("ふぉ", &["fo"]), ("ふゅ", &["fyu"]),
+    ("うぃ", &["wi"]), ("うぇ", &["we"]), ("うぉ", &["who"]),
+    ("ゔぁ", &["va"]), ("ゔぃ", &["vi"]), ("ゔぇ", &["ve"]), ("ゔぉ", &["vo"]),
+];
+
+/// 1モーラ分の音素（子音と母音）です。
+///
+/// [`to_phonemes`] の戻り値として使われます。特殊なモーラは次のように表します。
+///
+/// | モーラ | `consonant` | `vowel` |
+/// |--------|-------------|---------|
+/// | 母音のみ（あ） | `None` | `Some('a')` |
+/// | 拗音（きゃ） | `Some("ky")` | `Some('a')` |
+/// | 促音（っ） | `Some("Q")` | `None` |
+/// | 撥音（ん） | `Some("N")` | `None` |
+/// | 長音（ー） | `None` | 直前の母音 |
+#[cfg(feature = "romaji")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Phoneme {
+    /// 子音（ヘボン式に基づく綴り。`k`、`sh`、`ch`、`ts`、`ky` など）
+    pub consonant: Option<String>,
+    /// 母音（`a` `i` `u` `e` `o` のいずれか）
+    pub vowel: Option<char>,
+}
+
+/// かなをモーラ単位の音素列に分解します。
+///
+/// カタカナ・半角カタカナはひらがなとして扱い、かな以外の文字は無視します。
+/// 子音の綴りはヘボン式に基づきます（`し` → `sh` + `i`、`ち` → `ch` + `i`、`ふ` → `f` + `u`）。
+/// 「ゐ」「ゑ」「を」は現代の発音に合わせて母音のみとします。
+///
+/// - 拗音（きゃ、しゅ など）は1モーラとし、子音に `y` を付けます（`ky`、`ny`）。
+///   `sh`、`ch`、`j` の行には `y` を付けません。外来音の「てゅ」「でゅ」「ふゅ」「ゔゅ」も
+///   拗音として扱います（`ty`、`dy`、`fy`、`vy`）
+/// - 小書きの母音を伴う外来音（ふぁ、てぃ、ゔぇ、うぃ、いぇ など）も1モーラとします
+/// - 促音は子音 `Q`、撥音は子音 `N` の母音の無いモーラとします
+/// - 長音符は直前の母音を繰り返すモーラとします。先頭の長音符は無視します
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{to_phonemes, Phoneme};
+///
+/// let phonemes = to_phonemes("キャッチ");
+/// let p = |c: Option<&str>, v: Option<char>| Phoneme { consonant: c.map(String::from), vowel: v };
+/// assert_eq!(
+///     phonemes,
+///     vec![p(Some("ky"), Some('a')), p(Some("Q"), None), p(Some("ch"), Some('i'))]
+/// );
+/// ```
+#[cfg(feature = "romaji")]
+pub fn to_phonemes(input: &str) -> Vec<Phoneme> {
+    let text = to_hiragana(&half_width_katakana_to_full_width(input));
+    let chars: Vec<char> = text.chars().collect();
+    let mut phonemes: Vec<Phoneme> = Vec::with_capacity(chars.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+
+        let (consonant, vowel) = match c {
+            'っ' => (Some(String::from("Q")), None),
+            'ん' => (Some(String::from("N")), None),
+            'ー' => match phonemes.last().and_then(|p| p.vowel) {
+                Some(v) => (None, Some(v)),
+                None => continue,
+            },
+            _ => {
+                let Some((consonant, vowel)) = mora_phoneme(c) else {
+                    continue;
+                };
+                let small = chars.get(i).copied();
+                let small_vowel = small.and_then(mora_phoneme).map(|(_, v)| v);
+                match (small, consonant) {
+                    // 拗音（でゅ、ふゅ などの外来音を含む）
+                    (Some('ゃ' | 'ゅ' | 'ょ'), Some(consonant))
+                        if vowel == 'i'
+                            || (small == Some('ゅ') && matches!(c, 'て' | 'で' | 'ふ' | 'ゔ')) =>
+                    {
+                        i += 1;
+                        let mut consonant = String::from(consonant);
+                        if !matches!(consonant.as_str(), "sh" | "ch" | "j") {
+                            consonant.push('y');
+                        }
+                        (Some(consonant), small_vowel)
+                    }
+                    // 小書きの母音を伴う外来音
+                    (Some('ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ'), _)
+                        if consonant.is_some() || matches!(c, 'う' | 'い') =>
+                    {
+                        i += 1;
+                        let consonant = consonant.unwrap_or(if c == 'う' { "w" } else { "y" });
+                        (Some(String::from(consonant)), small_vowel)
+                    }
+                    _ => (consonant.map(String::from), Some(vowel)),
+                }
+            }
+        };
+
+        phonemes.push(Phoneme { consonant, vowel });
+    }
+
+    phonemes
+}
+
+/// ひらがな1文字の子音と母音を返します（内部ヘルパー関数）。
+#[cfg(feature = "romaji")]
+fn mora_phoneme(c: char) -> Option<(Option<&'static str>, char)> {
+    let vowel = match hiragana_vowel(c)? {
+        'あ' => 'a',
+        'い' => 'i',
+        'う' => 'u',
+        'え' => 'e',
+        _ => 'o',
+    };
+    let consonant = match c {
+        'あ' | 'い' | 'う' | 'え' | 'お' | 'ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ' | 'ゐ' | 'ゑ' | 'を' => None,
+        'し' => Some("sh"),
+        'ち' => Some("ch"),
+        'つ' => Some("ts"),
+        'ふ' => Some("f"),
+        'じ' | 'ぢ' => Some("j"),
+        'づ' => Some("z"),
+        'ゔ' => Some("v"),
+        'か' | 'き' | 'く' | 'け' | 'こ' | 'ゕ' | 'ゖ' => Some("k"),
+        'が' | 'ぎ' | 'ぐ' | 'げ' | 'ご' => Some("g"),
+        'さ' | 'す' | 'せ' | 'そ' => Some("s"),
+        'ざ' | 'ず' | 'ぜ' | 'ぞ' => Some("z"),
+        'た' | 'て' | 'と' => Some("t"),
+        'だ' | 'で' | 'ど' => Some("d"),
+        'な' | 'に' | 'ぬ' | 'ね' | 'の' => Some("n"),
+        'は' | 'ひ' | 'へ' | 'ほ' => Some("h"),
+        'ば' | 'び' | 'ぶ' | 'べ' | 'ぼ' => Some("b"),
+        'ぱ' | 'ぴ' | 'ぷ' | 'ぺ' | 'ぽ' => Some("p"),
+        'ま' | 'み' | 'む' | 'め' | 'も' => Some("m"),
+        'や' | 'ゆ' | 'よ' | 'ゃ' | 'ゅ' | 'ょ' => Some("y"),
+        'ら' | 'り' | 'る' | 'れ' | 'ろ' => Some("r"),
+        'わ' | 'ゎ' => Some("w"),
+        _ => return None,
+    };
+    Some((consonant, vowel))
+}
+
+/// かなをヘボン式に基づくローマ字に変換します。
+///
+/// かなの部分を [`to_phonemes`] で音素列に分解し、各モーラの子音と母音をつなげて綴ります。
+/// かな以外の文字はそのまま残します。音素への分解の規則は [`to_phonemes`] を参照してください。
+///
+/// - 促音は後続の子音を重ねます（`きっぷ` → `kippu`）。`ch` の前は `t` にします（`マッチ` → `matchi`）。
+///   後続に子音が無い場合（語末の `っ` など）は何も出力しません
+/// - 撥音は `n` とし、母音や `y` の前では `n'` にします（`きんえん` → `kin'en`）
+/// - 長音は母音を重ねて表します（`コーヒー` → `koohii`）。マクロンは使いません
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::to_romaji;
+///
+/// assert_eq!(to_romaji("しんぶん"), "shinbun");
+/// assert_eq!(to_romaji("キャッチ"), "kyatchi");
+/// assert_eq!(to_romaji("コーヒーを1杯"), "koohiio1杯");
+/// ```
+#[cfg(feature = "romaji")]
+pub fn to_romaji(input: &str) -> String {
+    let is_target = |c: char| is_kana(c) || is_half_width_katakana(c) || c == 'ー';
+    let mut result = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(c) = rest.chars().next() {
+        match match_run(rest, is_target) {
+            Some((kana, remaining)) => {
+                push_romaji(&mut result, &to_phonemes(kana));
+                rest = remaining;
+            }
+            None => {
+                result.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    result
+}
+
+/// 音素列をローマ字で書き出します（内部ヘルパー関数）。
+#[cfg(feature = "romaji")]
+fn push_romaji(result: &mut String, phonemes: &[Phoneme]) {
+    for (i, phoneme) in phonemes.iter().enumerate() {
+        let next_consonant = phonemes.get(i + 1).map(|next| next.consonant.as_deref());
+        match (phoneme.consonant.as_deref(), phoneme.vowel) {
+            (Some("Q"), None) => {
+                if let Some(Some(next)) = next_consonant {
+                    if next.starts_with("ch") {
+                        result.push('t');
+                    } else if !matches!(next, "Q" | "N") {
+                        result.push_str(&next[..1]);
+                    }
+                }
+            }
+            (Some("N"), None) => {
+                result.push('n');
+                if matches!(next_consonant, Some(None))
+                    || matches!(next_consonant, Some(Some(next)) if next.starts_with('y'))
+                {
+                    result.push('\'');
+                }
+            }
+            (consonant, vowel) => {
+                result.extend(consonant);
+                result.extend(vowel);
+            }
+        }
+    }
+}
+
+/// ルビ（ふりがな）付きテキストの区間です。
+///
+/// [`parse_aozora_ruby`] の戻り値として使われます。
+#[cfg(feature = "ruby")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RubySegment {
+    /// 親文字（ルビが無い場合は本文そのもの）
+    pub base: String,
+    /// ルビ。ルビが付いていない区間では `None`
+    pub ruby: Option<String>,
+}
+
+/// 青空文庫形式のルビ記法をパースします。
+///
+/// 以下の規則で解釈します。
+///
+/// - `｜親文字《ルビ》`: `｜`（U+FF5C、または半角 `|`）から `《` までを親文字とします
+/// - `漢字《ルビ》`: `｜` が無い場合は、`《` の直前に連続する漢字（`々`、`〆`、`ヵ`、`ヶ` を含む）を親文字とします。
+///   直前が漢字でない場合は、直前に連続する同じ種別（[`classify`]）の文字を親文字とします
+/// - ルビはネストしない前提です。`《` の後の最初の `》` までをルビとします
+/// - 対応する `》` が無い `《`、中身が空の `《》`、親文字が無い `《ルビ》` は本文としてそのまま残します
+/// - `《` が続かない `｜` も本文としてそのまま残します
+///
+/// 青空文庫で `《》` を文字として表す `※［＃始め二重山括弧、1-1-52］` のような注記は解釈せず、本文として扱います。
+/// ルビの無い連続した本文は1つの区間にまとめられます。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{parse_aozora_ruby, RubySegment};
+///
+/// let segments = parse_aozora_ruby("吾輩《わがはい》は｜猫である《ねこである》");
+/// assert_eq!(
+///     segments,
+///     vec![
+///         RubySegment { base: "吾輩".to_string(), ruby: Some("わがはい".to_string()) },
+///         RubySegment { base: "は".to_string(), ruby: None },
+///         RubySegment { base: "猫である".to_string(), ruby: Some("ねこである".to_string()) },
+///     ]
+/// );
+/// ```
+#[cfg(feature = "ruby")]
+pub fn parse_aozora_ruby(input: &str) -> Vec<RubySegment> {
+    let mut segments = Vec::new();
+    let mut plain = String::new();
+    // 親文字の開始記号（｜）の位置と、その記号自体
+    let mut marker: Option<(usize, char)> = None;
+    let mut rest = input;
+
+    while let Some(c) = rest.chars().next() {
+        rest = &rest[c.len_utf8()..];
+
+        match c {
+            '｜' | '|' => {
+                if let Some((position, mark)) = marker.take() {
+                    plain.insert(position, mark);
+                }
+                marker = Some((plain.len(), c));
+            }
+            '《' => {
+                let ruby = match rest.find('》') {
+                    Some(end) if end > 0 => &rest[..end],
+                    _ => {
+                        plain.push(c);
+                        continue;
+                    }
+                };
+
+                let base_start = match marker {
+                    Some((position, _)) => position,
+                    None => ruby_base_start(&plain),
+                };
+                if base_start == plain.len() {
+                    // 親文字が無い場合は、開始記号も含めて本文として扱う
+                    if let Some((position, mark)) = marker.take() {
+                        plain.insert(position, mark);
+                    }
+                    plain.push(c);
+                    continue;
+                }
+                marker = None;
+
+                let base = plain.split_off(base_start);
+                if !plain.is_empty() {
+                    segments.push(RubySegment {
+                        base: core::mem::take(&mut plain),
+                        ruby: None,
+                    });
+                }
+                segments.push(RubySegment {
+                    base,
+                    ruby: Some(String::from(ruby)),
+                });
+                rest = &rest[ruby.len() + '》'.len_utf8()..];
+            }
+            _ => plain.push(c),
+        }
+    }
+
+    if let Some((position, mark)) = marker {
+        plain.insert(position, mark);
+    }
+    if !plain.is_empty() {
+        segments.push(RubySegment {
+            base: plain,
+            ruby: None,
+        });
+    }
+
+    segments
+}
+
+/// ルビ付きテキストの区間列を HTML の `<ruby>` 要素に変換します。
+///
+/// ルビのある区間は `<ruby>親文字<rt>ルビ</rt></ruby>` に、ルビの無い区間はそのままのテキストになります。
+/// 親文字・ルビ・本文の `<`、`>`、`&` はエスケープされます。
+/// `<rp>` によるフォールバックを付ける場合は [`to_ruby_html_with`] を使用してください。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{parse_aozora_ruby, to_ruby_html};
+///
+/// let segments = parse_aozora_ruby("漢字《かんじ》を読む");
+/// assert_eq!(to_ruby_html(&segments), "<ruby>漢字<rt>かんじ</rt></ruby>を読む");
+/// ```
+#[cfg(feature = "ruby")]
+pub fn to_ruby_html(segments: &[RubySegment]) -> String {
+    to_ruby_html_with(segments, RubyHtmlOptions::default())
+}
+
+/// [`to_ruby_html_with`] の動作を指定するオプションです。
+#[cfg(feature = "ruby")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RubyHtmlOptions {
+    /// `true` の場合、ルビに対応しないブラウザ向けに `<rp>(</rp>` と `<rp>)</rp>` を付けます。
+    pub fallback_parentheses: bool,
+}
+
+/// オプションを指定して、ルビ付きテキストの区間列を HTML に変換します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{parse_aozora_ruby, to_ruby_html_with, RubyHtmlOptions};
+///
+/// let segments = parse_aozora_ruby("漢字《かんじ》");
+/// let opts = RubyHtmlOptions { fallback_parentheses: true };
+/// assert_eq!(
+///     to_ruby_html_with(&segments, opts),
+///     "<ruby>漢字<rp>(</rp><rt>かんじ</rt><rp>)</rp></ruby>"
+/// );
+/// ```
+#[cfg(feature = "ruby")]
+pub fn to_ruby_html_with(segments: &[RubySegment], opts: RubyHtmlOptions) -> String {
+    let mut html = String::new();
+
+    for segment in segments {
+        match &segment.ruby {
+            Some(ruby) => {
+                html.push_str("<ruby>");
+                push_html_escaped(&mut html, &segment.base);
+                if opts.fallback_parentheses {
+                    html.push_str("<rp>(</rp>");
+                }
+                html.push_str("<rt>");
+                push_html_escaped(&mut html, ruby);
+                html.push_str("</rt>");
+                if opts.fallback_parentheses {
+                    html.push_str("<rp>)</rp>");
+                }
+                html.push_str("</ruby>");
+            }
+            None => push_html_escaped(&mut html, &segment.base),
+        }
+    }
+
+    html
+}
+
+/// `<`、`>`、`&` をエスケープして追記します（内部ヘルパー関数）。
+#[cfg(feature = "ruby")]
+fn push_html_escaped(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '&' => out.push_str("&amp;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// `｜` の無いルビの親文字が始まる位置（バイトオフセット）を返します（内部ヘルパー関数）。
+#[cfg(feature = "ruby")]
+fn ruby_base_start(text: &str) -> usize {
+    let is_base_kanji = |c: char| is_kanji(c) || matches!(c, '々' | '〆' | 'ヵ' | 'ヶ');
+
+    let last = match text.chars().next_back() {
+        Some(c) => c,
+        None => return 0,
+    };
+
+    let mut start = text.len();
+    for (i, c) in text.char_indices().rev() {
+        let same_run = if is_base_kanji(last) {
+            is_base_kanji(c)
+        } else {
+            classify(c) == classify(last)
+        };
+        if !same_run {
+            break;
+        }
+        start = i;
+    }
+    start
+}
+
+/// 文字に濁点を追加します（内部ヘルパー関数）。
+fn add_dakuten(c: char) -> char {
+    match c {
+        // ひらがな
+        'か' => 'が', 'き' => 'ぎ', 'く' => 'ぐ', 'け' => 'げ', 'こ' => 'ご',
+        'さ' => 'ざ', 'し' => 'じ', 'す' => 'ず', 'せ' => 'ぜ', 'そ' => 'ぞ',
+        'た' => 'だ', 'ち' => 'ぢ', 'つ' => 'づ', 'て' => 'で', 'と' => 'ど',
+        'は' => 'ば', 'ひ' => 'び', 'ふ' => 'ぶ', 'へ' => 'べ', 'ほ' => 'ぼ',
+        // カタカナ
+        'カ' => 'ガ', 'キ' => 'ギ', 'ク' => 'グ', 'ケ' => 'ゲ', 'コ' => 'ゴ',
+        'サ' => 'ザ', 'シ' => 'ジ', 'ス' => 'ズ', 'セ' => 'ゼ', 'ソ' => 'ゾ',
+        'タ' => 'ダ', 'チ' => 'ヂ', 'ツ' => 'ヅ', 'テ' => 'デ', 'ト' => 'ド',
+        'ハ' => 'バ', 'ヒ' => 'ビ', 'フ' => 'ブ', 'ヘ' => 'ベ', 'ホ' => 'ボ',
+        _ => c,
+    }
+}
+
+/// 濁音・半濁音を清音に戻します（内部ヘルパー関数）。
+fn remove_voicing(c: char) -> char {
+    match c {
+        // ひらがな
+        'が' => 'か', 'ぎ' => 'き', 'ぐ' => 'く', 'げ' => 'け', 'ご' => 'こ',
+        'ざ' => 'さ', 'じ' => 'し', 'ず' => 'す', 'ぜ' => 'せ', 'ぞ' => 'そ',
+        'だ' => 'た', 'ぢ' => 'ち', 'づ' => 'つ', 'で' => 'て', 'ど' => 'と',
+        'ば' => 'は', 'び' => 'ひ', 'ぶ' => 'ふ', 'べ' => 'へ', 'ぼ' => 'ほ',
+        'ぱ' => 'は', 'ぴ' => 'ひ', 'ぷ' => 'ふ', 'ぺ' => 'へ', 'ぽ' => 'ほ',
+        'ゔ' => 'う',
+        // カタカナ
+        'ガ' => 'カ', 'ギ' => 'キ', 'グ' => 'ク', 'ゲ' => 'ケ', 'ゴ' => 'コ',
+        'ザ' => 'サ', 'ジ' => 'シ', 'ズ' => 'ス', 'ゼ' => 'セ', 'ゾ' => 'ソ',
+        'ダ' => 'タ', 'ヂ' => 'チ', 'ヅ' => 'ツ', 'デ' => 'テ', 'ド' => 'ト',
+        'バ' => 'ハ', 'ビ' => 'ヒ', 'ブ' => 'フ', 'ベ' => 'ヘ', 'ボ' => 'ホ',
+        'パ' => 'ハ', 'ピ' => 'ヒ', 'プ' => 'フ', 'ペ' => 'ヘ', 'ポ' => 'ホ',
+        'ヴ' => 'ウ',
+        _ => c,
+    }
+}
+
+/// 清音・濁音・半濁音のいずれかの文字から、その文字を含む組を返します（内部ヘルパー関数）。
+///
+/// [`tables::VOICING_HIRAGANA`] と [`tables::VOICING_KATAKANA`] の行と同じ組
+/// （清音、濁音、半濁音）を返します。表に無い文字は `None` です。
+fn voicing_entry(c: char) -> Option<(char, char, Option<char>)> {
+    let entry = match c {
+        // ひらがな
+        'か' | 'が' => ('か', 'が', None),
+        'き' | 'ぎ' => ('き', 'ぎ', None),
+        'く' | 'ぐ' => ('く', 'ぐ', None),
+        'け' | 'げ' => ('け', 'げ', None),
+        'こ' | 'ご' => ('こ', 'ご', None),
+        'さ' | 'ざ' => ('さ', 'ざ', None),
+        'し' | 'じ' => ('し', 'じ', None),
+        'す' | 'ず' => ('す', 'ず', None),
+        'せ' | 'ぜ' => ('せ', 'ぜ', None),
+        'そ' | 'ぞ' => ('そ', 'ぞ', None),
+        'た' | 'だ' => ('た', 'だ', None),
+        'ち' | 'ぢ' => ('ち', 'ぢ', None),
+        'つ' | 'づ' => ('つ', 'づ', None),
+        'て' | 'で' => ('て', 'で', None),
+        'と' | 'ど' => ('と', 'ど', None),
+        'は' | 'ば' | 'ぱ' => ('は', 'ば', Some('ぱ')),
+        'ひ' | 'び' | 'ぴ' => ('ひ', 'び', Some('ぴ')),
+        'ふ' | 'ぶ' | 'ぷ' => ('ふ', 'ぶ', Some('ぷ')),
+        'へ' | 'べ' | 'ぺ' => ('へ', 'べ', Some('ぺ')),
+        'ほ' | 'ぼ' | 'ぽ' => ('ほ', 'ぼ', Some('ぽ')),
+        'う' | 'ゔ' => ('う', 'ゔ', None),
+        // カタカナ
+        'カ' | 'ガ' => ('カ', 'ガ', None),
+        'キ' | 'ギ' => ('キ', 'ギ', None),
+        'ク' | 'グ' => ('ク', 'グ', None),
+        'ケ' | 'ゲ' => ('ケ', 'ゲ', None),
+        'コ' | 'ゴ' => ('コ', 'ゴ', None),
+        'サ' | 'ザ' => ('サ', 'ザ', None),
+        'シ' | 'ジ' => ('シ', 'ジ', None),
+        'ス' | 'ズ' => ('ス', 'ズ', None),
+        'セ' | 'ゼ' => ('セ', 'ゼ', None),
+        'ソ' | 'ゾ' => ('ソ', 'ゾ', None),
+        'タ' | 'ダ' => ('タ', 'ダ', None),
+        'チ' | 'ヂ' => ('チ', 'ヂ', None),
+        'ツ' | 'ヅ' => ('ツ', 'ヅ', None),
+        'テ' | 'デ' => ('テ', 'デ', None),
+        'ト' | 'ド' => ('ト', 'ド', None),
+        'ハ' | 'バ' | 'パ' => ('ハ', 'バ', Some('パ')),
+        'ヒ' | 'ビ' | 'ピ' => ('ヒ', 'ビ', Some('ピ')),
+        'フ' | 'ブ' | 'プ' => ('フ', 'ブ', Some('プ')),
+        'ヘ' | 'ベ' | 'ペ' => ('ヘ', 'ベ', Some('ペ')),
+        'ホ' | 'ボ' | 'ポ' => ('ホ', 'ボ', Some('ポ')),
+        'ウ' | 'ヴ' => ('ウ', 'ヴ', None),
+        'ワ' | 'ヷ' => ('ワ', 'ヷ', None),
+        'ヰ' | 'ヸ' => ('ヰ', 'ヸ', None),
+        'ヱ' | 'ヹ' => ('ヱ', 'ヹ', None),
+        'ヲ' | 'ヺ' => ('ヲ', 'ヺ', None),
+        _ => return None,
+    };
+    Some(entry)
+}
+
+/// 小書きのかなを通常の大きさのかなに変換します（内部ヘルパー関数）。
+fn to_large_kana(c: char) -> char {
+    match c {
+        // ひらがな
+        'ぁ' => 'あ', 'ぃ' => 'い', 'ぅ' => 'う', 'ぇ' => 'え', 'ぉ' => 'お',
+        'っ' => 'つ', 'ゃ' => 'や', 'ゅ' => 'ゆ', 'ょ' => 'よ', 'ゎ' => 'わ',
+        'ゕ' => 'か', 'ゖ' => 'け',
+        // カタカナ
+        'ァ' => 'ア', 'ィ' => 'イ', 'ゥ' => 'ウ', 'ェ' => 'エ', 'ォ' => 'オ',
+        'ッ' => 'ツ', 'ャ' => 'ヤ', 'ュ' => 'ユ', 'ョ' => 'ヨ', 'ヮ' => 'ワ',
+        'ヵ' => 'カ', 'ヶ' => 'ケ',
+        _ => c,
+    }
+}
+
+/// 全角ASCII文字を半角に変換しながら、任意のライターに書き出します。
+///
+/// [`to_half_width`] と同じ変換を行いますが、中間の `String` を確保しません。
+/// 変換が不要な部分はまとめて書き出します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::write_half_width;
+///
+/// let mut out = String::from("結果: ");
+/// write_half_width("ＡＢＣ１２３", &mut out).unwrap();
+/// assert_eq!(out, "結果: ABC123");
+/// ```
+pub fn write_half_width<W: core::fmt::Write>(input: &str, out: &mut W) -> core::fmt::Result {
+    write_mapped(input, out, half_width_char_of)
+}
+
+/// 半角ASCII文字を全角に変換しながら、任意のライターに書き出します。
+///
+/// [`to_full_width`] と同じ変換を行いますが、中間の `String` を確保しません。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::write_full_width;
+///
+/// let mut out = String::new();
+/// write_full_width("ABC", &mut out).unwrap();
+/// assert_eq!(out, "ＡＢＣ");
+/// ```
+pub fn write_full_width<W: core::fmt::Write>(input: &str, out: &mut W) -> core::fmt::Result {
+    write_mapped(input, out, full_width_char_of)
+}
+
+/// カタカナをひらがなに変換しながら、任意のライターに書き出します。
+///
+/// [`to_hiragana`] と同じ変換を行いますが、中間の `String` を確保しません。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::write_hiragana;
+///
+/// let mut out = String::new();
+/// write_hiragana("カタカナ", &mut out).unwrap();
+/// assert_eq!(out, "かたかな");
+/// ```
+pub fn write_hiragana<W: core::fmt::Write>(input: &str, out: &mut W) -> core::fmt::Result {
+    write_mapped(input, out, hiragana_char)
+}
+
+/// ひらがなをカタカナに変換しながら、任意のライターに書き出します。
+///
+/// [`to_katakana`] と同じ変換を行いますが、中間の `String` を確保しません。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::write_katakana;
+///
+/// let mut out = String::new();
+/// write_katakana("ひらがな", &mut out).unwrap();
+/// assert_eq!(out, "ヒラガナ");
+/// ```
+pub fn write_katakana<W: core::fmt::Write>(input: &str, out: &mut W) -> core::fmt::Result {
+    write_mapped(input, out, katakana_char)
+}
+
+/// UTF-8 のバイト列を検証してから、全角ASCII文字を半角に変換します。
+///
+/// ネットワークのバッファやファイルの読み込み結果を、`&str` に変換せずにそのまま渡せます。
+/// 変換は [`to_half_width`] と同じです。
+///
+/// # エラー
+///
+/// 入力が正しい UTF-8 でない場合は [`Utf8Error`](core::str::Utf8Error) を返します。
+/// 失敗した位置は [`valid_up_to`](core::str::Utf8Error::valid_up_to)（先頭から何バイト目までが正しいか）で取得できます。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::to_half_width_bytes;
+///
+/// assert_eq!(to_half_width_bytes("ＡＢＣ".as_bytes()).unwrap(), "ABC");
+///
+/// let err = to_half_width_bytes(b"abc\xFFdef").unwrap_err();
+/// assert_eq!(err.valid_up_to(), 3);
+/// ```
+pub fn to_half_width_bytes(input: &[u8]) -> Result<String, core::str::Utf8Error> {
+    core::str::from_utf8(input).map(to_half_width)
+}
+
+/// UTF-8 のバイト列を検証してから、半角ASCII文字を全角に変換します。
+///
+/// 変換は [`to_full_width`] と、エラーは [`to_half_width_bytes`] と同じです。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::to_full_width_bytes;
+///
+/// assert_eq!(to_full_width_bytes(b"ABC").unwrap(), "ＡＢＣ");
+/// ```
+pub fn to_full_width_bytes(input: &[u8]) -> Result<String, core::str::Utf8Error> {
+    core::str::from_utf8(input).map(to_full_width)
+}
+
+/// UTF-8 のバイト列を検証してから、カタカナをひらがなに変換します。
+///
+/// 変換は [`to_hiragana`] と、エラーは [`to_half_width_bytes`] と同じです。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::to_hiragana_bytes;
+///
+/// assert_eq!(to_hiragana_bytes("カタカナ".as_bytes()).unwrap(), "かたかな");
+/// ```
+pub fn to_hiragana_bytes(input: &[u8]) -> Result<String, core::str::Utf8Error> {
+    core::str::from_utf8(input).map(to_hiragana)
+}
+
+/// UTF-8 のバイト列を検証してから、ひらがなをカタカナに変換します。
+///
+/// 変換は [`to_katakana`] と、エラーは [`to_half_width_bytes`] と同じです。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::to_katakana_bytes;
+///
+/// assert_eq!(to_katakana_bytes("ひらがな".as_bytes()).unwrap(), "ヒラガナ");
+/// ```
+pub fn to_katakana_bytes(input: &[u8]) -> Result<String, core::str::Utf8Error> {
+    core::str::from_utf8(input).map(to_katakana)
+}
+
+/// 1文字変換を適用しながらライターに書き出します（内部ヘルパー関数）。
+///
+/// 変換されない文字の連続は `write_str` でまとめて書き出します。
+fn write_mapped<W: core::fmt::Write>(
+    input: &str,
+    out: &mut W,
+    convert: fn(char) -> char,
+) -> core::fmt::Result {
+    let mut start = 0;
+
+    for (i, c) in input.char_indices() {
+        let converted = convert(c);
+        if converted != c {
+            out.write_str(&input[start..i])?;
+            out.write_char(converted)?;
+            start = i + c.len_utf8();
+        }
+    }
+
+    out.write_str(&input[start..])
+}
+
+/// 全角ASCII文字を半角に変換しながら表示するラッパーです。
+///
+/// [`Display`](core::fmt::Display) の実装で [`write_half_width`] を呼び出すため、
+/// `format!` や `write!` に埋め込んでも中間の `String` を確保しません。
+/// `to_string()` で [`to_half_width`] と同じ結果の `String` も得られます。
+/// 幅や寄せなどの書式指定は無視されます。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::HalfWidth;
+///
+/// assert_eq!(format!("結果: {}", HalfWidth("ＡＢＣ１２３")), "結果: ABC123");
+/// assert_eq!(HalfWidth("ＸＹＺ").to_string(), "XYZ");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HalfWidth<'a>(pub &'a str);
+
+impl core::fmt::Display for HalfWidth<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write_half_width(self.0, f)
+    }
+}
+
+/// 半角ASCII文字を全角に変換しながら表示するラッパーです。
+///
+/// [`HalfWidth`] と同様に、[`write_full_width`] で変換しながら書き出します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::FullWidth;
+///
+/// assert_eq!(format!("[{}]", FullWidth("ABC")), "[ＡＢＣ]");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FullWidth<'a>(pub &'a str);
+
+impl core::fmt::Display for FullWidth<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write_full_width(self.0, f)
+    }
+}
+
+/// カタカナをひらがなに変換しながら表示するラッパーです。
+///
+/// [`HalfWidth`] と同様に、[`write_hiragana`] で変換しながら書き出します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::ToHiragana;
+///
+/// assert_eq!(format!("読み: {}", ToHiragana("カタカナ")), "読み: かたかな");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToHiragana<'a>(pub &'a str);
+
+impl core::fmt::Display for ToHiragana<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write_hiragana(self.0, f)
+    }
+}
+
+/// ひらがなをカタカナに変換しながら表示するラッパーです。
+///
+/// [`HalfWidth`] と同様に、[`write_katakana`] で変換しながら書き出します。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::ToKatakana;
+///
+/// assert_eq!(format!("{}", ToKatakana("ひらがな")), "ヒラガナ");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToKatakana<'a>(pub &'a str);
+
+impl core::fmt::Display for ToKatakana<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write_katakana(self.0, f)
+    }
+}
+
+/// 入力ストリームを読み取りながら1文字ずつ変換する [`Read`](std::io::Read) アダプタです。
+///
+/// 巨大なファイルをすべてメモリに載せることなく変換できます。
+/// 1文字を1文字に写す変換（幅変換・かな変換）のみに対応しています。
+///
+/// UTF-8 のマルチバイト文字が読み取りチャンクの境界で分断された場合は、
+/// 内部バッファに保持して次の読み取りで結合します。
+/// 不正な UTF-8 を読み取った場合は [`std::io::ErrorKind::InvalidData`] のエラーを返します。
+///
+/// `std` feature が有効な場合のみ利用できます。
+///
+/// # 使用例
+///
+/// ```
+/// use std::io::Read;
+/// use japanese_text::ConvertReader;
+///
+/// let mut reader = ConvertReader::half_width("ＡＢＣ１２３".as_bytes());
+/// let mut output = String::new();
+/// reader.read_to_string(&mut output).unwrap();
+/// assert_eq!(output, "ABC123");
+/// ```
+#[cfg(feature = "std")]
+pub struct ConvertReader<R> {
+    inner: R,
+    convert: fn(char) -> char,
+    /// まだ UTF-8 として確定していない入力バイト
+    pending: Vec<u8>,
+    /// 変換済みでまだ返していない出力バイト
+    output: Vec<u8>,
+    position: usize,
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> ConvertReader<R> {
+    /// 任意の1文字変換を適用するリーダーを作成します。
+    pub fn new(inner: R, convert: fn(char) -> char) -> Self {
+        ConvertReader {
+            inner,
+            convert,
+            pending: Vec::new(),
+            output: Vec::new(),
+            position: 0,
+        }
+    }
+
+    /// 全角ASCII文字を半角に変換するリーダーを作成します（[`to_half_width`] と同じ変換）。
+    pub fn half_width(inner: R) -> Self {
+        Self::new(inner, half_width_char_of)
+    }
+
+    /// 半角ASCII文字を全角に変換するリーダーを作成します（[`to_full_width`] と同じ変換）。
+    pub fn full_width(inner: R) -> Self {
+        Self::new(inner, full_width_char_of)
+    }
+
+    /// カタカナをひらがなに変換するリーダーを作成します（[`to_hiragana`] と同じ変換）。
+    pub fn hiragana(inner: R) -> Self {
+        Self::new(inner, hiragana_char)
+    }
+
+    /// ひらがなをカタカナに変換するリーダーを作成します（[`to_katakana`] と同じ変換）。
+    pub fn katakana(inner: R) -> Self {
+        Self::new(inner, katakana_char)
+    }
+
+    /// 内部のリーダーを取り出します。
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// 出力バッファが空になったときに、次の入力チャンクを読み取って変換します。
+    /// 入力の終端に達した場合は `false` を返します。
+    fn fill_output(&mut self) -> std::io::Result<bool> {
+        let mut buf = [0u8; 8192];
+
+        loop {
+            let n = self.inner.read(&mut buf)?;
+            if n == 0 {
+                if self.pending.is_empty() {
+                    return Ok(false);
+                }
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "stream ended in the middle of a UTF-8 sequence",
+                ));
+            }
+            self.pending.extend_from_slice(&buf[..n]);
+
+            let valid_up_to = match core::str::from_utf8(&self.pending) {
+                Ok(text) => text.len(),
+                Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                Err(e) => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+            };
+
+            if valid_up_to == 0 {
+                // 文字の途中までしか読めていないので、続きを読む
+                continue;
+            }
+
+            let text = core::str::from_utf8(&self.pending[..valid_up_to]).unwrap_or_default();
+            self.output.clear();
+            self.position = 0;
+            let mut encoded = [0u8; 4];
+            for c in text.chars() {
+                let converted = (self.convert)(c);
+                self.output
+                    .extend_from_slice(converted.encode_utf8(&mut encoded).as_bytes());
+            }
+            self.pending.drain(..valid_up_to);
+            return Ok(true);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> std::io::Read for ConvertReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.position >= self.output.len() && !self.fill_output()? {
+            return Ok(0);
+        }
+
+        let remaining = &self.output[self.position..];
+        let n = remaining.len().min(buf.len());
+        buf[..n].copy_from_slice(&remaining[..n]);
+        self.position += n;
+        Ok(n)
+    }
+}
+
+/// 入力ストリームを1行ずつ読み取り、変換を適用して書き出します。
+///
+/// 1行ずつ処理するため、巨大なファイルもすべてメモリに載せることなく正規化できます。
+/// 変換は [`apply_all`] と同じ順序で、行ごとに独立して適用します。
+///
+/// - 改行コード（`\r\n` と `\n`）は変換の対象から除き、入力のまま書き出します。
+///   末尾に改行の無い最終行には改行を補いません。`\r` 単独は改行とみなしません。
+/// - 入力の先頭に BOM（U+FEFF）がある場合は、変換の対象から除いてそのまま書き出します。
+///   BOM を取り除きたい場合は、読み取り側で読み飛ばしてください。
+/// - 不正な UTF-8 を読み取った場合は [`std::io::ErrorKind::InvalidData`] のエラーを返します。
+///
+/// `std` feature が有効な場合のみ利用できます。
+///
+/// # 使用例
+///
+/// ```
+/// use japanese_text::{normalize_reader, Transform};
+///
+/// let input = "ＡＢＣ\r\nカタカナ\n";
+/// let mut output = Vec::new();
+/// normalize_reader(input.as_bytes(), &mut output, &[Transform::HalfWidth, Transform::ToHiragana]).unwrap();
+/// assert_eq!(String::from_utf8(output).unwrap(), "ABC\r\nかたかな\n");
+/// ```
+#[cfg(feature = "std")]
+pub fn normalize_reader<R: std::io::BufRead, W: std::io::Write>(
+    mut reader: R,
+    mut writer: W,
+    transforms: &[Transform],
+) -> std::io::Result<()> {
+    let mut line = String::new();
+    let mut first = true;
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+
+        let mut body = line.as_str();
+        if first {
+            if let Some(rest) = body.strip_prefix('\u{FEFF}') {
+                writer.write_all("\u{FEFF}".as_bytes())?;
+                body = rest;
+            }
+            first = false;
+        }
+
+        let ending_len = if body.ends_with("\r\n") {
+            2
+        } else if body.ends_with('\n') {
+            1
+        } else {
+            0
+        };
+        let (content, ending) = body.split_at(body.len() - ending_len);
+        writer.write_all(apply_all(transforms, content).as_bytes())?;
+        writer.write_all(ending.as_bytes())?;
+    }
+
+    writer.flush()
+}
+
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
+mod tests {
+    use super::*;
+    use alloc::format;
+    use alloc::string::ToString;
+    use alloc::vec;
+    use core::ops::RangeInclusive;
+    use proptest::prelude::*;
+
+    /// 範囲の端の文字が出やすいように、範囲の先頭・末尾の文字と範囲内の任意の文字を混ぜて生成するストラテジーです。
+    fn chars_in(ranges: &'static [RangeInclusive<char>]) -> impl Strategy<Value = char> {
+        let edges: Vec<char> = ranges
+            .iter()
+            .flat_map(|range| [*range.start(), *range.end()])
+            .collect();
+        prop_oneof![
+            proptest::sample::select(edges),
+            proptest::char::ranges(ranges.into()),
+        ]
+    }
+
+    /// 指定した文字のストラテジーから、最大32文字の文字列を生成するストラテジーです。
+    fn string_of(chars: impl Strategy<Value = char>) -> impl Strategy<Value = String> {
+        proptest::collection::vec(chars, 0..=32).prop_map(String::from_iter)
+    }
+
+    #[test]
+    fn test_to_half_width_with() {
+        let input = "Ａ１　！～あ";
+        for bits in 0..8 {
+            let opts = ConversionOptions {
+                convert_space: bits & 1 != 0,
+                convert_ascii: bits & 2 != 0,
+                convert_symbols: bits & 4 != 0,
+            };
+            let mut expected = String::new();
+            expected.push_str(if opts.convert_ascii { "A1" } else { "Ａ１" });
+            expected.push(if opts.convert_space { ' ' } else { '　' });
+            expected.push_str(if opts.convert_symbols { "!~" } else { "！～" });
+            expected.push('あ');
+            assert_eq!(to_half_width_with(input, opts), expected, "{:?}", opts);
+        }
+
+        // 範囲の境界
+        let symbols_only = ConversionOptions {
+            convert_space: false,
+            convert_ascii: false,
+            convert_symbols: true,
+        };
+        assert_eq!(to_half_width_with("／０９：＠ＡＺ［｀ａｚ｛", symbols_only), "/０９:@ＡＺ[`ａｚ{");
+        let ascii_only = ConversionOptions {
+            convert_space: false,
+            convert_ascii: true,
+            convert_symbols: false,
+        };
+        assert_eq!(to_half_width_with("／０９：＠ＡＺ［｀ａｚ｛", ascii_only), "／09：＠AZ［｀az｛");
+
+        // 既定は to_half_width と同じ
+        let all: String = ('\u{FF01}'..='\u{FF5E}').chain(['　', 'あ', 'A']).collect();
+        assert_eq!(to_half_width_with(&all, ConversionOptions::default()), to_half_width(&all));
+    }
+
+    #[test]
+    fn test_to_half_width_all() {
+        assert_eq!(to_half_width_all("ＡＢＣ１２３"), "ABC123");
+        assert_eq!(to_half_width_all("カタカナ"), "ｶﾀｶﾅ");
+        assert_eq!(to_half_width_all("ガ"), "ｶﾞ");
+        assert_eq!(to_half_width_all("パソコン　ＰＣ"), "ﾊﾟｿｺﾝ PC");
+        assert_eq!(to_half_width_all("「テスト」、ア・イ。"), "｢ﾃｽﾄ｣､ｱ･ｲ｡");
+        // 半角のものはそのまま
+        assert_eq!(to_half_width_all("ｶﾞABC"), "ｶﾞABC");
+        // ひらがな・漢字・半角の無いカタカナは対象外
+        assert_eq!(to_half_width_all("ひらがな漢字ヶ"), "ひらがな漢字ヶ");
+        assert_eq!(to_half_width_all(""), "");
+        // to_half_width はカタカナを変換しない
+        assert_eq!(to_half_width("ガ"), "ガ");
+    }
+
+    #[test]
+    fn test_to_half_width() {
+        assert_eq!(to_half_width("ＡＢＣ"), "ABC");
+        assert_eq!(to_half_width("１２３"), "123");
+        assert_eq!(to_half_width("！＠＃"), "!@#");
+        assert_eq!(to_half_width("　"), " ");
+        assert_eq!(to_half_width("Ｈｅｌｌｏ　Ｗｏｒｌｄ"), "Hello World");
+        // Mixed content
+        assert_eq!(to_half_width("ＡＢＣあいう"), "ABCあいう");
+        assert_eq!(to_half_width("log: ｅｒｒｏｒ 発生 ＃１"), "log: error 発生 #1");
+        // 先頭・末尾の変換対象
+        assert_eq!(to_half_width("Ａabc"), "Aabc");
+        assert_eq!(to_half_width("abcＡ"), "abcA");
+        // 0xE3/0xEF で始まるが変換対象外の文字
+        assert_eq!(to_half_width("あ。ｱ￥"), "あ。ｱ￥");
+        // 32バイト単位の読み飛ばしをまたぐ位置の変換対象
+        let long = format!("{}Ｘ{}　{}", "a".repeat(31), "b".repeat(60), "c".repeat(40));
+        let expected = format!("{}X{} {}", "a".repeat(31), "b".repeat(60), "c".repeat(40));
+        assert_eq!(to_half_width(&long), expected);
+        // ASCII 以外の変換対象外の文字を含むブロック
+        let long = format!("{}é{}Ａ", "a".repeat(40), "b".repeat(30));
+        let expected = format!("{}é{}A", "a".repeat(40), "b".repeat(30));
+        assert_eq!(to_half_width(&long), expected);
+    }
+
+    #[test]
+    fn test_find_unconverted_full_width() {
+        assert_eq!(
+            find_unconverted_full_width("Ａ￥１００〜"),
+            vec![(3, '￥'), (15, '〜')]
+        );
+        assert_eq!(
+            find_unconverted_full_width("「テスト」ー"),
+            vec![(0, '「'), (12, '」'), (15, 'ー')]
+        );
+        // 変換できる全角文字、かな・漢字、半角文字、絵文字は含めない
+        assert!(find_unconverted_full_width("ＡＢＣ　！かなカナ漢字abcｶﾅ😀").is_empty());
+        assert!(find_unconverted_full_width("").is_empty());
+    }
+
+    #[test]
+    fn test_to_full_width_preserves_non_ascii() {
+        // すでに全角の文字・かな・漢字は二重に変換しない
+        assert_eq!(to_full_width("ＡＢＣ　カタカナ漢字"), "ＡＢＣ　カタカナ漢字");
+        // サロゲートペアになる文字（絵文字・拡張漢字）
+        assert_eq!(to_full_width("a😀b𠮷c"), "ａ😀ｂ𠮷ｃ");
+        assert_eq!(to_full_width("👨\u{200D}👩\u{200D}👧!"), "👨\u{200D}👩\u{200D}👧！");
+        // 結合文字は直前の文字と切り離さない
+        assert_eq!(to_full_width("e\u{0301}"), "ｅ\u{0301}");
+        assert_eq!(to_full_width("カ\u{3099}1"), "カ\u{3099}１");
+        assert_eq!(to_full_width("1\u{FE0F}\u{20E3}"), "１\u{FE0F}\u{20E3}");
+        // 制御文字は変換しない
+        assert_eq!(to_full_width("a\tb\n"), "ａ\tｂ\n");
+    }
+
+    const PRINTABLE_ASCII: &[RangeInclusive<char>] = &[' '..='~'];
+    const FULL_WIDTH_ASCII: &[RangeInclusive<char>] = &[tables::FULL_WIDTH_ASCII_RANGE, '　'..='　'];
+    const KATAKANA: &[RangeInclusive<char>] = &[tables::KATAKANA_RANGE];
+    const HIRAGANA: &[RangeInclusive<char>] = &[tables::HIRAGANA_RANGE];
+
+    proptest! {
+        #[test]
+        fn test_to_full_width_random_round_trip(input in string_of(any::<char>())) {
+            let full = to_full_width(&input);
+
+            // 文字数は変わらず、半角ASCII以外の文字はそのまま残る
+            prop_assert_eq!(full.chars().count(), input.chars().count());
+            for (before, after) in input.chars().zip(full.chars()) {
+                if !matches!(before, ' '..='~') {
+                    prop_assert_eq!(before, after);
+                }
+            }
+
+            // 二重に適用しても変わらない
+            prop_assert_eq!(to_full_width(&full), full.clone());
+            // 半角に戻すと、元の文字列を半角にしたものと一致する
+            prop_assert_eq!(to_half_width(&full), to_half_width(&input));
+            // 全角ASCII・全角スペースを含まない入力は元に戻る
+            if !input.chars().any(|c| c == '　' || tables::FULL_WIDTH_ASCII_RANGE.contains(&c)) {
+                prop_assert_eq!(to_half_width(&full), input);
+            }
+        }
+
+        /// ASCII の印字可能文字と空白のみの文字列は、全角化 → 半角化で元に戻る
+        #[test]
+        fn test_ascii_width_round_trip(ascii in string_of(chars_in(PRINTABLE_ASCII))) {
+            prop_assert_eq!(to_half_width(&to_full_width(&ascii)), ascii);
+        }
+
+        /// 全角英数字・記号（U+FF01-U+FF5E）と全角スペースのみの文字列は、半角化 → 全角化で元に戻る
+        #[test]
+        fn test_full_width_round_trip(full in string_of(chars_in(FULL_WIDTH_ASCII))) {
+            prop_assert_eq!(to_full_width(&to_half_width(&full)), full);
+        }
+
+        /// カタカナ（U+30A1-U+30F6）のみの文字列は、ひらがな化 → カタカナ化で元に戻る
+        #[test]
+        fn test_katakana_round_trip(katakana in string_of(chars_in(KATAKANA))) {
+            prop_assert_eq!(to_katakana(&to_hiragana(&katakana)), katakana);
+        }
+
+        /// ひらがな（U+3041-U+3096）のみの文字列は、カタカナ化 → ひらがな化で元に戻る
+        #[test]
+        fn test_hiragana_round_trip(hiragana in string_of(chars_in(HIRAGANA))) {
+            prop_assert_eq!(to_hiragana(&to_katakana(&hiragana)), hiragana);
+        }
+    }
+
+    #[test]
+    fn test_conversion_ranges_exhaustive() {
+        // 基本多言語面のすべての文字について、変換の対象範囲と変換先の範囲を確かめる
+        for c in (0..=0xFFFF).filter_map(char::from_u32) {
+            if let Some(half) = half_width_char(c) {
+                assert!(c == '　' || tables::FULL_WIDTH_ASCII_RANGE.contains(&c), "{c:?}");
+                assert_eq!(full_width_char(half), Some(c), "{c:?}");
+            }
+
+            if let Some(full) = full_width_char(c) {
+                assert!(matches!(c, ' '..='~'), "{c:?}");
+                assert_eq!(half_width_char(full), Some(c), "{c:?}");
+            }
+
+            let hiragana = hiragana_char(c);
+            if hiragana != c {
+                assert!(is_katakana(c), "{c:?}");
+                assert_eq!(katakana_char(hiragana), c, "{c:?}");
+            }
+
+            let katakana = katakana_char(c);
+            if katakana != c {
+                assert!(is_hiragana(c), "{c:?}");
+                assert_eq!(hiragana_char(katakana), c, "{c:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_half_width_char() {
+        assert_eq!(half_width_char('Ａ'), Some('A'));
+        assert_eq!(half_width_char('！'), Some('!'));
+        assert_eq!(half_width_char('～'), Some('~'));
+        assert_eq!(half_width_char('　'), Some(' '));
+        assert_eq!(half_width_char('A'), None);
+        assert_eq!(half_width_char('〜'), None);
+        assert_eq!(half_width_char('ｱ'), None);
+        assert_eq!(half_width_char('\u{FF5F}'), None);
+    }
+
+    #[test]
+    fn test_full_width_char() {
+        assert_eq!(full_width_char('A'), Some('Ａ'));
+        assert_eq!(full_width_char('!'), Some('！'));
+        assert_eq!(full_width_char('~'), Some('～'));
+        assert_eq!(full_width_char(' '), Some('　'));
+        assert_eq!(full_width_char('\t'), None);
+        assert_eq!(full_width_char('\u{7F}'), None);
+        assert_eq!(full_width_char('あ'), None);
+        // 独自の変換との組み合わせ（数字だけを全角にする）
+        let digits: String = "No.123"
+            .chars()
+            .map(|c| {
+                if c.is_ascii_digit() {
+                    full_width_char(c).unwrap_or(c)
+                } else {
+                    c
+                }
+            })
+            .collect();
+        assert_eq!(digits, "No.１２３");
+    }
+
+    #[test]
+    fn test_toggle_width() {
+        assert_eq!(toggle_width("ABC123"), "ＡＢＣ１２３");
+        assert_eq!(toggle_width("ＡＢＣ１２３"), "ABC123");
+        assert_eq!(toggle_width(" "), "　");
+        assert_eq!(toggle_width("　"), " ");
+        // 混在した入力は1文字ずつ反転する
+        assert_eq!(toggle_width("Ａb！?"), "Aｂ!？");
+        // 2回適用すると元に戻る
+        let input = "Hello　Ｗｏｒｌｄ!";
+        assert_eq!(toggle_width(&toggle_width(input)), input);
+        // どちらでもない文字はそのまま
+        assert_eq!(toggle_width("あア漢ｱ\n"), "あア漢ｱ\n");
+        assert_eq!(toggle_width(""), "");
+    }
+
+    #[test]
+    fn test_to_half_width_protected() {
+        let input = "ＡＢＣ ＤＥＦ ＧＨＩ";
+        assert_eq!(to_half_width_protected(input, &[]), Ok(String::from("ABC DEF GHI")));
+        assert_eq!(
+            to_half_width_protected(input, core::slice::from_ref(&(10..19))),
+            Ok(String::from("ABC ＤＥＦ GHI"))
+        );
+        // 重なった範囲・順不同の範囲
+        assert_eq!(
+            to_half_width_protected(input, &[20..29, 10..16, 13..19]),
+            Ok(String::from("ABC ＤＥＦ ＧＨＩ"))
+        );
+        assert_eq!(
+            to_half_width_protected(input, core::slice::from_ref(&(0..input.len()))),
+            Ok(String::from(input))
+        );
+        // 空の範囲
+        assert_eq!(
+            to_half_width_protected(input, core::slice::from_ref(&(3..3))),
+            Ok(String::from("ABC DEF GHI"))
+        );
+    }
+
+    #[test]
+    fn test_to_half_width_protected_invalid_range() {
+        let input = "ＡＢＣ";
+        assert_eq!(
+            to_half_width_protected(input, core::slice::from_ref(&(0..10))),
+            Err(ProtectedRangeError::OutOfBounds(0..10))
+        );
+        let reversed = core::ops::Range { start: 6, end: 3 };
+        assert_eq!(
+            to_half_width_protected(input, core::slice::from_ref(&reversed)),
+            Err(ProtectedRangeError::OutOfBounds(reversed))
+        );
+        assert_eq!(
+            to_half_width_protected(input, core::slice::from_ref(&(3..4))),
+            Err(ProtectedRangeError::NotCharBoundary(3..4))
+        );
+        assert_eq!(
+            ProtectedRangeError::NotCharBoundary(3..4).to_string(),
+            "protected range 3..4 is not on a char boundary"
+        );
+    }
+
+    #[test]
+    fn test_to_half_width_range() {
+        let input = "ＡＢＣ";
+        assert_eq!(to_half_width_range(input, 0..9), "ABC");
+        assert_eq!(to_half_width_range(input, 3..6), "ＡBＣ");
+        assert_eq!(to_half_width_range(input, 3..3), "ＡＢＣ");
+        assert_eq!(to_half_width_range("", 0..0), "");
+        // 範囲外の全角スペースや記号も変換しない
+        assert_eq!(to_half_width_range("（Ａ）（Ｂ）", 9..18), "（Ａ）(B)");
+
+        // 文字境界にない範囲は文字全体に広げる
+        assert_eq!(to_half_width_range(input, 1..2), "AＢＣ");
+        assert_eq!(to_half_width_range(input, 2..4), "ABＣ");
+        // 入力の長さを超える終了位置は切り詰める
+        assert_eq!(to_half_width_range(input, 6..100), "ＡＢC");
+        assert_eq!(to_half_width_range(input, 50..100), "ＡＢＣ");
+        // 開始位置が終了位置より後ろなら何も変換しない
+        let reversed = core::ops::Range { start: 6, end: 3 };
+        assert_eq!(to_half_width_range(input, reversed), "ＡＢＣ");
+    }
+
+    #[test]
+    fn test_protect_urls() {
+        let found = |input: &str| -> Vec<String> {
+            protect_urls(input)
+                .into_iter()
+                .map(|range| input[range].to_string())
+                .collect()
+        };
+
+        assert_eq!(found("見てhttps://example.com/a-b~c?x=1&y=2。"), ["https://example.com/a-b~c?x=1&y=2"]);
+        assert_eq!(found("http://example.com."), ["http://example.com"]);
+        assert_eq!(found("(see http://example.com/path), ok"), ["http://example.com/path)"]);
+        assert_eq!(found("<http://example.com>"), ["http://example.com"]);
+        assert_eq!(found("https://例え.jp"), ["https://"]);
+        assert_eq!(found("連絡先：taro.yamada+jp@mail.example.co.jpへ"), ["taro.yamada+jp@mail.example.co.jp"]);
+        assert_eq!(found("a@b.c."), ["a@b.c"]);
+        assert_eq!(
+            found("https://user@example.com と info@example.com"),
+            ["https://user@example.com", "info@example.com"]
+        );
+        // 範囲は重ならない
+        assert_eq!(found("a@b.com@c.com"), ["a@b.com"]);
+        assert_eq!(found("https://example.com/a@b.jp"), ["https://example.com/a@b.jp"]);
+        // 検出しないもの
+        assert!(found("@example.com").is_empty());
+        assert!(found("user@localhost").is_empty());
+        assert!(found("user@example.123").is_empty());
+        assert!(found("ｈｔｔｐｓ：／／ｅｘａｍｐｌｅ．ｃｏｍ").is_empty());
+        assert!(found("ftp://example.com").is_empty());
+        assert!(found("").is_empty());
+
+        let input = "ＵＲＬ：https://example.com/?q=1、ｍａｉｌ：a@example.jp";
+        assert_eq!(
+            to_half_width_protected(input, &protect_urls(input)).unwrap(),
+            "URL:https://example.com/?q=1、mail:a@example.jp"
+        );
+    }
+
+    #[test]
+    fn test_to_half_width_skip_code() {
+        assert_eq!(to_half_width_skip_code("ＡＢＣ"), "ABC");
+        assert_eq!(
+            to_half_width_skip_code("ＡＢＣ `ＤＥＦ` ＧＨＩ `Ｊ`"),
+            "ABC `ＤＥＦ` GHI `Ｊ`"
+        );
+        // バッククォートの個数が一致する区間を保護する
+        assert_eq!(to_half_width_skip_code("``Ａ`Ｂ``Ｃ"), "``Ａ`Ｂ``C");
+        assert_eq!(
+            to_half_width_skip_code("```rust\nlet ａ = 1;\n```\nＤ"),
+            "```rust\nlet ａ = 1;\n```\nD"
+        );
+        // 閉じられていないバッククォートは保護しない
+        assert_eq!(to_half_width_skip_code("`ＡＢＣ"), "`ABC");
+        // 全角のバッククォートは区切りにならない
+        assert_eq!(to_half_width_skip_code("｀Ａ｀"), "`A`");
+    }
+
+    #[test]
+    fn test_context_aware_half_width() {
+        assert_eq!(context_aware_half_width("（株）ＡＢＣ（１）"), "（株）ABC(1)");
+        assert_eq!(context_aware_half_width("（株）"), "（株）");
+        // 括弧は内側の文字で判定する
+        assert_eq!(context_aware_half_width("ＡＢＣ（株）"), "ABC（株）");
+        assert_eq!(context_aware_half_width("（１）（株）"), "(1)（株）");
+        assert_eq!(context_aware_half_width("［注］ｘ"), "［注］x");
+        assert_eq!(context_aware_half_width("ＡＢＣ"), "ABC");
+        assert_eq!(context_aware_half_width("Ｑ＆Ａ"), "Q&A");
+        assert_eq!(context_aware_half_width("１，０００円"), "1,000円");
+        // 連続する記号はまとめて判定する
+        assert_eq!(context_aware_half_width("本当！？ＯＫ！？"), "本当!?OK!?");
+        assert_eq!(context_aware_half_width("本当！？"), "本当！？");
+        // 半角の英数字に接する場合も半角化する
+        assert_eq!(context_aware_half_width("abc（def）"), "abc(def)");
+        // 全角スペースや和文の約物はそのまま
+        assert_eq!(context_aware_half_width("Ａ　Ｂ。"), "A　B。");
+        assert_eq!(context_aware_half_width("「ＡＢＣ」"), "「ABC」");
+        assert_eq!(context_aware_half_width("価格：１００円"), "価格:100円");
+        assert_eq!(context_aware_half_width(""), "");
+    }
+
+    #[test]
+    fn test_to_full_width() {
+        assert_eq!(to_full_width("ABC"), "ＡＢＣ");
+        assert_eq!(to_full_width("123"), "１２３");
+        assert_eq!(to_full_width("!@#"), "！＠＃");
+        assert_eq!(to_full_width(" "), "　");
+        assert_eq!(to_full_width("Hello World"), "Ｈｅｌｌｏ　Ｗｏｒｌｄ");
+        // Mixed content
+        assert_eq!(to_full_width("ABCあいう"), "ＡＢＣあいう");
+    }
+
+    #[test]
+    fn test_full_width_ascii_boundaries() {
+        // U+0020-U+007E の全95文字と、対応する全角文字（オフセット計算に頼らずに列挙）
+        let half = " !\"#$%&'()*+,-./0123456789:;<=>?@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~";
+        let full = "　！＂＃＄％＆＇（）＊＋，－．／０１２３４５６７８９：；＜＝＞？＠ＡＢＣＤＥＦＧＨＩＪＫＬＭＮＯＰＱＲＳＴＵＶＷＸＹＺ［＼］＾＿｀ａｂｃｄｅｆｇｈｉｊｋｌｍｎｏｐｑｒｓｔｕｖｗｘｙｚ｛｜｝～";
+        assert_eq!(half.chars().count(), 95);
+        assert_eq!(to_full_width(half), full);
+        assert_eq!(to_half_width(full), half);
+        for (h, f) in half.chars().zip(full.chars()) {
+            assert_eq!(full_width_char(h), Some(f), "{:?}", h);
+            assert_eq!(half_width_char(f), Some(h), "{:?}", f);
+        }
+
+        // 範囲の両端
+        assert_eq!(to_full_width("!~"), "\u{FF01}\u{FF5E}");
+        assert_eq!(full_width_char('\u{1F}'), None);
+        assert_eq!(full_width_char('\u{7F}'), None);
+        assert_eq!(half_width_char('\u{FF00}'), None);
+        assert_eq!(half_width_char('\u{FF5F}'), None);
+
+        // 環境依存で混同されやすい記号
+        assert_eq!(to_full_width("\\"), "\u{FF3C}"); // ＼（円記号 ￥ U+FFE5 ではない）
+        assert_eq!(to_full_width("~"), "\u{FF5E}"); // ～（波ダッシュ 〜 U+301C ではない）
+        assert_eq!(to_full_width("|"), "\u{FF5C}"); // ｜
+        assert_eq!(to_half_width("\u{301C}"), "\u{301C}");
+        assert_eq!(to_half_width("\u{FFE5}"), "\u{FFE5}");
+    }
+
+    #[test]
+    fn test_to_upper_lower_wide() {
+        assert_eq!(to_upper_wide("abcａｂｃ"), "ABCＡＢＣ");
+        assert_eq!(to_upper_wide("ｚＺzZ"), "ＺＺZZ");
+        assert_eq!(to_lower_wide("ABCＡＢＣ"), "abcａｂｃ");
+        assert_eq!(to_lower_wide("ＡａAa"), "ａａaa");
+        // 英字以外はそのまま
+        assert_eq!(to_upper_wide("１２３！＠かなé"), "１２３！＠かなé");
+        assert_eq!(to_lower_wide("ÉＯＫ"), "Éｏｋ");
+        // 幅変換とは順序に依存しない
+        let input = "Ｈｅｌｌｏ World";
+        assert_eq!(
+            to_half_width(&to_upper_wide(input)),
+            to_upper_wide(&to_half_width(input))
+        );
+        assert_eq!(
+            to_full_width(&to_lower_wide(input)),
+            to_lower_wide(&to_full_width(input))
+        );
+    }
+
+    #[test]
+    fn test_convert_brackets() {
+        assert_eq!(
+            convert_brackets("「注意」（必読）［１］｛ｘ｝＜ｙ＞｟ｚ｠", false),
+            "｢注意｣(必読)[１]{ｘ}<ｙ>⦅ｚ⦆"
+        );
+        assert_eq!(
+            convert_brackets("｢注意｣(必読)[1]{x}<y>⦅z⦆", true),
+            "「注意」（必読）［1］｛x｝＜y＞｟z｠"
+        );
+        // 半角の括弧が無いものは変換しない
+        assert_eq!(convert_brackets("『』【】〔〕〈〉《》", false), "『』【】〔〕〈〉《》");
+        // 変換方向と逆の括弧はそのまま
+        assert_eq!(convert_brackets("()「」", false), "()｢｣");
+        assert_eq!(convert_brackets("()「」", true), "（）「」");
+    }
+
+    #[test]
+    fn test_convert_brackets_pairs_roundtrip() {
+        // 開き括弧と閉じ括弧が常に組で変換されること
+        for &(full_open, full_close, half_open, half_close) in BRACKET_PAIRS {
+            let full: String = [full_open, full_close].iter().collect();
+            let half: String = [half_open, half_close].iter().collect();
+            assert_eq!(convert_brackets(&full, false), half);
+            assert_eq!(convert_brackets(&half, true), full);
+        }
+    }
+
+    #[test]
+    fn test_to_hiragana() {
+        assert_eq!(to_hiragana("カタカナ"), "かたかな");
+        assert_eq!(to_hiragana("コンニチハ"), "こんにちは");
+        assert_eq!(to_hiragana("アイウエオ"), "あいうえお");
+        assert_eq!(to_hiragana("ヴァイオリン"), "ゔぁいおりん");
+        // Mixed content
+        assert_eq!(to_hiragana("カタカナABC"), "かたかなABC");
+    }
+
+    #[test]
+    fn test_to_hiragana_with() {
+        let opts = HiraganaOptions {
+            prolonged: ProlongedPolicy::ToVowel,
+            ..Default::default()
+        };
+        assert_eq!(to_hiragana_with("コーヒー", opts), "こうひい");
+        assert_eq!(to_hiragana_with("ケーキ", opts), "けえき");
+        assert_eq!(to_hiragana_with("スーパー", opts), "すうぱあ");
+        assert_eq!(to_hiragana_with("チョーク", opts), "ちょうく");
+        assert_eq!(to_hiragana_with("ヤッホーーー", opts), "やっほううう");
+        assert_eq!(to_hiragana_with("ラーメン", opts), "らあめん");
+        // ひらがなの入力もそのまま展開できる
+        assert_eq!(to_hiragana_with("らーめん", opts), "らあめん");
+        // 文頭・母音を持たない文字の後は展開しない
+        assert_eq!(to_hiragana_with("ーン", opts), "ーん");
+        assert_eq!(to_hiragana_with("ンー", opts), "んー");
+        assert_eq!(to_hiragana_with("ッー", opts), "っー");
+        assert_eq!(to_hiragana_with("漢ー", opts), "漢ー");
+        assert_eq!(to_hiragana_with("Aー", opts), "Aー");
+        // 母音を持たない文字を挟むと直前の母音は引き継がない
+        assert_eq!(to_hiragana_with("カンー", opts), "かんー");
+        // 既定の Keep は to_hiragana と同じ
+        assert_eq!(
+            to_hiragana_with("コーヒー", HiraganaOptions::default()),
+            to_hiragana("コーヒー")
+        );
+
+        let opts = HiraganaOptions {
+            prolonged: ProlongedPolicy::Remove,
+            ..Default::default()
+        };
+        assert_eq!(to_hiragana_with("コーヒー", opts), "こひ");
+        assert_eq!(to_hiragana_with("ヤッホーーー", opts), "やっほ");
+        assert_eq!(to_hiragana_with("ーン", opts), "ん");
+        assert_eq!(to_hiragana_with("ー", opts), "");
+    }
+
+    #[test]
+    fn test_to_hiragana_with_vu_handling() {
+        let with = |vu_handling| HiraganaOptions {
+            vu_handling,
+            ..Default::default()
+        };
+
+        let opts = with(VuHandling::Precomposed);
+        assert_eq!(to_hiragana_with("ヴァイオリン", opts), "ゔぁいおりん");
+        assert_eq!(to_hiragana_with("ヷ", opts), "ヷ");
+
+        let opts = with(VuHandling::Decomposed);
+        assert_eq!(to_hiragana_with("ヴァイオリン", opts), "う\u{3099}ぁいおりん");
+        assert_eq!(to_hiragana_with("ゔ", opts), "う\u{3099}");
+        assert_eq!(to_hiragana_with("ヷヸヹヺ", opts), "わ\u{3099}ゐ\u{3099}ゑ\u{3099}を\u{3099}");
+
+        let opts = with(VuHandling::Fallback);
+        assert_eq!(to_hiragana_with("ヴァヴィヴヴェヴォ", opts), "ばびぶべぼ");
+        assert_eq!(to_hiragana_with("ヴゥ", opts), "ぶ");
+        assert_eq!(to_hiragana_with("ヴュー", opts), "びゅー");
+        assert_eq!(to_hiragana_with("ヴャヴョ", opts), "びゃびょ");
+        assert_eq!(to_hiragana_with("ヴカ", opts), "ぶか");
+        assert_eq!(to_hiragana_with("ヷヸヹヺ", opts), "ばびべぼ");
+        assert_eq!(to_hiragana_with("ヴ", opts), "ぶ");
+
+        // 長音記号の展開と組み合わせる
+        let opts = HiraganaOptions {
+            prolonged: ProlongedPolicy::ToVowel,
+            vu_handling: VuHandling::Decomposed,
+        };
+        assert_eq!(to_hiragana_with("ヴー", opts), "う\u{3099}う");
+        let opts = HiraganaOptions {
+            prolonged: ProlongedPolicy::ToVowel,
+            vu_handling: VuHandling::Fallback,
+        };
+        assert_eq!(to_hiragana_with("ヴァー", opts), "ばあ");
+    }
+
+    #[test]
+    fn test_kana_conversion_fast_path() {
+        // 変換対象の無い入力はそのまま返る
+        let ascii = "2024-01-01T00:00:00Z INFO request handled in 12ms\n".repeat(10);
+        assert_eq!(to_hiragana(&ascii), ascii);
+        assert_eq!(to_katakana(&ascii), ascii);
+        assert_eq!(to_hiragana("漢字、。ＡＢＣ"), "漢字、。ＡＢＣ");
+        // 16バイト単位の読み飛ばしの境界をまたぐ位置の文字も変換する
+        for offset in 0..20 {
+            let input = format!("{}カタカナ{}", "a".repeat(offset), "b".repeat(offset));
+            let expected = format!("{}かたかな{}", "a".repeat(offset), "b".repeat(offset));
+            assert_eq!(to_hiragana(&input), expected);
+            assert_eq!(to_katakana(&expected), input);
+        }
+    }
+
+    #[test]
+    fn test_to_katakana() {
+        assert_eq!(to_katakana("ひらがな"), "ヒラガナ");
+        assert_eq!(to_katakana("こんにちは"), "コンニチハ");
+        assert_eq!(to_katakana("あいうえお"), "アイウエオ");
+        assert_eq!(to_katakana("ゔぁいおりん"), "ヴァイオリン");
+        // Mixed content
+        assert_eq!(to_katakana("ひらがなABC"), "ヒラガナABC");
+    }
+
+    #[test]
+    fn test_kana_char() {
+        assert_eq!(hiragana_char('ア'), 'あ');
+        assert_eq!(hiragana_char('ヶ'), 'ゖ');
+        assert_eq!(hiragana_char('ヽ'), 'ヽ');
+        assert_eq!(hiragana_char('あ'), 'あ');
+        assert_eq!(hiragana_char('ｱ'), 'ｱ');
+        assert_eq!(katakana_char('あ'), 'ア');
+        assert_eq!(katakana_char('ゔ'), 'ヴ');
+        assert_eq!(katakana_char('ゞ'), 'ゞ');
+        assert_eq!(katakana_char('ア'), 'ア');
+        assert_eq!(katakana_char('漢'), '漢');
+
+        // 文字列版と1文字ずつの変換は一致する
+        let text = "ひらがなとカタカナ、ゝヽゟヿ漢字ABC";
+        assert_eq!(to_hiragana(text), text.chars().map(hiragana_char).collect::<String>());
+        assert_eq!(to_katakana(text), text.chars().map(katakana_char).collect::<String>());
+    }
+
+    #[test]
+    fn test_kana_conversion_range_edges() {
+        // 範囲の先頭と末尾
+        assert_eq!(to_katakana("ぁ"), "ァ");
+        assert_eq!(to_katakana("ゔゕゖ"), "ヴヵヶ");
+        assert_eq!(to_hiragana("ァ"), "ぁ");
+        assert_eq!(to_hiragana("ヴヵヶ"), "ゔゕゖ");
+        // 範囲の外側の隣接文字はそのまま
+        assert_eq!(to_katakana("\u{3040}\u{3097}\u{3098}"), "\u{3040}\u{3097}\u{3098}");
+        assert_eq!(to_hiragana("゠ヷヸヹヺ・ー"), "゠ヷヸヹヺ・ー");
+        // 合字（ゟ・ヿ）は対応する文字が無いため変換しない
+        assert_eq!(to_katakana("ゟ"), "ゟ");
+        assert_eq!(to_hiragana("ヿ"), "ヿ");
+        // 濁点・半濁点は共通の文字
+        assert_eq!(to_katakana("\u{3099}\u{309A}゛゜"), "\u{3099}\u{309A}゛゜");
+        assert_eq!(to_hiragana("\u{3099}\u{309A}゛゜"), "\u{3099}\u{309A}゛゜");
+    }
+
+    #[test]
+    fn test_to_half_width_url() {
+        assert_eq!(
+            to_half_width("ｈｔｔｐｓ：／／ｅｘａｍｐｌｅ．ｃｏｍ"),
+            "https://example.com"
+        );
+        assert_eq!(
+            to_half_width("ｈｔｔｐｓ：／／ｅｘａｍｐｌｅ．ｃｏｍ：８０８０／～ｕｓｅｒ／ｐａｔｈ＿１－２"),
+            "https://example.com:8080/~user/path_1-2"
+        );
+        // クエリ文字列・パーセントエンコーディング・フラグメント
+        assert_eq!(
+            to_half_width("／ｓｅａｒｃｈ？ｑ＝％Ｅ３％８１％８２＆ｌａｎｇ＝ｊａ；ｘ＝１＃ｔｏｐ"),
+            "/search?q=%E3%81%82&lang=ja;x=1#top"
+        );
+        assert_eq!(
+            to_half_width("［：：１］＋｛ａ｝｜＇＂＄＊，＜＞＼＾｀！（）"),
+            "[::1]+{a}|'\"$*,<>\\^`!()"
+        );
+        // メールアドレス
+        assert_eq!(
+            to_half_width("ｕｓｅｒ．ｎａｍｅ＋ｔａｇ＠ｅｘａｍｐｌｅ．ｃｏ．ｊｐ"),
+            "user.name+tag@example.co.jp"
+        );
+        // 波ダッシュは全角チルダではないため変換しない
+        assert_eq!(to_half_width("／〜ｕｓｅｒ"), "/〜user");
+    }
+
+    #[test]
+    fn test_to_half_width_all_ascii_symbols() {
+        for code in 0x21u32..=0x7E {
+            let ascii = char::from_u32(code).unwrap();
+            let full = char::from_u32(code - 0x21 + 0xFF01).unwrap();
+            assert_eq!(to_half_width(&full.to_string()), ascii.to_string());
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_full_half_width() {
+        let original = "ABC123!@#";
+        let full = to_full_width(original);
+        let back = to_half_width(&full);
+        assert_eq!(original, back);
+    }
+
+    #[test]
+    fn test_roundtrip_hiragana_katakana() {
+        let original = "こんにちは";
+        let katakana = to_katakana(original);
+        let back = to_hiragana(&katakana);
+        assert_eq!(original, back);
+    }
+
+    #[test]
+    fn test_empty_string() {
+        assert_eq!(to_half_width(""), "");
+        assert_eq!(to_full_width(""), "");
+        assert_eq!(to_hiragana(""), "");
+        assert_eq!(to_katakana(""), "");
+    }
+
+    #[test]
+    fn test_is_hiragana() {
+        assert_eq!(is_hiragana('あ'), true);
+        assert_eq!(is_hiragana('ん'), true);
+        assert_eq!(is_hiragana('ア'), false);
+        assert_eq!(is_hiragana('A'), false);
+        assert_eq!(is_hiragana('漢'), false);
+    }
+
+    #[test]
+    fn test_is_hiragana_strict() {
+        assert!(is_hiragana_strict('ぁ'));
+        assert!(is_hiragana_strict('ゔ'));
+        assert!(is_hiragana_strict('ゖ'));
+        assert!(!is_hiragana_strict('\u{3099}'));
+        assert!(!is_hiragana_strict('ゝ'));
+        assert!(!is_hiragana_strict('ゞ'));
+        assert!(!is_hiragana_strict('ゟ'));
+        assert!(!is_hiragana_strict('ア'));
+        // is_hiragana と同じ判定
+        for c in '\u{3000}'..='\u{30FF}' {
+            assert_eq!(is_hiragana_strict(c), is_hiragana(c));
+        }
+    }
+
+    #[test]
+    fn test_is_hiragana_block() {
+        assert!(is_hiragana_block('あ'));
+        assert!(is_hiragana_block('\u{3099}'));
+        assert!(is_hiragana_block('゛'));
+        assert!(is_hiragana_block('ゝ'));
+        assert!(is_hiragana_block('ゞ'));
+        assert!(is_hiragana_block('ゟ'));
+        assert!(!is_hiragana_block('゠'));
+        assert!(!is_hiragana_block('ア'));
+        assert!(!is_hiragana_block('漢'));
+        // 集計は音節文字のみを対象とする
+        assert_eq!(count_character_types("ゟゝ").hiragana, 0);
+    }
+
+    #[test]
+    fn test_is_katakana() {
+        assert_eq!(is_katakana('ア'), true);
+        assert_eq!(is_katakana('ン'), true);
+        assert_eq!(is_katakana('あ'), false);
+        assert_eq!(is_katakana('A'), false);
+    }
+
+    #[test]
+    fn test_is_katakana_phonetic_ext() {
+        assert!(is_katakana_phonetic_ext('ㇰ'));
+        assert!(is_katakana_phonetic_ext('ㇷ'));
+        assert!(is_katakana_phonetic_ext('ㇿ'));
+        assert!(!is_katakana_phonetic_ext('ア'));
+        assert!(!is_katakana_phonetic_ext('ァ'));
+        assert!(!is_katakana_phonetic_ext('ｱ'));
+        // to_hiragana では変換されない
+        assert_eq!(to_hiragana("アイヌㇷ゚"), "あいぬㇷ゚");
+    }
+
+    #[test]
+    fn test_is_half_width_katakana() {
+        assert_eq!(is_half_width_katakana('ｱ'), true);
+        assert_eq!(is_half_width_katakana('ﾝ'), true);
+        assert_eq!(is_half_width_katakana('ア'), false);
+        assert_eq!(is_half_width_katakana('A'), false);
+    }
+
+    #[test]
+    fn test_is_archaic_kana() {
+        for c in "ゐゑをゎゝゞヰヱヲヮヽヾヷヸヹヺ".chars() {
+            assert!(is_archaic_kana(c), "{}", c);
+        }
+        assert!(is_archaic_kana('\u{1B000}'));
+        assert!(is_archaic_kana('\u{1B16F}'));
+        assert!(!is_archaic_kana('あ'));
+        assert!(!is_archaic_kana('漢'));
+    }
+
+    #[test]
+    fn test_archaic_kana_conversion() {
+        assert_eq!(to_hiragana("ヰヱヲヮ"), "ゐゑをゎ");
+        assert_eq!(to_katakana("ゐゑをゎ"), "ヰヱヲヮ");
+        // 変体仮名は対応するかながないためそのまま
+        assert_eq!(to_hiragana("\u{1B001}"), "\u{1B001}");
+        // 繰り返し記号は変換しない
+        assert_eq!(to_hiragana("トヽキヾ"), "とヽきヾ");
+        assert_eq!(to_katakana("いすゞ"), "イスゞ");
+        // 対応するかなが無い文字はそのまま残す
+        assert_eq!(to_hiragana("ヷヺヿ"), "ヷヺヿ");
+        assert_eq!(to_hiragana("ㇰㇱㇷ"), "ㇰㇱㇷ");
+        assert_eq!(to_hiragana("㋐㋾"), "㋐㋾");
+        assert_eq!(to_katakana("ゟ"), "ゟ");
+    }
+
+    #[test]
+    fn test_is_kanji() {
+        assert_eq!(is_kanji('漢'), true);
+        assert_eq!(is_kanji('字'), true);
+        assert_eq!(is_kanji('あ'), false);
+        assert_eq!(is_kanji('A'), false);
+        // 拡張A・拡張B・互換漢字
+        assert!(is_kanji('㐀'));
+        assert!(is_kanji('𠮷'));
+        assert!(is_kanji('\u{F900}'));
+    }
+
+    #[test]
+    fn test_is_kanji_range_boundaries() {
+        let cases = [
+            ('\u{33FF}', '\u{3400}', '\u{4DBF}', '\u{4DC0}'),
+            ('\u{4DFF}', '\u{4E00}', '\u{9FFF}', '\u{A000}'),
+            ('\u{F8FF}', '\u{F900}', '\u{FAFF}', '\u{FB00}'),
+            ('\u{1FFFF}', '\u{20000}', '\u{2A6DF}', '\u{2A6E0}'),
+            ('\u{2A6FF}', '\u{2A700}', '\u{2EBEF}', '\u{2EBF0}'),
+            ('\u{2F7FF}', '\u{2F800}', '\u{2FA1F}', '\u{2FA20}'),
+            ('\u{2FFFF}', '\u{30000}', '\u{323AF}', '\u{323B0}'),
+        ];
+        for (before, first, last, after) in cases {
+            assert!(!is_kanji(before), "{:X}", before as u32);
+            assert!(is_kanji(first), "{:X}", first as u32);
+            assert!(is_kanji(last), "{:X}", last as u32);
+            assert!(!is_kanji(after), "{:X}", after as u32);
+        }
+
+        // 拡張・互換漢字は文字種の集計や抽出でも漢字として扱う
+        let input = "㐀𠮷\u{F900}\u{2F800}\u{30000}";
+        assert_eq!(count_character_types(input).kanji, 5);
+        assert_eq!(classify('𠮷'), CharClass::Kanji);
+        assert_eq!(extract_kanji(input), input);
+    }
+
+    #[test]
+    fn test_tables() {
+        assert!(tables::HIRAGANA_RANGE.contains(&'ゖ'));
+        assert!(!tables::HIRAGANA_RANGE.contains(&'ゝ'));
+        assert!(tables::KATAKANA_RANGE.contains(&'ヶ'));
+        assert!(!tables::KATAKANA_RANGE.contains(&'ー'));
+        for range in tables::KANJI_RANGES {
+            assert!(range.start() <= range.end());
+            assert!(is_kanji(*range.start()));
+            assert!(is_kanji(*range.end()));
+        }
+    }
+
+    #[test]
+    fn test_is_full_width() {
+        assert_eq!(is_full_width('Ａ'), true);
+        assert_eq!(is_full_width('１'), true);
+        assert_eq!(is_full_width('　'), true);
+        assert_eq!(is_full_width('A'), false);
+    }
+
+    #[test]
+    fn test_is_wide() {
+        assert!(is_wide('漢'));
+        assert!(is_wide('あ'));
+        assert!(is_wide('カ'));
+        assert!(is_wide('ー'));
+        assert!(is_wide('　'));
+        assert!(is_wide('。'));
+        assert!(is_wide('Ａ'));
+        assert!(is_wide('￥'));
+        assert!(is_wide('😀'));
+        assert!(is_wide('𠮷'));
+        assert!(!is_wide('ｶ'));
+        assert!(!is_wide('ﾞ'));
+        assert!(!is_wide('A'));
+        assert!(!is_wide(' '));
+        assert!(!is_wide('é'));
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify('あ'), CharClass::Hiragana);
+        assert_eq!(classify('ア'), CharClass::Katakana);
+        assert_eq!(classify('ｱ'), CharClass::HalfWidthKatakana);
+        assert_eq!(classify('漢'), CharClass::Kanji);
+        assert_eq!(classify('A'), CharClass::Ascii);
+        assert_eq!(classify('Ａ'), CharClass::FullWidth);
+        assert_eq!(classify('　'), CharClass::FullWidth);
+        assert_eq!(classify('、'), CharClass::Punctuation);
+        assert_eq!(classify('「'), CharClass::Punctuation);
+        assert_eq!(classify('・'), CharClass::Punctuation);
+        assert_eq!(classify('ー'), CharClass::Other);
+        assert_eq!(classify('😀'), CharClass::Other);
+    }
+
+    #[test]
+    fn test_segment_by_script() {
+        assert_eq!(
+            segment_by_script("東京タワー123"),
+            vec![
+                (CharClass::Kanji, "東京"),
+                (CharClass::Katakana, "タワー"),
+                (CharClass::Ascii, "123"),
+            ]
+        );
+        assert_eq!(
+            segment_by_script("漢字とカナ。"),
+            vec![
+                (CharClass::Kanji, "漢字"),
+                (CharClass::Hiragana, "と"),
+                (CharClass::Katakana, "カナ"),
+                (CharClass::Punctuation, "。"),
+            ]
+        );
+        // 中黒はデフォルトでは区切りになる
+        assert_eq!(
+            segment_by_script("アイス・コーヒー"),
+            vec![
+                (CharClass::Katakana, "アイス"),
+                (CharClass::Punctuation, "・"),
+                (CharClass::Katakana, "コーヒー"),
+            ]
+        );
+        // カタカナ以外の後の長音記号は独立したラン
+        assert_eq!(
+            segment_by_script("ーあ"),
+            vec![(CharClass::Other, "ー"), (CharClass::Hiragana, "あ")]
+        );
+        assert!(segment_by_script("").is_empty());
+    }
+
+    #[test]
+    fn test_segment_by_script_with() {
+        let opts = SegmentOptions {
+            prolonged_sound_in_katakana: true,
+            middle_dot_in_katakana: true,
+        };
+        assert_eq!(
+            segment_by_script_with("アイス・コーヒー", opts),
+            vec![(CharClass::Katakana, "アイス・コーヒー")]
+        );
+        // 末尾の中黒はカタカナに挟まれていないので含めない
+        assert_eq!(
+            segment_by_script_with("アイス・", opts),
+            vec![(CharClass::Katakana, "アイス"), (CharClass::Punctuation, "・")]
+        );
+        let opts = SegmentOptions {
+            prolonged_sound_in_katakana: false,
+            middle_dot_in_katakana: false,
+        };
+        assert_eq!(
+            segment_by_script_with("タワー", opts),
+            vec![(CharClass::Katakana, "タワ"), (CharClass::Other, "ー")]
+        );
+        // デフォルトは segment_by_script と同じ
+        for input in ["タワー", "アイス・コーヒーを", "ーあ", ""] {
+            assert_eq!(
+                segment_by_script_with(input, SegmentOptions::default()),
+                segment_by_script(input)
+            );
+        }
+    }
+
+    #[test]
+    fn test_match_run() {
+        assert_eq!(match_run("ひらがな漢字", is_hiragana), Some(("ひらがな", "漢字")));
+        assert_eq!(match_run("カタカナ", is_katakana), Some(("カタカナ", "")));
+        assert_eq!(match_run("漢字かな", is_hiragana), None);
+        assert_eq!(match_run("", is_hiragana), None);
+        assert_eq!(match_run("123abc", |c| c.is_ascii_digit()), Some(("123", "abc")));
+
+        // 返り値は元の文字列のスライス
+        let input = "あいう漢字";
+        let (run, rest) = match_run(input, is_hiragana).unwrap();
+        assert_eq!(run.as_ptr(), input.as_ptr());
+        assert_eq!(rest.as_ptr(), input[9..].as_ptr());
+    }
+
+    #[test]
+    fn test_segment_by_script_with_middle_dot_edges() {
+        let opts = SegmentOptions {
+            prolonged_sound_in_katakana: true,
+            middle_dot_in_katakana: true,
+        };
+        assert_eq!(
+            segment_by_script_with("ア・・イ", opts),
+            vec![
+                (CharClass::Katakana, "ア"),
+                (CharClass::Punctuation, "・・"),
+                (CharClass::Katakana, "イ"),
+            ]
+        );
+        assert_eq!(
+            segment_by_script_with("アー・イ・ー", opts),
+            vec![
+                (CharClass::Katakana, "アー・イ"),
+                (CharClass::Punctuation, "・"),
+                (CharClass::Other, "ー"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trim_script() {
+        assert_eq!(trim_script("！！！重要！！！", CharClass::FullWidth), "重要");
+        assert_eq!(trim_script("【告知】", CharClass::Punctuation), "告知");
+        assert_eq!(trim_script("かな漢字かな", CharClass::Hiragana), "漢字");
+        // 途中の文字は残す
+        assert_eq!(trim_script("。あ。い。", CharClass::Punctuation), "あ。い");
+        // すべて取り除かれる場合と該当しない場合
+        assert_eq!(trim_script("。。", CharClass::Punctuation), "");
+        assert_eq!(trim_script("漢字", CharClass::Hiragana), "漢字");
+        assert_eq!(trim_script("", CharClass::Kanji), "");
+
+        // 元の文字列のスライスを返す
+        let input = "　本文　";
+        let trimmed = trim_script(input, CharClass::FullWidth);
+        assert_eq!(trimmed, "本文");
+        assert_eq!(trimmed.as_ptr(), input[3..].as_ptr());
+    }
+
+    #[test]
+    fn test_trim_start_script() {
+        assert_eq!(trim_start_script("　　本文　", CharClass::FullWidth), "本文　");
+        assert_eq!(trim_start_script("ｱｲｳabc", CharClass::HalfWidthKatakana), "abc");
+        assert_eq!(trim_start_script("abc", CharClass::Kanji), "abc");
+    }
+
+    #[test]
+    fn test_trim_end_script() {
+        assert_eq!(trim_end_script("東京都。", CharClass::Punctuation), "東京都");
+        assert_eq!(trim_end_script("！重要！！", CharClass::FullWidth), "！重要");
+        assert_eq!(trim_end_script("abc", CharClass::Kanji), "abc");
+    }
+
+    #[test]
+    fn test_is_all_hiragana() {
+        assert!(is_all_hiragana("ひらがな"));
+        assert!(is_all_hiragana("ぁゖ"));
+        assert!(!is_all_hiragana("らーめん"));
+        assert!(!is_all_hiragana("ひらがなカタカナ"));
+        assert!(!is_all_hiragana("ひらがな "));
+        assert!(!is_all_hiragana(""));
+
+        assert!(is_all_hiragana_lenient("らーめん"));
+        assert!(is_all_hiragana_lenient("あ・い"));
+        assert!(!is_all_hiragana_lenient("ー・"));
+        assert!(!is_all_hiragana_lenient("ラーメン"));
+        assert!(!is_all_hiragana_lenient(""));
+    }
+
+    #[test]
+    fn test_is_all_katakana() {
+        assert!(is_all_katakana("カタカナ"));
+        assert!(!is_all_katakana("コーヒー"));
+        assert!(!is_all_katakana("ｶﾀｶﾅ"));
+        assert!(!is_all_katakana(""));
+
+        assert!(is_all_katakana_lenient("コーヒー"));
+        assert!(is_all_katakana_lenient("ジョン・スミス"));
+        assert!(!is_all_katakana_lenient("ー"));
+        assert!(!is_all_katakana_lenient("コーヒーとケーキ"));
+        // 半角カタカナは全角にそろえてから判定する
+        assert!(is_all_katakana_lenient(&half_width_katakana_to_full_width("ｺｰﾋｰ")));
+    }
+
+    #[test]
+    fn test_is_all_kana() {
+        assert!(is_all_kana("ひらがなとカタカナ"));
+        assert!(!is_all_kana("スーパー"));
+        assert!(!is_all_kana("かな漢字"));
+        assert!(!is_all_kana(""));
+
+        assert!(is_all_kana_lenient("すーぱー・マーケット"));
+        assert!(!is_all_kana_lenient("・"));
+    }
+
+    #[test]
+    fn test_contains_half_width_katakana() {
+        assert!(contains_half_width_katakana("ｱ"));
+        assert!(contains_half_width_katakana("abcﾞ"));
+        assert!(contains_half_width_katakana("価格｡"));
+        assert!(!contains_half_width_katakana("アイウ"));
+        assert!(!contains_half_width_katakana("ABC"));
+        assert!(!contains_half_width_katakana(""));
+    }
+
+    #[test]
+    fn test_contains_full_width_ascii() {
+        assert!(contains_full_width_ascii("ＡＢＣ"));
+        assert!(contains_full_width_ascii("abc！"));
+        assert!(contains_full_width_ascii("～"));
+        assert!(!contains_full_width_ascii("　"));
+        assert!(!contains_full_width_ascii("abc、。"));
+        assert!(!contains_full_width_ascii(""));
+    }
+
+    #[test]
+    fn test_contains_kanji() {
+        assert!(contains_kanji("漢"));
+        assert!(contains_kanji("ひらがなと𠮷"));
+        assert!(!contains_kanji("ひらがなとカタカナ"));
+        assert!(!contains_kanji("〇々"));
+        assert!(!contains_kanji(""));
+    }
+
+    #[test]
+    fn test_kana_voicing() {
+        assert_eq!(kana_voicing('か'), Some(Voicing::Plain));
+        assert_eq!(kana_voicing('あ'), Some(Voicing::Plain));
+        assert_eq!(kana_voicing('ン'), Some(Voicing::Plain));
+        assert_eq!(kana_voicing('が'), Some(Voicing::Voiced));
+        assert_eq!(kana_voicing('ヴ'), Some(Voicing::Voiced));
+        assert_eq!(kana_voicing('ヺ'), Some(Voicing::Voiced));
+        assert_eq!(kana_voicing('ぱ'), Some(Voicing::SemiVoiced));
+        assert_eq!(kana_voicing('ポ'), Some(Voicing::SemiVoiced));
+        assert_eq!(kana_voicing('ｶ'), None);
+        assert_eq!(kana_voicing('ー'), None);
+        assert_eq!(kana_voicing('ゞ'), None);
+        assert_eq!(kana_voicing('A'), None);
+
+        for &(base, voiced, semi_voiced) in tables::VOICING_HIRAGANA
+            .iter()
+            .chain(tables::VOICING_KATAKANA)
+        {
+            assert_eq!(kana_voicing(base), Some(Voicing::Plain));
+            assert_eq!(kana_voicing(voiced), Some(Voicing::Voiced));
+            if let Some(semi_voiced) = semi_voiced {
+                assert_eq!(kana_voicing(semi_voiced), Some(Voicing::SemiVoiced));
+            }
+        }
+    }
+
+    #[test]
+    fn test_toggle_dakuten() {
+        assert_eq!(toggle_dakuten('か'), 'が');
+        assert_eq!(toggle_dakuten('が'), 'か');
+        assert_eq!(toggle_dakuten('ハ'), 'バ');
+        assert_eq!(toggle_dakuten('パ'), 'バ');
+        assert_eq!(toggle_dakuten('う'), 'ゔ');
+        assert_eq!(toggle_dakuten('ワ'), 'ヷ');
+        assert_eq!(toggle_dakuten('あ'), 'あ');
+        assert_eq!(toggle_dakuten('漢'), '漢');
+
+        for &(base, voiced, _) in tables::VOICING_HIRAGANA.iter().chain(tables::VOICING_KATAKANA) {
+            assert_eq!(toggle_dakuten(toggle_dakuten(base)), base);
+            assert_eq!(toggle_dakuten(voiced), base);
+        }
+    }
+
+    #[test]
+    fn test_is_all_kanji() {
+        assert!(is_all_kanji("漢字"));
+        assert!(is_all_kanji("𠮷野"));
+        assert!(!is_all_kanji("佐々木"));
+        assert!(!is_all_kanji("漢字かな"));
+        assert!(!is_all_kanji(""));
+        // 全角英数字の変換の前後で結果は変わらない
+        assert_eq!(is_all_kanji("漢字Ａ"), is_all_kanji(&to_half_width("漢字Ａ")));
+    }
+
+    #[test]
+    fn test_mask_by_type() {
+        assert_eq!(mask_by_type("山田Taro", &[CharClass::Ascii], '*'), "山田****");
+        assert_eq!(mask_by_type("山田Taro", &[CharClass::Kanji], '*'), "**Taro");
+        assert_eq!(
+            mask_by_type("ID：１２3", &[CharClass::Ascii, CharClass::FullWidth], '#'),
+            "######"
+        );
+        assert_eq!(mask_by_type("あア", &[], '*'), "あア");
+        assert_eq!(mask_by_type("", &[CharClass::Ascii], '*'), "");
+
+        // マルチバイトのマスク文字でも文字数は変わらない
+        let input = "abcあいう𠮷";
+        let masked = mask_by_type(input, &[CharClass::Ascii, CharClass::Kanji], '𝄞');
+        assert_eq!(masked, "𝄞𝄞𝄞あいう𝄞");
+        assert_eq!(masked.chars().count(), input.chars().count());
+    }
+
+    #[test]
+    fn test_mask_digits() {
+        assert_eq!(mask_digits("電話: 03-1234-５６７８", '*'), "電話: **-****-****");
+        assert_eq!(mask_digits("三百", '*'), "三百");
+        assert_eq!(mask_digits("ＡＢＣ", '*'), "ＡＢＣ");
+        assert_eq!(mask_digits("2024年", '〇'), "〇〇〇〇年");
+    }
+
+    #[test]
+    fn test_is_emoji() {
+        for c in ['😀', '☀', '✅', '🚀', '🤔', '🫠', '🇯', '\u{FE0F}', '\u{1F3FB}'] {
+            assert!(is_emoji(c), "{:?}", c);
+        }
+        for c in ['あ', 'A', '漢', '\u{200D}', '※'] {
+            assert!(!is_emoji(c), "{:?}", c);
+        }
+    }
+
+    #[test]
+    fn test_strip_emoji() {
+        assert_eq!(strip_emoji("今日は晴れ☀️です😀"), "今日は晴れです");
+        // ZWJ で結合された複合絵文字
+        assert_eq!(strip_emoji("👨\u{200D}👩\u{200D}👧\u{200D}👦家族"), "家族");
+        // 肌の色の修飾子
+        assert_eq!(strip_emoji("👍🏽OK"), "OK");
+        // 国旗（地域指示記号の組）
+        assert_eq!(strip_emoji("🇯🇵日本"), "日本");
+        // 絵文字に挟まれていない ZWJ は残す
+        assert_eq!(strip_emoji("a\u{200D}b"), "a\u{200D}b");
+        assert_eq!(strip_emoji("😀\u{200D}a"), "\u{200D}a");
+        assert_eq!(strip_emoji("絵文字なし"), "絵文字なし");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_text_features() {
+        let empty = text_features("");
+        assert_eq!(empty.char_count, 0);
+        assert_eq!(empty.types, count_character_types(""));
+        assert_eq!(empty.hiragana_ratio, 0.0);
+        assert_eq!(empty.average_mora_length, 0.0);
+        assert_eq!(empty.char_entropy, 0.0);
+        assert_eq!(empty.type_entropy, 0.0);
+
+        let features = text_features("あいうABCD");
+        assert_eq!(features.char_count, 7);
+        assert_eq!(features.types, count_character_types("あいうABCD"));
+        assert_eq!(features.hiragana_ratio, 3.0 / 7.0);
+        assert_eq!(features.ascii_ratio, 4.0 / 7.0);
+        assert_eq!(features.kanji_ratio, 0.0);
+        assert_eq!(features.average_mora_length, 3.0);
+        // すべて異なる文字なので log2(7)
+        assert!((features.char_entropy - 7f64.log2()).abs() < 1e-12);
+
+        // 半角カタカナもカタカナの比率に含め、モーラは濁点をまとめて数える
+        let features = text_features("ｶﾞｯｺｳとガッコウ。");
+        assert_eq!(features.katakana_ratio, 9.0 / 11.0);
+        assert_eq!(features.punctuation_ratio, 1.0 / 11.0);
+        assert_eq!(features.average_mora_length, 3.0);
+
+        // 同じ文字・同じ文字種だけならエントロピーは0
+        let features = text_features("ああああ");
+        assert_eq!(features.char_entropy, 0.0);
+        assert_eq!(features.type_entropy, 0.0);
+        // 2種類が半分ずつなら1ビット
+        let features = text_features("あア");
+        assert_eq!(features.char_entropy, 1.0);
+        assert_eq!(features.type_entropy, 1.0);
+    }
+
+    #[test]
+    fn test_japanese_score() {
+        assert_eq!(japanese_score("ひらがな"), 1.0);
+        assert_eq!(japanese_score("の"), 1.0);
+        assert!(japanese_score("今日はいい天気ですね。") > 0.7);
+        assert!(japanese_score("日本語のテキストです。ABC") > 0.5);
+        // 英語・中国語はひらがなを含まないため低い
+        assert_eq!(japanese_score("Hello, world!"), 0.0);
+        assert!(japanese_score("我们今天去北京") <= 0.25);
+        // ひらがなを含まない短い文字列
+        assert_eq!(japanese_score("東京"), 0.25);
+        // 空白・数字・ASCII記号は対象外
+        assert_eq!(japanese_score("あ 123 !?"), 1.0);
+        assert_eq!(japanese_score(""), 0.0);
+        assert_eq!(japanese_score("123 !?"), 0.0);
+    }
+
+    #[test]
+    fn test_japanese_score_with() {
+        let weights = ScoreWeights {
+            kanji: 1.0,
+            no_hiragana_factor: 1.0,
+            ..ScoreWeights::default()
+        };
+        assert_eq!(japanese_score_with("東京", weights), 1.0);
+        assert_eq!(japanese_score_with("東京ab", weights), 0.5);
+
+        // 範囲外の重みでも結果は 0.0 から 1.0 に収まる
+        let weights = ScoreWeights {
+            hiragana: 3.0,
+            ..ScoreWeights::default()
+        };
+        assert_eq!(japanese_score_with("ひらがな", weights), 1.0);
+    }
+
+    #[test]
+    fn test_is_variation_selector() {
+        assert!(is_variation_selector('\u{FE00}'));
+        assert!(is_variation_selector('\u{FE0E}'));
+        assert!(is_variation_selector('\u{E0100}'));
+        assert!(is_variation_selector('\u{E01EF}'));
+        assert!(!is_variation_selector('\u{FE10}'));
+        assert!(!is_variation_selector('\u{E01F0}'));
+        assert!(!is_variation_selector('\u{3099}'));
+        assert!(!is_variation_selector('漢'));
+        // 文字種カウントでは other に数えられる
+        let counts = count_character_types("渡邊\u{E010A}");
+        assert_eq!(counts.kanji, 2);
+        assert_eq!(counts.other, 1);
+    }
+
+    #[test]
+    fn test_strip_variation_selectors() {
+        assert_eq!(strip_variation_selectors("渡邊\u{E010A}"), "渡邊");
+        assert_eq!(strip_variation_selectors("葛\u{E0100}飾\u{E0101}区"), "葛飾区");
+        assert_eq!(strip_variation_selectors("☀\u{FE0F}晴れ"), "☀晴れ");
+        // 異体字セレクタの有無が異なる文字列を照合できる
+        assert_eq!(
+            strip_variation_selectors("渡邊\u{E010A}"),
+            strip_variation_selectors("渡邊")
+        );
+        // 結合用濁点など、他の結合文字は残す
+        assert_eq!(strip_variation_selectors("か\u{3099}"), "か\u{3099}");
+        assert_eq!(strip_variation_selectors(""), "");
+    }
+
+    #[test]
+    fn test_count_width_weighted() {
+        assert_eq!(count_width_weighted(""), 0);
+        assert_eq!(count_width_weighted("Hello, World!\n"), 14);
+        assert_eq!(count_width_weighted("こんにちは"), 10);
+        assert_eq!(count_width_weighted("ＡＢＣ　１２３"), 14);
+        assert_eq!(count_width_weighted("漢字、。「」"), 12);
+        assert_eq!(count_width_weighted("ｶﾞｷﾞ｡"), 5);
+        assert_eq!(count_width_weighted("😀𠮷"), 4);
+        assert_eq!(count_width_weighted("é"), 2);
+        assert_eq!(count_width_weighted("東京タワー 333m"), 15);
+
+        let opts = WidthCountOptions { half_width_katakana_as_full: true };
+        assert_eq!(count_width_weighted_with("ｶﾞｷﾞ｡", opts), 10);
+        assert_eq!(count_width_weighted_with("ABC", opts), 3);
+    }
+
+    #[test]
+    fn test_count_graphemes() {
+        assert_eq!(count_graphemes(""), 0);
+        assert_eq!(count_graphemes("日本語"), 3);
+        // 異体字セレクタ付きの漢字
+        assert_eq!(count_graphemes("葛\u{E0100}城"), 2);
+        // 結合用濁点・半角濁点
+        assert_eq!(count_graphemes("か\u{3099}き"), 2);
+        assert_eq!(count_graphemes("ｶﾞｷﾞ"), 2);
+        // 結合文字
+        assert_eq!(count_graphemes("e\u{0301}"), 1);
+        // ZWJ シーケンス・肌の色・国旗
+        assert_eq!(count_graphemes("👨\u{200D}👩\u{200D}👧"), 1);
+        assert_eq!(count_graphemes("👍\u{1F3FD}"), 1);
+        assert_eq!(count_graphemes("🇯🇵🇺🇸"), 2);
+        assert_eq!(count_graphemes("a\r\nb"), 3);
+        // ZWJ は絵文字どうしの間でだけ結合する
+        assert_eq!(count_graphemes("a\u{200D}😀"), 2);
+        assert_eq!(count_graphemes("😀\u{200D}"), 1);
+        // ハングルの字母は音節として結合する
+        assert_eq!(count_graphemes("\u{1100}\u{1161}\u{11A8}"), 1);
+        assert_eq!(count_graphemes("\u{AC00}\u{11A8}\u{1100}"), 2);
+        assert_eq!(count_graphemes("한국어"), 3);
+    }
+
+    #[cfg(feature = "grapheme")]
+    #[test]
+    fn test_count_graphemes_unicode_segmentation() {
+        // スペーシングマークと前置文字は feature 有効時のみ結合する
+        assert_eq!(count_graphemes("\u{0915}\u{093F}"), 1);
+        assert_eq!(count_graphemes("\u{0600}1"), 1);
+        assert_eq!(visual_length("ｶﾞ\u{0915}\u{093F}"), 2);
+    }
+
+    #[test]
+    fn test_visual_length() {
+        assert_eq!(visual_length(""), 0);
+        assert_eq!(visual_length("ｶﾀｶﾅ"), 4);
+        // 濁点・半濁点の表記によらず同じ数
+        for input in ["ｶﾞﾊﾟ", "ガパ", "カ\u{3099}ハ\u{309A}", "ｶ\u{3099}ﾊﾟ"] {
+            assert_eq!(visual_length(input), 2, "{}", input);
+        }
+        // 全角・結合できない半角の濁点は独立した1文字
+        assert_eq!(visual_length("カ゛"), 2);
+        assert_eq!(visual_length("ｱﾞ"), 2);
+        assert_eq!(visual_length("ﾞ"), 1);
+        // 結合文字・異体字セレクタ・絵文字
+        assert_eq!(visual_length("葛\u{E0100}飾"), 2);
+        assert_eq!(visual_length("e\u{0301}"), 1);
+        assert_eq!(visual_length("👨\u{200D}👩\u{200D}👧ｶﾞ"), 2);
+
+        // 全角にそろえても変わらない
+        let mixed = "ﾃﾞｰﾀとデータ、ｱﾞｲ\u{3099}";
+        assert_eq!(visual_length(mixed), visual_length(&half_width_katakana_to_full_width(mixed)));
+        assert_eq!(visual_length(mixed), 11);
+    }
+
+    #[test]
+    fn test_graphemes() {
+        let graphemes: Vec<&str> = Graphemes::new("ｶﾞ葛\u{E0100}\r\n🇯🇵").collect();
+        assert_eq!(graphemes, vec!["ｶﾞ", "葛\u{E0100}", "\r\n", "🇯🇵"]);
+    }
+
+    #[test]
+    fn test_count_character_types() {
+        let counts = count_character_types("あア漢ABC123ｱｲｳ");
+        assert_eq!(counts.hiragana, 1);
+        assert_eq!(counts.katakana, 1);
+        assert_eq!(counts.kanji, 1);
+        assert_eq!(counts.ascii, 6);
+        assert_eq!(counts.half_width_katakana, 3);
+
+        let counts = count_character_types("「はい」、ー😀");
+        assert_eq!(counts.hiragana, 2);
+        assert_eq!(counts.punctuation, 3);
+        assert_eq!(counts.other, 2);
+    }
+
+    #[test]
+    fn test_character_types_add_sub() {
+        let a = count_character_types("あいアｶ漢");
+        let b = count_character_types("あアア。");
+
+        let sum = a.clone() + b.clone();
+        assert_eq!(sum.hiragana, 3);
+        assert_eq!(sum.katakana, 3);
+        assert_eq!(sum.half_width_katakana, 1);
+        assert_eq!(sum.kanji, 1);
+        assert_eq!(sum.punctuation, 1);
+
+        // 飽和減算のため負にはならない
+        let diff = a.clone() - b.clone();
+        assert_eq!(diff.hiragana, 1);
+        assert_eq!(diff.katakana, 0);
+        assert_eq!(diff.half_width_katakana, 1);
+        assert_eq!(diff.kanji, 1);
+        assert_eq!(diff.punctuation, 0);
+
+        assert_eq!(sum - b, a);
+        assert_eq!(a.clone() - a.clone(), count_character_types(""));
+    }
+
+    #[test]
+    fn test_normalize_whitespace() {
+        assert_eq!(normalize_whitespace("Hello　World"), "Hello World");
+        assert_eq!(normalize_whitespace("A\t\t\tB"), "A B");
+        assert_eq!(normalize_whitespace("  Multiple   Spaces  "), "Multiple Spaces");
+    }
+
+    #[test]
+    fn test_ideographic_space() {
+        assert_eq!(
+            normalize_ideographic_space("氏名　ＡＢＣ　　１２３"),
+            "氏名 ＡＢＣ  １２３"
+        );
+        assert_eq!(normalize_ideographic_space("A\tB"), "A\tB");
+        assert_eq!(to_ideographic_space("氏名 ABC  123"), "氏名　ABC　　123");
+        assert_eq!(to_ideographic_space("A\tB"), "A\tB");
+        let input = "全角　と 半角";
+        assert_eq!(
+            normalize_ideographic_space(&to_ideographic_space(input)),
+            normalize_ideographic_space(input)
+        );
+    }
+
+    #[test]
+    fn test_trim_japanese() {
+        assert_eq!(trim_japanese("　「こんにちは」。 "), "こんにちは");
+        assert_eq!(trim_japanese("（注）本文…"), "注）本文");
+        assert_eq!(trim_japanese("\t項目\n"), "項目");
+        assert_eq!(trim_japanese("中の、。は残す"), "中の、。は残す");
+        assert_eq!(trim_japanese("、。　"), "");
+        assert_eq!(trim_japanese(""), "");
+    }
+
+    #[test]
+    fn test_trim_start_end_japanese() {
+        assert_eq!(trim_start_japanese("　・項目。"), "項目。");
+        assert_eq!(trim_end_japanese("　・項目。"), "　・項目");
+        // 半角の約物は対象外
+        assert_eq!(trim_japanese("!abc."), "!abc.");
+    }
+
+    #[test]
+    fn test_trim_japanese_matches() {
+        assert_eq!(trim_japanese_matches("！！！重要！！！", &['！']), "重要");
+        assert_eq!(trim_japanese_matches("★☆見出し☆★", &['★', '☆']), "見出し");
+        assert_eq!(trim_japanese_matches("　本文　", &[]), "　本文　");
+    }
+
+    #[test]
+    fn test_normalize_lines() {
+        assert_eq!(
+            normalize_lines("カタカナ　\r\nテスト  \n", to_hiragana),
+            "かたかな\r\nてすと\n"
+        );
+        // 最終行に改行が無い場合
+        assert_eq!(normalize_lines("ア \nイ\t", to_hiragana), "あ\nい");
+        // 空行と先頭の空白は保持する
+        assert_eq!(normalize_lines("\n  Ａ　\n", to_half_width), "\n  A\n");
+        assert_eq!(normalize_lines("", to_half_width), "");
+    }
+
+    #[test]
+    fn test_half_width_katakana_to_full_width() {
+        assert_eq!(half_width_katakana_to_full_width("ｶﾀｶﾅ"), "カタカナ");
+        assert_eq!(half_width_katakana_to_full_width("ｶﾞｷﾞｸﾞｹﾞｺﾞ"), "ガギグゲゴ");
+        assert_eq!(half_width_katakana_to_full_width("ﾊﾟﾋﾟﾌﾟﾍﾟﾎﾟ"), "パピプペポ");
+        assert_eq!(half_width_katakana_to_full_width("ｺﾝﾆﾁﾊ"), "コンニチハ");
+    }
+
+    #[test]
+    fn test_half_width_katakana_combining_marks() {
+        assert_eq!(half_width_katakana_to_full_width("ｶ\u{3099}"), "ガ");
+        assert_eq!(half_width_katakana_to_full_width("ﾊ\u{309A}"), "パ");
+        assert_eq!(half_width_katakana_to_full_width("ｳ\u{3099}"), "ヴ");
+        // 半角濁点と結合用濁点の混在
+        assert_eq!(
+            half_width_katakana_to_full_width("ｶﾞｷ\u{3099}ﾊﾟﾋ\u{309A}"),
+            "ガギパピ"
+        );
+        // 結合できない組み合わせは結合文字をそのまま残す
+        assert_eq!(half_width_katakana_to_full_width("ｱ\u{3099}"), "ア\u{3099}");
+        assert_eq!(half_width_katakana_to_full_width("ｶ\u{309A}"), "カ\u{309A}");
+    }
+
+    #[test]
+    fn test_half_width_katakana_to_full_width_lenient() {
+        assert_eq!(half_width_katakana_to_full_width_lenient("ｶ ﾞ"), "ガ");
+        assert_eq!(half_width_katakana_to_full_width_lenient("ﾊ　ﾟ"), "パ");
+        assert_eq!(half_width_katakana_to_full_width_lenient("ﾃ  ﾞｰﾀ"), "データ");
+        // 通常モードでは空白で分断される
+        assert_eq!(half_width_katakana_to_full_width("ｶ ﾞ"), "カ ゛");
+        assert_eq!(half_width_katakana_to_full_width("ﾃ  ﾞｰﾀ"), "テ  ゛ータ");
+        // 結合対象でない文字間の空白は保持する
+        assert_eq!(half_width_katakana_to_full_width_lenient("ｱ ｲ ｳ"), "ア イ ウ");
+        assert_eq!(half_width_katakana_to_full_width_lenient("ｱ ﾞ"), "ア ゛");
+        assert_eq!(half_width_katakana_to_full_width_lenient("ｶ "), "カ ");
+        // タブや改行をはさんだ場合は結合しない
+        assert_eq!(half_width_katakana_to_full_width_lenient("ｶ\tﾞ"), "カ\t゛");
+        assert_eq!(half_width_katakana_to_full_width_lenient("ｶ\nﾞ"), "カ\n゛");
+        // 空白をはさまない場合は通常モードと同じ
+        assert_eq!(
+            half_width_katakana_to_full_width_lenient("ｶﾞｷﾞﾊﾟ"),
+            half_width_katakana_to_full_width("ｶﾞｷﾞﾊﾟ")
+        );
+    }
+
+    #[test]
+    fn test_normalize_voiced_marks() {
+        // 4つの表記はすべて合成済みの文字になる
+        assert_eq!(normalize_voiced_marks("ｶﾞ"), "ガ");
+        assert_eq!(normalize_voiced_marks("カ゛"), "ガ");
+        assert_eq!(normalize_voiced_marks("カ\u{3099}"), "ガ");
+        assert_eq!(normalize_voiced_marks("ガ"), "ガ");
+        // 半濁点
+        assert_eq!(normalize_voiced_marks("ﾊﾟ"), "パ");
+        assert_eq!(normalize_voiced_marks("ハ゜"), "パ");
+        assert_eq!(normalize_voiced_marks("ハ\u{309A}"), "パ");
+        // 全角カナ + 半角濁点、ひらがな、ヴ・ヷ
+        assert_eq!(normalize_voiced_marks("カﾞ"), "ガ");
+        assert_eq!(normalize_voiced_marks("か゛は\u{309A}"), "がぱ");
+        assert_eq!(normalize_voiced_marks("ウ゛ワ\u{3099}う゛"), "ヴヷゔ");
+        // 表記が混在した文
+        assert_eq!(
+            normalize_voiced_marks("ﾃﾞｰﾀヘ゛ース\u{3099}ト\u{3099}"),
+            "データベーズド"
+        );
+    }
+
+    #[test]
+    fn test_normalize_voiced_marks_uncombinable() {
+        // 付けられない組み合わせは濁点・半濁点を残す
+        assert_eq!(normalize_voiced_marks("ア゛"), "ア゛");
+        assert_eq!(normalize_voiced_marks("ア\u{3099}"), "ア\u{3099}");
+        assert_eq!(normalize_voiced_marks("カ゜"), "カ゜");
+        assert_eq!(normalize_voiced_marks("ｱﾞ"), "ア゛");
+        // 合成済みの文字にさらに濁点が続く場合や、先頭の濁点
+        assert_eq!(normalize_voiced_marks("ガ゛"), "ガ゛");
+        assert_eq!(normalize_voiced_marks("゛カ"), "゛カ");
+        assert_eq!(normalize_voiced_marks(""), "");
+    }
+
+    #[test]
+    fn test_decompose_kana() {
+        assert_eq!(decompose_kana("が"), "か\u{3099}");
+        assert_eq!(decompose_kana("パ"), "ハ\u{309A}");
+        assert_eq!(decompose_kana("ゔ"), "う\u{3099}");
+        assert_eq!(decompose_kana("ヴ"), "ウ\u{3099}");
+        assert_eq!(decompose_kana("ヷヺ"), "ワ\u{3099}ヲ\u{3099}");
+        // 分解対象でない文字はそのまま
+        assert_eq!(decompose_kana("かアｶﾞ漢A゛"), "かアｶﾞ漢A゛");
+        assert_eq!(decompose_kana(""), "");
+
+        // normalize_voiced_marks と往復できる
+        for &(base, voiced, semi_voiced) in tables::VOICING_HIRAGANA
+            .iter()
+            .chain(tables::VOICING_KATAKANA)
+        {
+            let decomposed = decompose_kana(&voiced.to_string());
+            assert_eq!(decomposed, format!("{}\u{3099}", base));
+            assert_eq!(normalize_voiced_marks(&decomposed), voiced.to_string());
+            if let Some(semi_voiced) = semi_voiced {
+                let decomposed = decompose_kana(&semi_voiced.to_string());
+                assert_eq!(decomposed, format!("{}\u{309A}", base));
+                assert_eq!(normalize_voiced_marks(&decomposed), semi_voiced.to_string());
+            }
+        }
+        let text = "がっこうでパンをたべ、ヴァイオリンをひいた";
+        assert_eq!(normalize_voiced_marks(&decompose_kana(text)), text);
+    }
+
+    #[test]
+    fn test_full_width_katakana_to_half_width() {
+        assert_eq!(full_width_katakana_to_half_width("カタカナ"), "ｶﾀｶﾅ");
+        assert_eq!(full_width_katakana_to_half_width("ガギグゲゴ"), "ｶﾞｷﾞｸﾞｹﾞｺﾞ");
+        assert_eq!(full_width_katakana_to_half_width("パピプペポ"), "ﾊﾟﾋﾟﾌﾟﾍﾟﾎﾟ");
+        assert_eq!(full_width_katakana_to_half_width("ヴヷヺ"), "ｳﾞﾜﾞｦﾞ");
+        assert_eq!(full_width_katakana_to_half_width("ァッャー"), "ｧｯｬｰ");
+        assert_eq!(full_width_katakana_to_half_width("。「」、・"), "｡｢｣､･");
+        assert_eq!(full_width_katakana_to_half_width("ア゛カ\u{3099}"), "ｱﾞｶﾞ");
+        // 対応する半角が無い文字とカタカナ以外はそのまま
+        assert_eq!(full_width_katakana_to_half_width("ヰヱヮヵヶヸヹヽヾㇰ"), "ヰヱヮヵヶヸヹヽヾㇰ");
+        assert_eq!(full_width_katakana_to_half_width("ひらがな漢字ABC"), "ひらがな漢字ABC");
+        assert_eq!(full_width_katakana_to_half_width(""), "");
+
+        // 半角カタカナ全体で往復できる
+        let half: String = ('\u{FF61}'..='\u{FF9D}').collect();
+        assert_eq!(full_width_katakana_to_half_width(&half_width_katakana_to_full_width(&half)), half);
+        for &full in HALF_WIDTH_KATAKANA_VOICED_TABLE
+            .iter()
+            .chain(&HALF_WIDTH_KATAKANA_SEMI_VOICED_TABLE)
+            .flatten()
+        {
+            let half = full_width_katakana_to_half_width(&full.to_string());
+            assert_eq!(half_width_katakana_to_full_width(&half), full.to_string());
+        }
+    }
+
+    #[test]
+    fn test_to_half_width_katakana() {
+        assert_eq!(to_half_width_katakana("がっこう"), "ｶﾞｯｺｳ");
+        assert_eq!(to_half_width_katakana("ぱんとコーヒー"), "ﾊﾟﾝﾄｺｰﾋｰ");
+        assert_eq!(to_half_width_katakana("ゔぁいおりん"), "ｳﾞｧｲｵﾘﾝ");
+        assert_eq!(to_half_width_katakana("ゐゝ漢字"), "ヰゝ漢字");
+        assert_eq!(to_half_width_katakana("ABC"), "ABC");
+    }
+
+    #[test]
+    fn test_half_width_katakana_to_full_width_nfd() {
+        assert_eq!(
+            half_width_katakana_to_full_width_nfd("ｶﾞｷﾞｸﾞ"),
+            "カ\u{3099}キ\u{3099}ク\u{3099}"
+        );
+        assert_eq!(
+            half_width_katakana_to_full_width_nfd("ﾊﾟｳﾞ"),
+            "ハ\u{309A}ウ\u{3099}"
+        );
+        // 結合用濁点の入力も分解したまま出力する
+        assert_eq!(half_width_katakana_to_full_width_nfd("ｶ\u{3099}"), "カ\u{3099}");
+        // 濁点の無い文字と結合できない濁点は合成版と同じ
+        assert_eq!(half_width_katakana_to_full_width_nfd("ｱｲｳ"), "アイウ");
+        assert_eq!(half_width_katakana_to_full_width_nfd("ｱﾞ"), "ア゛");
+        // 合成版とは出力が一致しない
+        assert_ne!(
+            half_width_katakana_to_full_width_nfd("ｶﾞ"),
+            half_width_katakana_to_full_width("ｶﾞ")
+        );
+        assert_eq!(half_width_katakana_to_full_width("ｶﾞ"), "ガ");
+    }
+
+    #[test]
+    fn test_half_width_katakana_table() {
+        assert_eq!(
+            half_width_katakana_to_full_width("｡｢｣､･ｦｧｨｩｪｫｬｭｮｯｰ"),
+            "。「」、・ヲァィゥェォャュョッー"
+        );
+        assert_eq!(half_width_katakana_to_full_width("ﾏﾐﾑﾒﾓﾔﾕﾖﾗﾘﾙﾚﾛﾜﾝ"), "マミムメモヤユヨラリルレロワン");
+        assert_eq!(half_width_katakana_to_full_width("ｳﾞｧｲｵﾘﾝ"), "ヴァイオリン");
+        // 濁点を付けられない文字の後の濁点・半濁点は全角の濁点・半濁点になる
+        assert_eq!(half_width_katakana_to_full_width("ｱﾞ"), "ア゛");
+        assert_eq!(half_width_katakana_to_full_width("ﾝﾞ"), "ン゛");
+        assert_eq!(half_width_katakana_to_full_width("ｶﾟ"), "カ゜");
+        assert_eq!(half_width_katakana_to_full_width("ﾞﾟ"), "゛゜");
+        assert_eq!(half_width_katakana_to_full_width("ｶﾞﾞ"), "ガ゛");
+        assert_eq!(half_width_katakana_to_full_width("Aﾞ"), "A゛");
+        // 半角カタカナ以外はそのまま
+        assert_eq!(half_width_katakana_to_full_width("ABC漢字"), "ABC漢字");
+    }
+
+    #[test]
+    fn test_normalize_prolonged_sound() {
+        assert_eq!(normalize_prolonged_sound("コーヒー"), "コーヒー");
+        assert_eq!(normalize_prolonged_sound("コ〜ヒ〜"), "コーヒー");
+        assert_eq!(normalize_prolonged_sound("ラーメン"), "ラーメン");
+    }
+
+    #[test]
+    fn test_collapse_prolonged_sound() {
+        assert_eq!(collapse_prolonged_sound("ヤッホーーーー"), "ヤッホー");
+        assert_eq!(collapse_prolonged_sound("コーヒー"), "コーヒー");
+        // 全角・半角・波ダッシュの混在も一続きとみなし、先頭の文字を残す
+        assert_eq!(collapse_prolonged_sound("ホーｰ〜～"), "ホー");
+        assert_eq!(collapse_prolonged_sound("ﾎｰｰｰ"), "ﾎｰ");
+        assert_eq!(collapse_prolonged_sound("すご〜ーい"), "すご〜い");
+        // 促音は既定では畳まない
+        assert_eq!(collapse_prolonged_sound("やったっっっ"), "やったっっっ");
+        assert_eq!(collapse_prolonged_sound(""), "");
+    }
+
+    #[test]
+    fn test_collapse_prolonged_sound_with() {
+        let opts = CollapseOptions {
+            collapse_repeats: true,
+            max_run: 1,
+        };
+        assert_eq!(collapse_prolonged_sound_with("やったっっっ", opts), "やったっ");
+        assert_eq!(collapse_prolonged_sound_with("アッッｯっ", opts), "アッ");
+        // 長音と促音は別々の連続
+        assert_eq!(collapse_prolonged_sound_with("えーっっーー", opts), "えーっー");
+
+        let opts = CollapseOptions {
+            collapse_repeats: false,
+            max_run: 3,
+        };
+        assert_eq!(collapse_prolonged_sound_with("ねーーーーー", opts), "ねーーー");
+        assert_eq!(collapse_prolonged_sound_with("ねーー", opts), "ねーー");
+
+        let opts = CollapseOptions {
+            collapse_repeats: false,
+            max_run: 0,
+        };
+        assert_eq!(collapse_prolonged_sound_with("ねーー", opts), "ねー");
+    }
+
+    #[test]
+    fn test_normalize_hyphens() {
+        assert_eq!(normalize_hyphens("１ー２ー３"), "１-２-３");
+        assert_eq!(normalize_hyphens("東京都渋谷区1－2－3"), "東京都渋谷区1-2-3");
+        assert_eq!(normalize_hyphens("‐‑‒–—―"), "------");
+        assert_eq!(normalize_hyphens("1-2-3"), "1-2-3");
+    }
+
+    #[test]
+    fn test_normalize_dashes() {
+        assert_eq!(normalize_dashes("‐‑‒–—―−－ー-", '-'), "----------");
+        assert_eq!(normalize_dashes("コーヒー", '-'), "コ-ヒ-");
+        assert_eq!(normalize_dashes("1-2", '－'), "1－2");
+        assert_eq!(normalize_dashes("ハイフンなし", '-'), "ハイフンなし");
+    }
+
+    #[test]
+    fn test_normalize_dashes_with() {
+        let opts = DashOptions { preserve_prolonged_sound: true };
+        assert_eq!(normalize_dashes_with("コーヒー", '-', opts), "コーヒー");
+        assert_eq!(normalize_dashes_with("ヤッホーーー", '-', opts), "ヤッホーーー");
+        assert_eq!(normalize_dashes_with("１ー２", '-', opts), "１-２");
+        assert_eq!(normalize_dashes_with("ーコ―ヒ", '-', opts), "-コ-ヒ");
+        assert_eq!(
+            normalize_dashes_with("コーヒー", '-', DashOptions::default()),
+            "コ-ヒ-"
+        );
+    }
+
+    #[test]
+    fn test_normalize_punctuation_style() {
+        use PunctuationStyle::{Comma, JaMaru};
+        assert_eq!(normalize_punctuation_style("はい，そうです．", JaMaru), "はい、そうです。");
+        assert_eq!(normalize_punctuation_style("はい、そうです。", Comma), "はい，そうです．");
+        assert_eq!(normalize_punctuation_style("混在、例，です。．", JaMaru), "混在、例、です。。");
+        assert_eq!(normalize_punctuation_style("１，２３４．５円", JaMaru), "１，２３４．５円");
+        assert_eq!(normalize_punctuation_style("合計１．", JaMaru), "合計１。");
+        // 半角は既定では対象外
+        assert_eq!(normalize_punctuation_style("a, b.", JaMaru), "a, b.");
+        assert_eq!(normalize_punctuation_style("", Comma), "");
+    }
+
+    #[test]
+    fn test_normalize_punctuation_style_with() {
+        use PunctuationStyle::{Comma, JaMaru};
+        let opts = PunctuationOptions { include_half_width: true };
+        assert_eq!(
+            normalize_punctuation_style_with("合計は1,234.5円です.", JaMaru, opts),
+            "合計は1,234.5円です。"
+        );
+        assert_eq!(
+            normalize_punctuation_style_with("はい,そうです.", Comma, opts),
+            "はい，そうです．"
+        );
+        assert_eq!(normalize_punctuation_style_with("3.14と2,5", Comma, opts), "3.14と2,5");
+        assert_eq!(normalize_punctuation_style_with("v1.", JaMaru, opts), "v1。");
+    }
+
+    #[test]
+    fn test_fix_ocr_prolonged_sound() {
+        assert_eq!(fix_ocr_prolonged_sound("コ-ヒ-"), "コーヒー");
+        assert_eq!(fix_ocr_prolonged_sound("ス—パ‐"), "スーパー");
+        assert_eq!(fix_ocr_prolonged_sound("ボ－ル"), "ボール");
+        assert_eq!(fix_ocr_prolonged_sound("ヤッホ--"), "ヤッホーー");
+        assert_eq!(fix_ocr_prolonged_sound("ｺ-ﾋ-"), "ｺｰﾋｰ");
+        // 数字・英数字に接するハイフンは残す
+        assert_eq!(fix_ocr_prolonged_sound("03-1234-5678"), "03-1234-5678");
+        assert_eq!(fix_ocr_prolonged_sound("ページ-3"), "ページ-3");
+        assert_eq!(fix_ocr_prolonged_sound("テスト-ABC"), "テスト-ABC");
+        assert_eq!(fix_ocr_prolonged_sound("テスト－１"), "テスト－１");
+        // 漢字・ひらがなが続くダッシュは残す
+        assert_eq!(fix_ocr_prolonged_sound("ロンドン—東京"), "ロンドン—東京");
+        assert_eq!(fix_ocr_prolonged_sound("テスト-です"), "テスト-です");
+        // 約物・空白が続く場合は補正する
+        assert_eq!(fix_ocr_prolonged_sound("コ-ヒ-。"), "コーヒー。");
+        assert_eq!(fix_ocr_prolonged_sound("コ-ヒ-！"), "コーヒー！");
+        assert_eq!(fix_ocr_prolonged_sound("ｺ-ﾋ-｡"), "ｺｰﾋｰ｡");
+        assert_eq!(fix_ocr_prolonged_sound("コ-ヒ- と紅茶"), "コーヒー と紅茶");
+        // カタカナ以外の後は残す
+        assert_eq!(fix_ocr_prolonged_sound("あ-い"), "あ-い");
+        assert_eq!(fix_ocr_prolonged_sound("東京-大阪"), "東京-大阪");
+        assert_eq!(fix_ocr_prolonged_sound("-コ"), "-コ");
+        assert_eq!(fix_ocr_prolonged_sound("ア・-"), "ア・-");
+        assert_eq!(fix_ocr_prolonged_sound(""), "");
+    }
+
+    #[test]
+    fn test_converter() {
+        let converter = Converter::from_pairs(&[('國', '国'), ('學', '学'), ('廣', '広')]);
+        assert_eq!(converter.convert("國學院・廣島"), "国学院・広島");
+        assert_eq!(converter.convert(""), "");
+
+        // 後の組が優先される
+        let converter = Converter::from_pairs(&[('a', 'x'), ('b', 'y'), ('a', 'z')]);
+        assert_eq!(converter.convert("abc"), "zyc");
+
+        assert_eq!(Converter::default().convert("そのまま"), "そのまま");
+    }
+
+    #[test]
+    fn test_to_shinjitai() {
+        assert_eq!(to_shinjitai("國學院大學"), "国学院大学");
+        assert_eq!(to_shinjitai("舊字體の文書を讀む"), "旧字体の文書を読む");
+        assert_eq!(to_shinjitai("辨・瓣・辯"), "弁・弁・弁");
+        // 新字体・表に無い字・かなはそのまま
+        assert_eq!(to_shinjitai("国学院 かな ABC"), "国学院 かな ABC");
+        assert_eq!(to_shinjitai(""), "");
+    }
+
+    #[test]
+    fn test_normalize_compat_kanji() {
+        assert_eq!(normalize_compat_kanji("\u{FA19}社と\u{FA1A}"), "\u{795E}社と\u{7965}");
+        assert_eq!(normalize_compat_kanji("\u{F900}"), "\u{8C48}");
+        assert_eq!(normalize_compat_kanji("\u{FAD9}"), "\u{9F8E}");
+        assert_eq!(normalize_compat_kanji("\u{2F800}"), "\u{4E3D}");
+        assert_eq!(normalize_compat_kanji("\u{2FA1D}"), "\u{2A600}");
+        // 正規分解を持たない字・統合漢字・かなはそのまま
+        assert_eq!(normalize_compat_kanji("\u{FA11}\u{FA0E}"), "\u{FA11}\u{FA0E}");
+        assert_eq!(normalize_compat_kanji("神社 かな ABC"), "神社 かな ABC");
+        assert_eq!(normalize_compat_kanji(""), "");
+    }
+
+    #[cfg(feature = "unicode-norm")]
+    #[test]
+    fn test_unicode_normalization_wrappers() {
+        assert_eq!(nfkc("ＡＢＣ　１２３"), "ABC 123");
+        assert_eq!(nfkc("ｶﾞｷﾞﾊﾟ"), "ガギパ");
+        assert_eq!(nfkc("①²™㈱㌔"), "12TM(株)キロ");
+        assert_eq!(nfkc("か\u{3099}"), "が");
+        assert_eq!(nfkd("ガ"), "カ\u{3099}");
+        assert_eq!(nfkd("ｶﾞ"), "カ\u{3099}");
+        assert_eq!(nfc("ハ\u{309A}ン"), "パン");
+        assert_eq!(nfc("ｶﾞＡ①"), "ｶﾞＡ①");
+        assert_eq!(nfd("パン"), "ハ\u{309A}ン");
+        assert_eq!(nfkc(""), "");
+
+        // クレート独自の変換との対応
+        let kana: String = tables::VOICING_HIRAGANA
+            .iter()
+            .chain(tables::VOICING_KATAKANA)
+            .flat_map(|&(_, voiced, semi_voiced)| core::iter::once(voiced).chain(semi_voiced))
+            .collect();
+        assert_eq!(nfd(&kana), decompose_kana(&kana));
+        assert_eq!(nfc(&decompose_kana(&kana)), kana);
+        for &(compat, unified) in tables::COMPAT_KANJI_PAIRS {
+            assert_eq!(nfc(&compat.to_string()), unified.to_string());
+        }
+        let ascii: String = ('\u{FF01}'..='\u{FF5E}').chain(['　']).collect();
+        assert_eq!(nfkc(&ascii), to_half_width(&ascii));
+    }
+
+    #[test]
+    fn test_compat_kanji_pairs() {
+        let pairs = tables::COMPAT_KANJI_PAIRS;
+        assert_eq!(pairs.len(), 1002);
+        assert!(pairs.windows(2).all(|w| w[0].0 < w[1].0));
+        for &(compat, unified) in pairs {
+            assert!(
+                ('\u{F900}'..='\u{FAFF}').contains(&compat)
+                    || ('\u{2F800}'..='\u{2FA1F}').contains(&compat)
+            );
+            assert!(is_kanji(unified));
+            // 変換先は互換漢字ではない
+            assert!(pairs.binary_search_by_key(&unified, |&(c, _)| c).is_err());
+        }
+    }
+
+    #[test]
+    fn test_voicing_tables() {
+        let voiced: Vec<char> = tables::VOICING_HIRAGANA.iter().map(|e| e.1).collect();
+        assert_eq!(voiced, tables::VOICED_HIRAGANA);
+        let semi: Vec<char> = tables::VOICING_HIRAGANA.iter().filter_map(|e| e.2).collect();
+        assert_eq!(semi, tables::SEMI_VOICED_HIRAGANA);
+        let voiced: Vec<char> = tables::VOICING_KATAKANA.iter().map(|e| e.1).collect();
+        assert_eq!(voiced, tables::VOICED_KATAKANA);
+        let semi: Vec<char> = tables::VOICING_KATAKANA.iter().filter_map(|e| e.2).collect();
+        assert_eq!(semi, tables::SEMI_VOICED_KATAKANA);
+
+        // voicing_entry は表の行と一致し、表に無い文字には None を返す
+        let rows: Vec<_> = tables::VOICING_HIRAGANA
+            .iter()
+            .chain(tables::VOICING_KATAKANA)
+            .copied()
+            .collect();
+        for c in '\u{3040}'..='\u{30FF}' {
+            let row = rows
+                .iter()
+                .find(|&&(base, voiced, semi_voiced)| {
+                    base == c || voiced == c || semi_voiced == Some(c)
+                })
+                .copied();
+            assert_eq!(voicing_entry(c), row, "{c}");
+        }
+
+        // add_dakuten と remove_voicing は「う」とワ行を除いて表に従う
+        for &(base, voiced, semi_voiced) in &rows {
+            if "うウワヰヱヲ".contains(base) {
+                assert_eq!(add_dakuten(base), base);
+                continue;
+            }
+            assert_eq!(add_dakuten(base), voiced);
+            assert_eq!(remove_voicing(voiced), base);
+            if let Some(semi_voiced) = semi_voiced {
+                assert_eq!(remove_voicing(semi_voiced), base);
+            }
+        }
+        assert_eq!(remove_voicing('ゔ'), 'う');
+        assert_eq!(remove_voicing('ヴ'), 'ウ');
+        assert_eq!(remove_voicing('ヷ'), 'ヷ');
+        // ひらがなとカタカナの表は同じ順で対応する
+        for (h, k) in tables::VOICING_HIRAGANA.iter().zip(tables::VOICING_KATAKANA) {
+            assert_eq!(katakana_char(h.0), k.0);
+            assert_eq!(katakana_char(h.1), k.1);
+        }
+        assert_eq!(add_dakuten('あ'), 'あ');
+        assert_eq!(remove_voicing('か'), 'か');
+    }
+
+    #[test]
+    fn test_shinjitai_pairs_sorted() {
+        // 二分探索のため、旧字体の順に重複なく並んでいること
+        for pair in tables::SHINJITAI_PAIRS.windows(2) {
+            assert!(pair[0].0 < pair[1].0);
+        }
+        for &(old, new) in tables::SHINJITAI_PAIRS {
+            assert_ne!(old, new);
+            assert_eq!(to_shinjitai(&old.to_string()), new.to_string());
+        }
+    }
+
+    #[test]
+    fn test_unify_kana() {
+        for input in ["ﾊﾞｲｵﾘﾝ", "バイオリン", "ばいおりん", "ﾊﾞいオりﾝ"] {
+            assert_eq!(unify_kana(input, KanaForm::Katakana), "バイオリン");
+            assert_eq!(unify_kana(input, KanaForm::Hiragana), "ばいおりん");
+            assert_eq!(unify_katakana(input), "バイオリン");
+        }
+        // かな以外はそのまま
+        assert_eq!(unify_kana("漢字ABC｡", KanaForm::Hiragana), "漢字ABC。");
+    }
+
+    #[test]
+    fn test_search_key() {
+        let expected = search_key("ばいおりん");
+        assert_eq!(expected, "はいおりん");
+        assert_eq!(search_key("ﾊﾞｲｵﾘﾝ"), expected);
+        assert_eq!(search_key("バイオリン"), expected);
+        assert_eq!(search_key("ＡＢＣ"), "abc");
+        assert_eq!(search_key("Ｃｏｆｆｅｅ　コーヒー"), "coffee こうひい");
+        assert_eq!(search_key("パン"), search_key("ハン"));
+    }
+
+    #[test]
+    fn test_search_key_with() {
+        let all_off = SearchKeyOptions {
+            half_width_katakana: false,
+            hiragana: false,
+            half_width_ascii: false,
+            lowercase: false,
+            prolonged: ProlongedPolicy::Keep,
+            remove_voicing: false,
+        };
+        assert_eq!(search_key_with("ｶﾞＡーパ", all_off), "ｶﾞＡーパ");
+
+        let opts = SearchKeyOptions { half_width_katakana: true, ..all_off };
+        assert_eq!(search_key_with("ｶﾞ", opts), "ガ");
+
+        // カタカナのまま長音を展開する
+        let opts = SearchKeyOptions { prolonged: ProlongedPolicy::ToVowel, ..all_off };
+        assert_eq!(search_key_with("コーヒー", opts), "コウヒイ");
+        let opts = SearchKeyOptions { prolonged: ProlongedPolicy::Remove, ..all_off };
+        assert_eq!(search_key_with("コーヒー", opts), "コヒ");
+
+        let opts = SearchKeyOptions { remove_voicing: false, ..SearchKeyOptions::default() };
+        assert_eq!(search_key_with("ﾊﾞｲｵﾘﾝ", opts), "ばいおりん");
+    }
+
+    #[test]
+    #[cfg(feature = "numerals")]
+    fn test_kanji_to_arabic() {
+        assert_eq!(kanji_to_arabic("千二百三十四"), Some(1234));
+        assert_eq!(kanji_to_arabic("十"), Some(10));
+        assert_eq!(kanji_to_arabic("二十一"), Some(21));
+        assert_eq!(kanji_to_arabic("百五"), Some(105));
+        assert_eq!(kanji_to_arabic("二〇二四"), Some(2024));
+        assert_eq!(kanji_to_arabic("一億二千三百四十五万六千七百八十九"), Some(123_456_789));
+        assert_eq!(kanji_to_arabic("万"), Some(10_000));
+        assert_eq!(kanji_to_arabic("1万2000"), Some(12_000));
+        assert_eq!(kanji_to_arabic("１，０００"), Some(1000));
+        assert_eq!(kanji_to_arabic("零"), Some(0));
+        assert_eq!(kanji_to_arabic(""), None);
+        assert_eq!(kanji_to_arabic("百円"), None);
+        assert_eq!(kanji_to_arabic("99999999999999999999"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "numerals")]
+    fn test_extract_numbers() {
+        let numbers = extract_numbers("１，２３４円と千二百三十四円と1234円");
+        assert_eq!(numbers, vec![(0..15, 1234), (21..39, 1234), (45..49, 1234)]);
+
+        // 単位・通貨記号は範囲に含めない
+        let input = "￥3,000（税込）";
+        let numbers = extract_numbers(input);
+        assert_eq!(numbers.len(), 1);
+        assert_eq!(&input[numbers[0].0.clone()], "3,000");
+        assert_eq!(numbers[0].1, 3000);
+
+        // 桁区切りでないカンマ
+        let values = |s: &str| extract_numbers(s).into_iter().map(|(_, v)| v).collect::<Vec<_>>();
+        assert_eq!(values("1,2,3"), vec![1, 2, 3]);
+        assert_eq!(values("1,2345"), vec![1, 2345]);
+        assert_eq!(values("12,345,678"), vec![12_345_678]);
+        // 算用数字と位取りの混在
+        assert_eq!(values("約1万2000人"), vec![12_000]);
+        assert_eq!(values("3億円"), vec![300_000_000]);
+        // 位取りだけでは数値を始めない
+        assert_eq!(values("万が一"), vec![1]);
+        assert!(values("数値なし").is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "numerals")]
+    fn test_extract_numbers_sign_and_decimal() {
+        let input = "残高-500円、差額－１２、気温−3.5度";
+        let numbers = extract_numbers(input);
+        let texts: Vec<&str> = numbers.iter().map(|(r, _)| &input[r.clone()]).collect();
+        assert_eq!(texts, vec!["-500", "－１２", "−3.5"]);
+        let values: Vec<i64> = numbers.iter().map(|(_, v)| *v).collect();
+        assert_eq!(values, vec![-500, -12, -3]);
+
+        // 数字・英字の後のハイフンは負号ではない
+        let values = |s: &str| extract_numbers(s).into_iter().map(|(_, v)| v).collect::<Vec<_>>();
+        assert_eq!(values("2024-01-02"), vec![2024, 1, 2]);
+        assert_eq!(values("A-1"), vec![1]);
+        // 小数点の後に数字が無ければ文末の句点として扱う
+        assert_eq!(values("1.5と2."), vec![1, 2]);
+        assert_eq!(values("１．２５"), vec![1]);
+    }
+
+    #[test]
+    fn test_split_sentences() {
+        assert_eq!(
+            split_sentences("今日は晴れ。明日は雨！本当？"),
+            vec!["今日は晴れ。", "明日は雨！", "本当？"]
+        );
+        // 半角の文末記号
+        assert_eq!(split_sentences("ﾃｽﾄ｡OK! Really?"), vec!["ﾃｽﾄ｡", "OK!", "Really?"]);
+        // 文末記号の連続と閉じ括弧は同じ文に含める
+        assert_eq!(
+            split_sentences("えっ！？「行くよ。」わかった。"),
+            vec!["えっ！？", "「行くよ。」", "わかった。"]
+        );
+        // 三点リーダ・ピリオドでは分割しない
+        assert_eq!(split_sentences("待って……。3.14です。"), vec!["待って……。", "3.14です。"]);
+        // 文末記号の無い末尾、前後の空白と空の文
+        assert_eq!(split_sentences("　一文目。\n 二文目"), vec!["一文目。", "二文目"]);
+        assert_eq!(split_sentences("。。 "), vec!["。。"]);
+        assert!(split_sentences("").is_empty());
+        // 既定では括弧内の句点でも分割する
+        assert_eq!(
+            split_sentences("「はい。そうです。」と答えた。"),
+            vec!["「はい。", "そうです。」", "と答えた。"]
+        );
+    }
+
+    #[test]
+    fn test_split_sentences_with_brackets() {
+        let opts = SentenceOptions { respect_brackets: true };
+        assert_eq!(
+            split_sentences_with("「はい。そうです。」と答えた。次の文。", opts),
+            vec!["「はい。そうです。」と答えた。", "次の文。"]
+        );
+        // 入れ子の括弧
+        assert_eq!(
+            split_sentences_with("「彼は『だめだ！』と言った。」と聞いた。本当だ。", opts),
+            vec!["「彼は『だめだ！』と言った。」と聞いた。", "本当だ。"]
+        );
+        // 括弧を閉じただけでは文末とみなさない
+        assert_eq!(
+            split_sentences_with("（注意。）次。", opts),
+            vec!["（注意。）次。"]
+        );
+        // 閉じられていない括弧は末尾まで続く
+        assert_eq!(split_sentences_with("「未完。続く。", opts), vec!["「未完。続く。"]);
+        // 対応の無い閉じ括弧は無視する
+        assert_eq!(split_sentences_with("」あ。い。", opts), vec!["」あ。", "い。"]);
+    }
+
+    #[test]
+    fn test_split_nakaguro() {
+        assert_eq!(split_nakaguro("アイス・コーヒー"), vec!["アイス", "コーヒー"]);
+        assert_eq!(split_nakaguro("ｱｲｽ･ｺｰﾋｰ"), vec!["ｱｲｽ", "ｺｰﾋｰ"]);
+        assert_eq!(split_nakaguro("・ア・・イ・"), vec!["ア", "イ"]);
+        assert_eq!(split_nakaguro("コーヒー"), vec!["コーヒー"]);
+        assert!(split_nakaguro("・･").is_empty());
+        assert!(split_nakaguro("").is_empty());
+    }
+
+    #[test]
+    fn test_morae() {
+        let split = |input| morae(input).collect::<Vec<_>>();
+        assert_eq!(split("きゃ"), ["きゃ"]);
+        assert_eq!(split("がっこう"), ["が", "っ", "こ", "う"]);
+        assert_eq!(split("しんぶんし"), ["し", "ん", "ぶ", "ん", "し"]);
+        assert_eq!(split("ファッション"), ["ファ", "ッ", "ショ", "ン"]);
+        assert_eq!(split("ヴァイオリン"), ["ヴァ", "イ", "オ", "リ", "ン"]);
+        assert_eq!(split("ｷｬﾞｯﾄ"), ["ｷｬﾞ", "ｯ", "ﾄ"]);
+        assert_eq!(split("か\u{3099}ぱ゜"), ["か\u{3099}", "ぱ゜"]);
+        // かな以外は1文字ずつ
+        assert_eq!(split("東京abc"), ["東", "京", "a", "b", "c"]);
+        // 前のかなの無い小書きは独立
+        assert_eq!(split("ゃあ"), ["ゃ", "あ"]);
+        assert_eq!(split("漢ゃ"), ["漢", "ゃ"]);
+        assert_eq!(split("ーっ"), ["ー", "っ"]);
+        assert!(split("").is_empty());
+
+        // 連結すると元の文字列に戻る
+        let input = "がっこうのﾃｨｰﾁｬｰ、Mr.スミス";
+        assert_eq!(morae(input).collect::<String>(), input);
+    }
+
+    #[test]
+    fn test_count_morae() {
+        assert_eq!(count_morae("がっこう"), 4);
+        assert_eq!(count_morae("しゃしん"), 3);
+        assert_eq!(count_morae("コーヒー"), 4);
+        assert_eq!(count_morae("きょうと"), 3);
+        assert_eq!(count_morae(""), 0);
+    }
+
+    #[test]
+    fn test_find_unbalanced_brackets() {
+        assert!(find_unbalanced_brackets("").is_empty());
+        assert!(find_unbalanced_brackets("括弧なし").is_empty());
+        assert!(find_unbalanced_brackets("「『引用』と（注）」【〔《〈〉》〕】").is_empty());
+        // 種類の違う閉じ括弧
+        assert_eq!(find_unbalanced_brackets("「文章』"), [(0, '「'), (9, '』')]);
+        // 閉じられていない開き括弧・対応の無い閉じ括弧
+        assert_eq!(find_unbalanced_brackets("「あ"), [(0, '「')]);
+        assert_eq!(find_unbalanced_brackets("あ」"), [(3, '」')]);
+        assert_eq!(find_unbalanced_brackets("）（"), [(0, '）'), (3, '（')]);
+        // 入れ子の内側が閉じられないまま外側が閉じられた
+        assert_eq!(find_unbalanced_brackets("「『」"), [(3, '『')]);
+        assert_eq!(find_unbalanced_brackets("（「）」"), [(3, '「'), (9, '」')]);
+        // 半角の括弧は対象外
+        assert!(find_unbalanced_brackets("(「)」").is_empty());
+    }
+
+    #[test]
+    fn test_validate_kana() {
+        let issue = |position, kind| KanaIssue { position, kind };
+
+        assert!(validate_kana("").is_empty());
+        assert!(validate_kana("がっこうでしゃしんをとった").is_empty());
+        assert!(validate_kana("チョコレートとコーヒー、ティーカップ").is_empty());
+        assert!(validate_kana("デュエット・フュージョン・ヴュー").is_empty());
+        assert!(validate_kana("ｷﾞｬﾗﾘｰ").is_empty());
+        assert!(validate_kana("漢字とABC").is_empty());
+
+        // 語頭の促音
+        assert_eq!(validate_kana("っかい"), [issue(0, IssueKind::LeadingSokuon)]);
+        assert_eq!(validate_kana("Aッ"), [issue(1, IssueKind::LeadingSokuon)]);
+        assert_eq!(validate_kana("「ｯ"), [issue(3, IssueKind::LeadingSokuon)]);
+        // 孤立した拗音
+        assert_eq!(validate_kana("かゃ"), [issue(3, IssueKind::IsolatedYoon)]);
+        assert_eq!(validate_kana("ゅ"), [issue(0, IssueKind::IsolatedYoon)]);
+        assert_eq!(validate_kana("ぃょ"), [issue(3, IssueKind::IsolatedYoon)]);
+        assert_eq!(validate_kana("いゃ"), [issue(3, IssueKind::IsolatedYoon)]);
+        assert_eq!(validate_kana("ヰュ"), [issue(3, IssueKind::IsolatedYoon)]);
+        assert_eq!(validate_kana("ｶｮ"), [issue(3, IssueKind::IsolatedYoon)]);
+        // 濁点付きのイ段に続く拗音は正しい
+        assert!(validate_kana("き\u{3099}ゃ").is_empty());
+        // 語頭の長音記号と連続する長音記号
+        assert_eq!(validate_kana("ーあ"), [issue(0, IssueKind::LeadingProlonged)]);
+        assert_eq!(
+            validate_kana("あーーー"),
+            [
+                issue(6, IssueKind::RepeatedProlonged),
+                issue(9, IssueKind::RepeatedProlonged),
+            ]
+        );
+        assert_eq!(
+            validate_kana("っゃｰｰ"),
+            [
+                issue(0, IssueKind::LeadingSokuon),
+                issue(3, IssueKind::IsolatedYoon),
+                issue(9, IssueKind::RepeatedProlonged),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_katakana_words() {
+        assert_eq!(
+            extract_katakana_words("ニューヨーク・タイムズでコーヒーを飲む"),
+            vec!["ニューヨーク・タイムズ", "コーヒー"]
+        );
+        // 先頭の長音符・中黒と語末の中黒は含めない
+        assert_eq!(extract_katakana_words("ーアイス・"), vec!["アイス"]);
+        assert_eq!(extract_katakana_words("・ヴァイオリン"), vec!["ヴァイオリン"]);
+        assert_eq!(extract_katakana_words("3ヶ月"), vec!["ヶ"]);
+        assert_eq!(extract_katakana_words("ｺｰﾋｰ"), Vec::<String>::new());
+        assert_eq!(extract_katakana_words("ひらがなと漢字"), Vec::<String>::new());
+        assert_eq!(extract_katakana_words(""), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_extract_katakana_words_with() {
+        let opts = KatakanaWordOptions {
+            min_len: 2,
+            include_half_width: false,
+        };
+        assert_eq!(extract_katakana_words_with("3ヶ月とエ", opts), Vec::<String>::new());
+        assert_eq!(extract_katakana_words_with("3ヶ月のパスタ", opts), vec!["パスタ"]);
+
+        let opts = KatakanaWordOptions {
+            min_len: 1,
+            include_half_width: true,
+        };
+        assert_eq!(
+            extract_katakana_words_with("ｶﾞｰﾃﾞﾝ･ﾊﾟｰﾃｨｰとテスト", opts),
+            vec!["ｶﾞｰﾃﾞﾝ･ﾊﾟｰﾃｨｰ", "テスト"]
+        );
+        // 半角の句読点は語に含めない
+        assert_eq!(extract_katakana_words_with("｢ﾃｽﾄ｣", opts), vec!["ﾃｽﾄ"]);
+    }
+
+    #[test]
+    fn test_extract_hiragana() {
+        assert_eq!(extract_hiragana("漢字かなカナ混じり"), "かなじり");
+        assert_eq!(extract_hiragana("らーめん・いろゝ"), "らめんいろ");
+        assert_eq!(extract_hiragana("ABC"), "");
+
+        let opts = ExtractOptions {
+            include_prolonged_sound: true,
+            include_middle_dot: true,
+            include_iteration_marks: true,
+        };
+        assert_eq!(extract_hiragana_with("らーめん・いろゝ", opts), "らーめん・いろゝ");
+        // カタカナの繰り返し記号はひらがなでは残さない
+        assert_eq!(extract_hiragana_with("あヽ", opts), "あ");
+    }
+
+    #[test]
+    fn test_extract_katakana() {
+        assert_eq!(extract_katakana("カタカナとひらがな"), "カタカナ");
+        assert_eq!(extract_katakana("コーヒー・ｶﾌｪ"), "コヒ");
+
+        let opts = ExtractOptions {
+            include_prolonged_sound: true,
+            include_middle_dot: true,
+            ..ExtractOptions::default()
+        };
+        assert_eq!(extract_katakana_with("コーヒー・ｶﾌｪ", opts), "コーヒー・");
+        let opts = ExtractOptions {
+            include_iteration_marks: true,
+            ..ExtractOptions::default()
+        };
+        assert_eq!(extract_katakana_with("トヽキゝ", opts), "トヽキ");
+    }
+
+    #[test]
+    fn test_extract_kanji() {
+        assert_eq!(extract_kanji("東京都に住む"), "東京都住");
+        assert_eq!(extract_kanji("佐々木𠮷野"), "佐木𠮷野");
+        assert_eq!(extract_kanji(""), "");
+
+        let opts = ExtractOptions {
+            include_iteration_marks: true,
+            include_prolonged_sound: true,
+            ..ExtractOptions::default()
+        };
+        assert_eq!(extract_kanji_with("佐々木ー", opts), "佐々木ー");
+    }
+
+    #[test]
+    fn test_normalize_for_search() {
+        // 全角・半角・ひらがな・カタカナの表記ゆれ
+        let expected = normalize_for_search("こーひー");
+        assert_eq!(normalize_for_search("コーヒー"), expected);
+        assert_eq!(normalize_for_search("ｺｰﾋｰ"), expected);
+        assert_eq!(normalize_for_search("コ〜ヒ〜"), expected);
+        assert_eq!(normalize_for_search("ﾊﾞｲｵﾘﾝ"), "ばいおりん");
+        // 英数字と空白
+        assert_eq!(normalize_for_search("  ＡＢＣ　　テスト\t"), "ABC てすと");
+        // 繰り返し記号
+        assert_eq!(normalize_for_search("いすゞ"), "いすず");
+        assert_eq!(normalize_for_search("トヽロ"), "ととろ");
+    }
+
+    #[test]
+    fn test_apply() {
+        assert_eq!(apply(Transform::HalfWidth, "ＡＢＣ"), "ABC");
+        assert_eq!(apply(Transform::ToKatakana, "ひらがな"), "ヒラガナ");
+        assert_eq!(apply(Transform::StripEmoji, "OK👍"), "OK");
+        assert_eq!(
+            apply_all(
+                &[Transform::HalfWidthKatakanaToFullWidth, Transform::ToHiragana],
+                "ｶﾞｷﾞ"
+            ),
+            "がぎ"
+        );
+        assert_eq!(apply_all(&[], "そのまま"), "そのまま");
+    }
 
-    for (i, &c) in chars.iter().enumerate() {
-        match c {
-            // ひらがな繰り返し記号（無声音）
-            'ゝ' => {
-                if i > 0 {
-                    result.push(chars[i - 1]);
-                } else {
-                    result.push(c);
-                }
+    #[test]
+    fn test_transform_from_str() {
+        for transform in Transform::ALL {
+            assert_eq!(transform.name().parse(), Ok(transform));
+            assert_eq!(transform.to_string(), transform.name());
+        }
+        let err = "halfwidth".parse::<Transform>().unwrap_err();
+        assert_eq!(err.to_string(), "unknown transform name: \"halfwidth\"");
+        assert!("".parse::<Transform>().is_err());
+    }
+
+    #[test]
+    fn test_normalize_with_report() {
+        let (text, report) = normalize_with_report("ＡＢＣ　ｺｰﾋｰ");
+        assert_eq!(text, "ABC こーひー");
+        assert_eq!(
+            report,
+            NormalizationReport {
+                fullwidth_to_halfwidth: 4,
+                halfwidth_katakana_to_fullwidth: 4,
+                katakana_to_hiragana: 2,
+                ..NormalizationReport::default()
             }
-            // ひらがな繰り返し記号（濁音）
-            'ゞ' => {
-                if i > 0 {
-                    let prev = chars[i - 1];
-                    let voiced = add_dakuten(prev);
-                    result.push(voiced);
-                } else {
-                    result.push(c);
-                }
+        );
+
+        // 濁点・半濁点の結合は変換後の1文字として数える
+        let (text, report) = normalize_with_report("ｶﾞｷﾞ");
+        assert_eq!(text, "がぎ");
+        assert_eq!(report.halfwidth_katakana_to_fullwidth, 2);
+        let (_, report) = normalize_with_report("ﾊﾟﾝﾞ");
+        assert_eq!(report.halfwidth_katakana_to_fullwidth, 3);
+
+        // 空白: 前後の除去・畳み込み・半角スペース以外からの置き換え
+        let (text, report) = normalize_with_report(" a  b\tc\u{3000}\u{3000}d ");
+        assert_eq!(text, "a b c d");
+        assert_eq!(report.whitespace, 5);
+
+        let (text, report) = normalize_with_report("すゝむ〜");
+        assert_eq!(text, "すすむー");
+        assert_eq!(report.iteration_marks, 1);
+        assert_eq!(report.prolonged_sound, 1);
+        assert_eq!(report.total(), 2);
+
+        let (text, report) = normalize_with_report("変換なし");
+        assert_eq!(text, "変換なし");
+        assert_eq!(report, NormalizationReport::default());
+    }
+
+    #[test]
+    fn test_normalize_with_report_matches_normalize_for_search() {
+        for input in ["ＡＢＣ　　テスト", "ｶﾞｷﾞ ｸﾞ", "  トヽキ〜 ", "", "　"] {
+            assert_eq!(normalize_with_report(input).0, normalize_for_search(input));
+        }
+        let a = normalize_with_report("ＡＢ").1;
+        let b = normalize_with_report("カナ").1;
+        assert_eq!((a + b).total(), 4);
+    }
+
+    #[test]
+    fn test_reading_sort_key() {
+        assert_eq!(reading_sort_key("ガッコー"), "かつこう");
+        assert_eq!(reading_sort_key("ｷｬﾝﾌﾟ"), "きやんふ");
+        assert_eq!(reading_sort_key("ヴァイオリン"), "うあいおりん");
+        assert_eq!(reading_sort_key("漢字"), "漢字");
+
+        let mut words = vec!["ぱん", "ハナ", "ばら", "はし", "ﾊﾟｽ"];
+        words.sort_by_key(|w| reading_sort_key(w));
+        assert_eq!(words, vec!["はし", "ﾊﾟｽ", "ハナ", "ばら", "ぱん"]);
+    }
+
+    #[test]
+    fn test_sort_key() {
+        // 読みが異なれば濁点の有無より読みを優先する
+        let mut words = vec!["ぱん", "ハン", "ばん", "はれ", "ばら"];
+        words.sort_by_key(|w| sort_key(w));
+        assert_eq!(words, vec!["ばら", "はれ", "ハン", "ばん", "ぱん"]);
+
+        // 清音 → 濁音 → 半濁音
+        assert!(sort_key("はは") < sort_key("はば"));
+        assert!(sort_key("はば") < sort_key("はぱ"));
+        // 小書き → 通常 → 長音
+        assert!(sort_key("やっつ") < sort_key("やつつ"));
+        assert!(sort_key("かあ") < sort_key("かー"));
+        assert!(sort_key("こう") < sort_key("コー"));
+        // 読みが前方一致する場合は短いほうが先
+        assert!(sort_key("かー") < sort_key("かあい"));
+        assert!(sort_key("ば") < sort_key("はな"));
+
+        // ひらがな・カタカナ・半角カナ・NFD は同じキー
+        assert_eq!(sort_key("ガッコー"), sort_key("がっこー"));
+        assert_eq!(sort_key("ｶﾞｯｺｰ"), sort_key("ガッコー"));
+        assert_eq!(sort_key("か\u{3099}"), sort_key("が"));
+        assert_eq!(sort_key(""), "\u{0}\u{0}");
+    }
+
+    #[test]
+    fn test_normalize_for_compare() {
+        assert_eq!(normalize_for_compare("Ａpple"), "apple");
+        assert_eq!(normalize_for_compare("ＡＰＰＬＥ"), "apple");
+        assert_eq!(normalize_for_compare("カタカナ"), "かたかな");
+        assert_eq!(normalize_for_compare("ｶﾀｶﾅ"), "かたかな");
+        assert_eq!(normalize_for_compare("  Ｆｉｌｅ　\t０１.txt "), "file 01.txt");
+        assert_eq!(normalize_for_compare("漢字"), "漢字");
+        assert_eq!(normalize_for_compare(""), "");
+
+        // 元の文字列を保ったまま、キーで並べ替えられる
+        let mut files = vec!["ｂｅｔａ", "ALPHA", "ガンマ", "alpha", "Beta"];
+        files.sort_by_cached_key(|name| normalize_for_compare(name));
+        assert_eq!(files, vec!["ALPHA", "alpha", "ｂｅｔａ", "Beta", "ガンマ"]);
+    }
+
+    #[test]
+    fn test_kana_group() {
+        assert_eq!(kana_group('あ'), Some(KanaGroup::A));
+        assert_eq!(kana_group('ヴ'), Some(KanaGroup::A));
+        assert_eq!(kana_group('が'), Some(KanaGroup::Ka));
+        assert_eq!(kana_group('ヶ'), Some(KanaGroup::Ka));
+        assert_eq!(kana_group('ず'), Some(KanaGroup::Sa));
+        assert_eq!(kana_group('っ'), Some(KanaGroup::Ta));
+        assert_eq!(kana_group('ヌ'), Some(KanaGroup::Na));
+        assert_eq!(kana_group('ぱ'), Some(KanaGroup::Ha));
+        assert_eq!(kana_group('ﾎ'), Some(KanaGroup::Ha));
+        assert_eq!(kana_group('む'), Some(KanaGroup::Ma));
+        assert_eq!(kana_group('ゃ'), Some(KanaGroup::Ya));
+        assert_eq!(kana_group('ﾙ'), Some(KanaGroup::Ra));
+        assert_eq!(kana_group('ゎ'), Some(KanaGroup::Wa));
+        assert_eq!(kana_group('ヲ'), Some(KanaGroup::Wa));
+        assert_eq!(kana_group('ん'), Some(KanaGroup::Wa));
+        assert_eq!(kana_group('ヷ'), Some(KanaGroup::Wa));
+        assert_eq!(kana_group('ー'), None);
+        assert_eq!(kana_group('ゝ'), None);
+        assert_eq!(kana_group('ｰ'), None);
+        assert_eq!(kana_group('漢'), None);
+        assert_eq!(kana_group('A'), None);
+        assert!(KanaGroup::A < KanaGroup::Wa);
+    }
+
+    #[test]
+    fn test_kana_to_gojuon() {
+        let gojuon = |row, vowel, voicing, small| {
+            Some(Gojuon {
+                row,
+                vowel,
+                voicing,
+                small,
+            })
+        };
+        use KanaGroup::*;
+        use Voicing::*;
+
+        assert_eq!(kana_to_gojuon('あ'), gojuon(A, Vowel::A, Plain, false));
+        assert_eq!(kana_to_gojuon('き'), gojuon(Ka, Vowel::I, Plain, false));
+        assert_eq!(kana_to_gojuon('ズ'), gojuon(Sa, Vowel::U, Voiced, false));
+        assert_eq!(kana_to_gojuon('ぺ'), gojuon(Ha, Vowel::E, SemiVoiced, false));
+        assert_eq!(kana_to_gojuon('ろ'), gojuon(Ra, Vowel::O, Plain, false));
+        assert_eq!(kana_to_gojuon('ゔ'), gojuon(A, Vowel::U, Voiced, false));
+        assert_eq!(kana_to_gojuon('ゐ'), gojuon(Wa, Vowel::I, Plain, false));
+        assert_eq!(kana_to_gojuon('ヲ'), gojuon(Wa, Vowel::O, Plain, false));
+        assert_eq!(kana_to_gojuon('ヺ'), gojuon(Wa, Vowel::O, Voiced, false));
+        // 小書き
+        assert_eq!(kana_to_gojuon('ぁ'), gojuon(A, Vowel::A, Plain, true));
+        assert_eq!(kana_to_gojuon('っ'), gojuon(Ta, Vowel::U, Plain, true));
+        assert_eq!(kana_to_gojuon('ョ'), gojuon(Ya, Vowel::O, Plain, true));
+        assert_eq!(kana_to_gojuon('ゎ'), gojuon(Wa, Vowel::A, Plain, true));
+        assert_eq!(kana_to_gojuon('ヶ'), gojuon(Ka, Vowel::E, Plain, true));
+        // 半角カタカナ
+        assert_eq!(kana_to_gojuon('ﾊ'), gojuon(Ha, Vowel::A, Plain, false));
+        assert_eq!(kana_to_gojuon('ｯ'), gojuon(Ta, Vowel::U, Plain, true));
+        // 行段に収まらない文字
+        assert_eq!(kana_to_gojuon('ん'), None);
+        assert_eq!(kana_to_gojuon('ン'), None);
+        assert_eq!(kana_to_gojuon('ー'), None);
+        assert_eq!(kana_to_gojuon('ゝ'), None);
+        assert_eq!(kana_to_gojuon('ﾞ'), None);
+        assert_eq!(kana_to_gojuon('漢'), None);
+
+        // 行は kana_group と一致する
+        for c in ('ぁ'..='ゖ').chain('ァ'..='ヺ') {
+            if let Some(g) = kana_to_gojuon(c) {
+                assert_eq!(Some(g.row), kana_group(c), "{}", c);
             }
-            // カタカナ繰り返し記号（無声音）
-            'ヽ' => {
-                if i > 0 {
-                    result.push(chars[i - 1]);
-                } else {
-                    result.push(c);
-                }
+        }
+    }
+
+    #[test]
+    fn test_gojuon_to_kana() {
+        let g = |row, vowel, voicing, small| Gojuon {
+            row,
+            vowel,
+            voicing,
+            small,
+        };
+        use KanaForm::{Hiragana, Katakana};
+        use Voicing::*;
+
+        assert_eq!(gojuon_to_kana(&g(KanaGroup::Ka, Vowel::I, Plain, false), Hiragana), Some('き'));
+        assert_eq!(gojuon_to_kana(&g(KanaGroup::Ka, Vowel::I, Plain, false), Katakana), Some('キ'));
+        assert_eq!(gojuon_to_kana(&g(KanaGroup::Sa, Vowel::U, Voiced, false), Hiragana), Some('ず'));
+        assert_eq!(gojuon_to_kana(&g(KanaGroup::Ha, Vowel::O, SemiVoiced, false), Katakana), Some('ポ'));
+        assert_eq!(gojuon_to_kana(&g(KanaGroup::Ta, Vowel::U, Plain, true), Hiragana), Some('っ'));
+        assert_eq!(gojuon_to_kana(&g(KanaGroup::Ya, Vowel::O, Plain, true), Katakana), Some('ョ'));
+        assert_eq!(gojuon_to_kana(&g(KanaGroup::A, Vowel::U, Voiced, false), Katakana), Some('ヴ'));
+        assert_eq!(gojuon_to_kana(&g(KanaGroup::Wa, Vowel::A, Voiced, false), Katakana), Some('ヷ'));
+
+        // 存在しない組み合わせ
+        assert_eq!(gojuon_to_kana(&g(KanaGroup::Ya, Vowel::I, Plain, false), Hiragana), None);
+        assert_eq!(gojuon_to_kana(&g(KanaGroup::Ya, Vowel::E, Plain, false), Katakana), None);
+        assert_eq!(gojuon_to_kana(&g(KanaGroup::Wa, Vowel::U, Plain, false), Hiragana), None);
+        assert_eq!(gojuon_to_kana(&g(KanaGroup::Na, Vowel::A, Voiced, false), Hiragana), None);
+        assert_eq!(gojuon_to_kana(&g(KanaGroup::Ka, Vowel::A, SemiVoiced, false), Hiragana), None);
+        assert_eq!(gojuon_to_kana(&g(KanaGroup::Wa, Vowel::A, Voiced, false), Hiragana), None);
+        assert_eq!(gojuon_to_kana(&g(KanaGroup::Sa, Vowel::A, Plain, true), Hiragana), None);
+        assert_eq!(gojuon_to_kana(&g(KanaGroup::Ta, Vowel::U, Voiced, true), Hiragana), None);
+
+        // kana_to_gojuon とのラウンドトリップ
+        for c in 'ぁ'..='ゖ' {
+            if let Some(gojuon) = kana_to_gojuon(c) {
+                assert_eq!(gojuon_to_kana(&gojuon, Hiragana), Some(c), "{}", c);
             }
-            // カタカナ繰り返し記号（濁音）
-            'ヾ' => {
-                if i > 0 {
-                    let prev = chars[i - 1];
-                    let voiced = add_dakuten(prev);
-                    result.push(voiced);
-                } else {
-                    result.push(c);
-                }
+        }
+        for c in 'ァ'..='ヺ' {
+            if let Some(gojuon) = kana_to_gojuon(c) {
+                assert_eq!(gojuon_to_kana(&gojuon, Katakana), Some(c), "{}", c);
             }
-            _ => result.push(c),
         }
     }
 
-    result
-}
+    #[test]
+    fn test_heading_group() {
+        assert_eq!(heading_group("さとう"), Some(KanaGroup::Sa));
+        assert_eq!(heading_group("ｷﾑﾗ"), Some(KanaGroup::Ka));
+        assert_eq!(heading_group("ぱぴぷ"), Some(KanaGroup::Ha));
+        assert_eq!(heading_group("佐藤"), None);
+        assert_eq!(heading_group(""), None);
+    }
 
-/// 文字に濁点を追加します（内部ヘルパー関数）。
-fn add_dakuten(c: char) -> char {
-    match c {
-        // ひらがな
-        'か' => 'が', 'き' => 'ぎ', 'く' => 'ぐ', 'け' => 'げ', 'こ' => 'ご',
-        'さ' => 'ざ', 'し' => 'じ', 'す' => 'ず', 'せ' => 'ぜ', 'そ' => 'ぞ',
-        'た' => 'だ', 'ち' => 'ぢ', 'つ' => 'づ', 'て' => 'で', 'と' => 'ど',
-        'は' => 'ば', 'ひ' => 'び', 'ふ' => 'ぶ', 'へ' => 'べ', 'ほ' => 'ぼ',
-        // カタカナ
-        'カ' => 'ガ', 'キ' => 'ギ', 'ク' => 'グ', 'ケ' => 'ゲ', 'コ' => 'ゴ',
-        'サ' => 'ザ', 'シ' => 'ジ', 'ス' => 'ズ', 'セ' => 'ゼ', 'ソ' => 'ゾ',
-        'タ' => 'ダ', 'チ' => 'ヂ', 'ツ' => 'ヅ', 'テ' => 'デ', 'ト' => 'ド',
-        'ハ' => 'バ', 'ヒ' => 'ビ', 'フ' => 'ブ', 'ヘ' => 'ベ', 'ホ' => 'ボ',
-        _ => c,
+    #[test]
+    fn test_apply_rendaku() {
+        assert_eq!(apply_rendaku("て", "かみ"), "てがみ");
+        assert_eq!(apply_rendaku("ひと", "ひと"), "ひとびと");
+        assert_eq!(apply_rendaku("はな", "ち"), "はなぢ");
+        assert_eq!(apply_rendaku("やま", "さくら"), "やまざくら");
+        assert_eq!(apply_rendaku("ほん", "たな"), "ほんだな");
+        assert_eq!(apply_rendaku("アマ", "ト"), "アマド");
+        // か・さ・た・は行以外、すでに濁音・半濁音の場合はそのまま
+        assert_eq!(apply_rendaku("あお", "うみ"), "あおうみ");
+        assert_eq!(apply_rendaku("ゆき", "まつり"), "ゆきまつり");
+        assert_eq!(apply_rendaku("あか", "ぱん"), "あかぱん");
+        assert_eq!(apply_rendaku("あか", "ごま"), "あかごま");
+        // 空の語や、かな以外で始まる語
+        assert_eq!(apply_rendaku("て", ""), "て");
+        assert_eq!(apply_rendaku("", "かみ"), "がみ");
+        assert_eq!(apply_rendaku("て", "紙"), "て紙");
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_apply_rendaku_lymans_law() {
+        assert_eq!(apply_rendaku("おお", "かぜ"), "おおかぜ");
+        assert_eq!(apply_rendaku("ひとり", "たび"), "ひとりたび");
+        assert_eq!(apply_rendaku("ふる", "タヌキ"), "ふるダヌキ");
+        // 半濁音はライマンの法則の対象外
+        assert_eq!(apply_rendaku("ひ", "かっぱ"), "ひがっぱ");
+
+        let opts = RendakuOptions { lymans_law: false };
+        assert_eq!(apply_rendaku_with("おお", "かぜ", opts), "おおがぜ");
+        assert_eq!(apply_rendaku_with("おお", "カゼ", opts), "おおガゼ");
+        assert!(RendakuOptions::default().lymans_law);
+    }
 
     #[test]
-    fn test_to_half_width() {
-        assert_eq!(to_half_width("ＡＢＣ"), "ABC");
-        assert_eq!(to_half_width("１２３"), "123");
-        assert_eq!(to_half_width("！＠＃"), "!@#");
-        assert_eq!(to_half_width("　"), " ");
-        assert_eq!(to_half_width("Ｈｅｌｌｏ　Ｗｏｒｌｄ"), "Hello World");
-        // Mixed content
-        assert_eq!(to_half_width("ＡＢＣあいう"), "ABCあいう");
+    #[cfg(feature = "romaji")]
+    fn test_to_typing_sequence() {
+        assert_eq!(
+            to_typing_sequence("すし"),
+            vec![vec!["su"], vec!["shi", "si", "ci"]]
+        );
+        // カタカナ・半角カタカナはひらがなとして扱う
+        assert_eq!(to_typing_sequence("ｼ"), to_typing_sequence("し"));
+        assert_eq!(to_typing_sequence("シ"), to_typing_sequence("し"));
+        // 長音符・句読点・かな以外
+        assert_eq!(
+            to_typing_sequence("ケーキ。A"),
+            vec![vec!["ke"], vec!["-"], vec!["ki"], vec!["."], vec!["A"]]
+        );
+        assert!(to_typing_sequence("").is_empty());
     }
 
     #[test]
-    fn test_to_full_width() {
-        assert_eq!(to_full_width("ABC"), "ＡＢＣ");
-        assert_eq!(to_full_width("123"), "１２３");
-        assert_eq!(to_full_width("!@#"), "！＠＃");
-        assert_eq!(to_full_width(" "), "　");
-        assert_eq!(to_full_width("Hello World"), "Ｈｅｌｌｏ　Ｗｏｒｌｄ");
-        // Mixed content
-        assert_eq!(to_full_width("ABCあいう"), "ＡＢＣあいう");
+    #[cfg(feature = "romaji")]
+    fn test_to_typing_sequence_youon() {
+        let units = to_typing_sequence("きゃしゃ");
+        assert_eq!(units.len(), 2);
+        assert_eq!(units[0][0], "kya");
+        assert!(units[0].contains(&String::from("kilya")));
+        assert!(units[0].contains(&String::from("kixya")));
+        assert_eq!(units[1][..2], ["sha", "sya"]);
+        assert!(units[1].contains(&String::from("cixya")));
+
+        // 拗音にならない組み合わせは1文字ずつ
+        assert_eq!(to_typing_sequence("かゃ").len(), 2);
     }
 
     #[test]
-    fn test_to_hiragana() {
-        assert_eq!(to_hiragana("カタカナ"), "かたかな");
-        assert_eq!(to_hiragana("コンニチハ"), "こんにちは");
-        assert_eq!(to_hiragana("アイウエオ"), "あいうえお");
-        assert_eq!(to_hiragana("ヴァイオリン"), "ゔぁいおりん");
-        // Mixed content
-        assert_eq!(to_hiragana("カタカナABC"), "かたかなABC");
+    #[cfg(feature = "romaji")]
+    fn test_to_typing_sequence_sokuon() {
+        let units = to_typing_sequence("っか");
+        assert_eq!(units.len(), 1);
+        assert_eq!(units[0][..2], ["kka", "cca"]);
+        assert!(units[0].contains(&String::from("ltuka")));
+        assert!(units[0].contains(&String::from("xtsuca")));
+
+        assert_eq!(to_typing_sequence("まっちゃ")[1][..3], ["ccha", "ttya", "ccya"]);
+        // 末尾・母音の前・連続する促音は単独
+        assert_eq!(
+            to_typing_sequence("あっ")[1],
+            vec!["ltu", "xtu", "ltsu", "xtsu"]
+        );
+        assert_eq!(to_typing_sequence("っあ")[0][0], "ltu");
+        let units = to_typing_sequence("っっと");
+        assert_eq!(units.len(), 2);
+        assert_eq!(units[0][0], "ltu");
+        assert_eq!(units[1][0], "tto");
     }
 
     #[test]
-    fn test_to_katakana() {
-        assert_eq!(to_katakana("ひらがな"), "ヒラガナ");
-        assert_eq!(to_katakana("こんにちは"), "コンニチハ");
-        assert_eq!(to_katakana("あいうえお"), "アイウエオ");
-        assert_eq!(to_katakana("ゔぁいおりん"), "ヴァイオリン");
-        // Mixed content
-        assert_eq!(to_katakana("ひらがなABC"), "ヒラガナABC");
+    #[cfg(feature = "romaji")]
+    fn test_to_typing_sequence_hatsuon() {
+        assert_eq!(to_typing_sequence("ほんと")[1], vec!["nn", "n", "xn"]);
+        // 母音・や行・な行の前と末尾では n を単独で許容しない
+        assert_eq!(to_typing_sequence("きんえん")[1], vec!["nn", "xn"]);
+        assert_eq!(to_typing_sequence("こんや")[1], vec!["nn", "xn"]);
+        assert_eq!(to_typing_sequence("こんにゃく")[1], vec!["nn", "xn"]);
+        assert_eq!(to_typing_sequence("きんえん")[3], vec!["nn", "xn"]);
+    }
+
+    #[cfg(feature = "romaji")]
+    fn phoneme(consonant: Option<&str>, vowel: Option<char>) -> Phoneme {
+        Phoneme {
+            consonant: consonant.map(String::from),
+            vowel,
+        }
     }
 
     #[test]
-    fn test_roundtrip_full_half_width() {
-        let original = "ABC123!@#";
-        let full = to_full_width(original);
-        let back = to_half_width(&full);
-        assert_eq!(original, back);
+    #[cfg(feature = "romaji")]
+    fn test_to_phonemes() {
+        assert_eq!(
+            to_phonemes("さくら"),
+            vec![
+                phoneme(Some("s"), Some('a')),
+                phoneme(Some("k"), Some('u')),
+                phoneme(Some("r"), Some('a')),
+            ]
+        );
+        assert_eq!(
+            to_phonemes("あしたつ"),
+            vec![
+                phoneme(None, Some('a')),
+                phoneme(Some("sh"), Some('i')),
+                phoneme(Some("t"), Some('a')),
+                phoneme(Some("ts"), Some('u')),
+            ]
+        );
+        // 促音・撥音・長音
+        assert_eq!(
+            to_phonemes("コンピューター"),
+            vec![
+                phoneme(Some("k"), Some('o')),
+                phoneme(Some("N"), None),
+                phoneme(Some("py"), Some('u')),
+                phoneme(None, Some('u')),
+                phoneme(Some("t"), Some('a')),
+                phoneme(None, Some('a')),
+            ]
+        );
+        assert_eq!(
+            to_phonemes("ｷｯﾄ"),
+            vec![
+                phoneme(Some("k"), Some('i')),
+                phoneme(Some("Q"), None),
+                phoneme(Some("t"), Some('o')),
+            ]
+        );
+        // かな以外と先頭の長音符は無視する
+        assert_eq!(to_phonemes("ー漢A"), vec![]);
     }
 
     #[test]
-    fn test_roundtrip_hiragana_katakana() {
-        let original = "こんにちは";
-        let katakana = to_katakana(original);
-        let back = to_hiragana(&katakana);
-        assert_eq!(original, back);
+    #[cfg(feature = "romaji")]
+    fn test_to_phonemes_youon() {
+        assert_eq!(to_phonemes("しゃ"), vec![phoneme(Some("sh"), Some('a'))]);
+        assert_eq!(to_phonemes("ちょ"), vec![phoneme(Some("ch"), Some('o'))]);
+        assert_eq!(to_phonemes("じゅ"), vec![phoneme(Some("j"), Some('u'))]);
+        assert_eq!(to_phonemes("にょ"), vec![phoneme(Some("ny"), Some('o'))]);
+        assert_eq!(to_phonemes("ファ"), vec![phoneme(Some("f"), Some('a'))]);
+        assert_eq!(to_phonemes("ティ"), vec![phoneme(Some("t"), Some('i'))]);
+        assert_eq!(to_phonemes("ヴェ"), vec![phoneme(Some("v"), Some('e'))]);
+        assert_eq!(to_phonemes("ウィ"), vec![phoneme(Some("w"), Some('i'))]);
+        assert_eq!(to_phonemes("イェ"), vec![phoneme(Some("y"), Some('e'))]);
+        assert_eq!(to_phonemes("てゅ"), vec![phoneme(Some("ty"), Some('u'))]);
+        assert_eq!(to_phonemes("デュ"), vec![phoneme(Some("dy"), Some('u'))]);
+        assert_eq!(to_phonemes("フュ"), vec![phoneme(Some("fy"), Some('u'))]);
+        assert_eq!(to_phonemes("ゔゅ"), vec![phoneme(Some("vy"), Some('u'))]);
+        // 拗音・外来音にならない組み合わせ
+        assert_eq!(
+            to_phonemes("かゃ"),
+            vec![phoneme(Some("k"), Some('a')), phoneme(Some("y"), Some('a'))]
+        );
+        assert_eq!(
+            to_phonemes("あぁ"),
+            vec![phoneme(None, Some('a')), phoneme(None, Some('a'))]
+        );
+        assert_eq!(
+            to_phonemes("てゃ"),
+            vec![phoneme(Some("t"), Some('e')), phoneme(Some("y"), Some('a'))]
+        );
+        assert_eq!(to_phonemes("を"), vec![phoneme(None, Some('o'))]);
     }
 
     #[test]
-    fn test_empty_string() {
-        assert_eq!(to_half_width(""), "");
-        assert_eq!(to_full_width(""), "");
-        assert_eq!(to_hiragana(""), "");
-        assert_eq!(to_katakana(""), "");
+    #[cfg(feature = "romaji")]
+    fn test_to_romaji() {
+        assert_eq!(to_romaji(""), "");
+        assert_eq!(to_romaji("ひらがな"), "hiragana");
+        assert_eq!(to_romaji("しちつふじ"), "shichitsufuji");
+        assert_eq!(to_romaji("ｶﾀｶﾅ"), "katakana");
+        // 拗音・外来音
+        assert_eq!(to_romaji("きゃしゅちょ"), "kyashucho");
+        assert_eq!(to_romaji("ファイル・ティー"), "fairu・tii");
+        assert_eq!(to_romaji("でゅえっと ふゅーじょん"), "dyuetto fyuujon");
+        assert_eq!(to_romaji("テューバ"), "tyuuba");
+        // 促音
+        assert_eq!(to_romaji("きって"), "kitte");
+        assert_eq!(to_romaji("まっちゃ"), "matcha");
+        assert_eq!(to_romaji("あっ"), "a");
+        assert_eq!(to_romaji("あっ!"), "a!");
+        // 撥音
+        assert_eq!(to_romaji("せんせい"), "sensei");
+        assert_eq!(to_romaji("きんえん"), "kin'en");
+        assert_eq!(to_romaji("こんや"), "kon'ya");
+        assert_eq!(to_romaji("ほん"), "hon");
+        // 長音とかな以外の文字
+        assert_eq!(to_romaji("ラーメン2杯"), "raamen2杯");
+        assert_eq!(to_romaji("ABC"), "ABC");
+        assert_eq!(to_romaji("がっこう"), "gakkou");
+        assert_eq!(to_romaji("ちゅうしゃじょう"), "chuushajou");
+        assert_eq!(to_romaji("ヴァイオリン"), "vaiorin");
     }
 
     #[test]
-    fn test_is_hiragana() {
-        assert_eq!(is_hiragana('あ'), true);
-        assert_eq!(is_hiragana('ん'), true);
-        assert_eq!(is_hiragana('ア'), false);
-        assert_eq!(is_hiragana('A'), false);
-        assert_eq!(is_hiragana('漢'), false);
+    fn test_normalize_trait() {
+        use alloc::borrow::Cow;
+        use alloc::boxed::Box;
+
+        assert_eq!("ＡＢＣ".normalize(Transform::HalfWidth), "ABC");
+        assert_eq!(String::from("カナ").normalize(Transform::ToHiragana), "かな");
+        assert_eq!(Cow::Borrowed("ＡＢＣ").normalize(Transform::HalfWidth), "ABC");
+        let owned: Cow<str> = Cow::Owned(String::from("かな"));
+        assert_eq!(owned.normalize(Transform::ToKatakana), "カナ");
+        let boxed: Box<str> = Box::from("ＡＢＣ");
+        assert_eq!(boxed.normalize(Transform::HalfWidth), "ABC");
+
+        // 自由関数と同じ結果になる
+        let transforms = [Transform::HalfWidth, Transform::ToHiragana, Transform::NormalizeWhitespace];
+        let input = " ＡＢＣ　カタカナ ";
+        assert_eq!(input.normalize_all(&transforms), apply_all(&transforms, input));
+        for transform in Transform::ALL {
+            assert_eq!(input.normalize(transform), apply(transform, input));
+        }
+
+        fn normalize_generic<T: Normalize>(input: T) -> String {
+            input.normalize(Transform::FullWidth)
+        }
+        assert_eq!(normalize_generic("A"), "Ａ");
+        assert_eq!(normalize_generic(String::from("A")), "Ａ");
+        assert_eq!(normalize_generic(Cow::Borrowed("A")), "Ａ");
     }
 
     #[test]
-    fn test_is_katakana() {
-        assert_eq!(is_katakana('ア'), true);
-        assert_eq!(is_katakana('ン'), true);
-        assert_eq!(is_katakana('あ'), false);
-        assert_eq!(is_katakana('A'), false);
+    fn test_normalize_with_mapping() {
+        let input = "ｶﾞｲﾄﾞ ABC";
+        let (output, mapping) =
+            normalize_with_mapping(input, Transform::HalfWidthKatakanaToFullWidth);
+        assert_eq!(output, "ガイド ABC");
+        // ガ(0..3) イ(3..6) ド(6..9) は ｶﾞ(0) ｲ(6) ﾄﾞ(9) から
+        assert_eq!(&mapping[..9], &[0, 0, 0, 6, 6, 6, 9, 9, 9]);
+        assert_eq!(mapping.last(), Some(&input.len()));
+
+        let (output, mapping) = normalize_with_mapping("ＡＢ", Transform::HalfWidth);
+        assert_eq!(output, "AB");
+        assert_eq!(mapping, vec![0, 3, 6]);
+
+        // 連続する空白は最初の空白に、前後の空白は対応なし
+        let (output, mapping) =
+            normalize_with_mapping("  a \t b ", Transform::NormalizeWhitespace);
+        assert_eq!(output, "a b");
+        assert_eq!(mapping, vec![2, 3, 6, 8]);
+
+        // 取り除かれた絵文字
+        let (output, mapping) = normalize_with_mapping("a😀b", Transform::StripEmoji);
+        assert_eq!(output, "ab");
+        assert_eq!(mapping, vec![0, 5, 6]);
+
+        let (output, mapping) = normalize_with_mapping("", Transform::HalfWidth);
+        assert_eq!(output, "");
+        assert_eq!(mapping, vec![0]);
     }
 
     #[test]
-    fn test_is_half_width_katakana() {
-        assert_eq!(is_half_width_katakana('ｱ'), true);
-        assert_eq!(is_half_width_katakana('ﾝ'), true);
-        assert_eq!(is_half_width_katakana('ア'), false);
-        assert_eq!(is_half_width_katakana('A'), false);
+    fn test_normalize_with_mapping_matches_apply() {
+        let inputs = [
+            "ＡＢＣ　ｶﾞｷﾞｸﾞ ﾊﾟﾋﾟ ｱﾞ",
+            "  いろゝ かゞ トヽキ\t\nコ〜ヒ〜 ",
+            "家族👨\u{200D}👩\u{200D}👧です\u{200D}😀",
+            "Hello World ‐―－ー ﾞ",
+        ];
+        for transform in Transform::ALL {
+            for input in inputs {
+                let (output, mapping) = normalize_with_mapping(input, transform);
+                assert_eq!(output, apply(transform, input), "{transform}");
+                assert_eq!(mapping.len(), output.len() + 1);
+                assert!(mapping.windows(2).all(|w| w[0] <= w[1]));
+                assert!(mapping.iter().all(|&offset| input.is_char_boundary(offset)));
+            }
+        }
+    }
+
+    #[cfg(feature = "ruby")]
+    fn ruby(base: &str, ruby: Option<&str>) -> RubySegment {
+        RubySegment {
+            base: base.to_string(),
+            ruby: ruby.map(|r| r.to_string()),
+        }
     }
 
     #[test]
-    fn test_is_kanji() {
-        assert_eq!(is_kanji('漢'), true);
-        assert_eq!(is_kanji('字'), true);
-        assert_eq!(is_kanji('あ'), false);
-        assert_eq!(is_kanji('A'), false);
+    #[cfg(feature = "ruby")]
+    fn test_parse_aozora_ruby() {
+        assert_eq!(
+            parse_aozora_ruby("吾輩《わがはい》は猫である"),
+            vec![ruby("吾輩", Some("わがはい")), ruby("は猫である", None)]
+        );
+        // 親文字の開始記号
+        assert_eq!(
+            parse_aozora_ruby("その｜小さな猫《こねこ》"),
+            vec![ruby("その", None), ruby("小さな猫", Some("こねこ"))]
+        );
+        assert_eq!(
+            parse_aozora_ruby("|ABC《えーびーしー》"),
+            vec![ruby("ABC", Some("えーびーしー"))]
+        );
+        // 漢字の連続のみが親文字になる
+        assert_eq!(
+            parse_aozora_ruby("私は時々《ときどき》"),
+            vec![ruby("私は", None), ruby("時々", Some("ときどき"))]
+        );
+        // 漢字以外の直前の同じ種別の連続
+        assert_eq!(
+            parse_aozora_ruby("漢字とカタカナ《かたかな》"),
+            vec![ruby("漢字と", None), ruby("カタカナ", Some("かたかな"))]
+        );
+        // ルビの無いテキスト
+        assert_eq!(parse_aozora_ruby("ルビなし"), vec![ruby("ルビなし", None)]);
+        assert!(parse_aozora_ruby("").is_empty());
     }
 
     #[test]
-    fn test_is_full_width() {
-        assert_eq!(is_full_width('Ａ'), true);
-        assert_eq!(is_full_width('１'), true);
-        assert_eq!(is_full_width('　'), true);
-        assert_eq!(is_full_width('A'), false);
+    #[cfg(feature = "ruby")]
+    fn test_parse_aozora_ruby_literal() {
+        // 閉じ括弧が無い・中身が空・親文字が無い場合は本文として残す
+        assert_eq!(parse_aozora_ruby("漢字《かんじ"), vec![ruby("漢字《かんじ", None)]);
+        assert_eq!(parse_aozora_ruby("漢字《》"), vec![ruby("漢字《》", None)]);
+        assert_eq!(parse_aozora_ruby("《るび》"), vec![ruby("《るび》", None)]);
+        // 《 が続かない ｜ はそのまま残す
+        assert_eq!(parse_aozora_ruby("A｜B"), vec![ruby("A｜B", None)]);
+        assert_eq!(
+            parse_aozora_ruby("｜A｜漢字《かんじ》"),
+            vec![ruby("｜A", None), ruby("漢字", Some("かんじ"))]
+        );
+        // 親文字が空の ｜ は本文として残し、後続のルビに影響しない
+        assert_eq!(
+            parse_aozora_ruby("｜《る》本《ほん》"),
+            vec![ruby("｜《る》", None), ruby("本", Some("ほん"))]
+        );
     }
 
     #[test]
-    fn test_count_character_types() {
-        let counts = count_character_types("あア漢ABC123ｱｲｳ");
-        assert_eq!(counts.hiragana, 1);
-        assert_eq!(counts.katakana, 1);
-        assert_eq!(counts.kanji, 1);
-        assert_eq!(counts.ascii, 6);
-        assert_eq!(counts.half_width_katakana, 3);
+    #[cfg(feature = "ruby")]
+    fn test_to_ruby_html() {
+        let segments = parse_aozora_ruby("吾輩《わがはい》は猫である");
+        assert_eq!(
+            to_ruby_html(&segments),
+            "<ruby>吾輩<rt>わがはい</rt></ruby>は猫である"
+        );
+        // HTML エスケープ
+        let segments = vec![ruby("A&B", Some("<b>")), ruby("x<y>", None)];
+        assert_eq!(
+            to_ruby_html(&segments),
+            "<ruby>A&amp;B<rt>&lt;b&gt;</rt></ruby>x&lt;y&gt;"
+        );
+        assert_eq!(to_ruby_html(&[]), "");
     }
 
     #[test]
-    fn test_normalize_whitespace() {
-        assert_eq!(normalize_whitespace("Hello　World"), "Hello World");
-        assert_eq!(normalize_whitespace("A\t\t\tB"), "A B");
-        assert_eq!(normalize_whitespace("  Multiple   Spaces  "), "Multiple Spaces");
+    #[cfg(feature = "ruby")]
+    fn test_to_ruby_html_with() {
+        let segments = parse_aozora_ruby("本《ほん》");
+        let opts = RubyHtmlOptions { fallback_parentheses: true };
+        assert_eq!(
+            to_ruby_html_with(&segments, opts),
+            "<ruby>本<rp>(</rp><rt>ほん</rt><rp>)</rp></ruby>"
+        );
+        assert_eq!(
+            to_ruby_html_with(&segments, RubyHtmlOptions::default()),
+            to_ruby_html(&segments)
+        );
     }
 
     #[test]
-    fn test_half_width_katakana_to_full_width() {
-        assert_eq!(half_width_katakana_to_full_width("ｶﾀｶﾅ"), "カタカナ");
-        assert_eq!(half_width_katakana_to_full_width("ｶﾞｷﾞｸﾞｹﾞｺﾞ"), "ガギグゲゴ");
-        assert_eq!(half_width_katakana_to_full_width("ﾊﾟﾋﾟﾌﾟﾍﾟﾎﾟ"), "パピプペポ");
-        assert_eq!(half_width_katakana_to_full_width("ｺﾝﾆﾁﾊ"), "コンニチハ");
+    fn test_normalize_small_kana() {
+        assert_eq!(normalize_small_kana("きゃっと"), "きやつと");
+        assert_eq!(normalize_small_kana("ぁぃぅぇぉっゃゅょゎゕゖ"), "あいうえおつやゆよわかけ");
+        assert_eq!(normalize_small_kana("ァィゥェォッャュョヮヵヶ"), "アイウエオツヤユヨワカケ");
+        // 半角カタカナ・カタカナ拡張は対象外
+        assert_eq!(normalize_small_kana("ｧｯㇰ"), "ｧｯㇰ");
+        assert_eq!(normalize_small_kana(""), "");
     }
 
     #[test]
-    fn test_normalize_prolonged_sound() {
-        assert_eq!(normalize_prolonged_sound("コーヒー"), "コーヒー");
-        assert_eq!(normalize_prolonged_sound("コ〜ヒ〜"), "コーヒー");
-        assert_eq!(normalize_prolonged_sound("ラーメン"), "ラーメン");
+    fn test_normalize_small_kana_selective() {
+        let input = "ぁぃぅぇぉっゃゅょゎゕゖ";
+        assert_eq!(normalize_small_kana_selective(input, SmallKana::empty()), input);
+        assert_eq!(
+            normalize_small_kana_selective(input, SmallKana::VOWELS),
+            "あいうえおっゃゅょゎゕゖ"
+        );
+        assert_eq!(normalize_small_kana_selective(input, SmallKana::YOON), "ぁぃぅぇぉっやゆよゎゕゖ");
+        assert_eq!(normalize_small_kana_selective(input, SmallKana::SOKUON), "ぁぃぅぇぉつゃゅょゎゕゖ");
+        assert_eq!(normalize_small_kana_selective(input, SmallKana::WA), "ぁぃぅぇぉっゃゅょわゕゖ");
+        assert_eq!(normalize_small_kana_selective(input, SmallKana::KA_KE), "ぁぃぅぇぉっゃゅょゎかけ");
+        assert_eq!(normalize_small_kana_selective(input, SmallKana::ALL), normalize_small_kana(input));
+
+        assert_eq!(
+            normalize_small_kana_selective("ティーカップとヶ月", SmallKana::VOWELS | SmallKana::SOKUON),
+            "テイーカツプとヶ月"
+        );
+
+        let mut targets = SmallKana::empty();
+        assert!(!targets.contains(SmallKana::VOWELS));
+        targets |= SmallKana::VOWELS;
+        assert!(targets.contains(SmallKana::VOWELS));
+        assert!(!targets.contains(SmallKana::VOWELS | SmallKana::YOON));
+        assert!(SmallKana::ALL.contains(SmallKana::VOWELS | SmallKana::YOON | SmallKana::KA_KE));
+        assert!(targets.contains(SmallKana::empty()));
+        assert_eq!(SmallKana::default(), SmallKana::empty());
     }
 
     #[test]
@@ -643,5 +9785,173 @@ mod tests {
         assert_eq!(expand_iteration_marks("かゞ"), "かが");
         assert_eq!(expand_iteration_marks("トヽキ"), "トトキ");
         assert_eq!(expand_iteration_marks("カヾ"), "カガ");
+        assert_eq!(expand_iteration_marks("うゞ"), "うう");
+    }
+
+    #[test]
+    fn test_write_conversions() {
+        let mut out = String::new();
+        write_half_width("ＡＢＣ　あいう１２３", &mut out).unwrap();
+        assert_eq!(out, "ABC あいう123");
+
+        let mut out = String::new();
+        write_full_width("Hello World", &mut out).unwrap();
+        assert_eq!(out, to_full_width("Hello World"));
+
+        let mut out = String::new();
+        write_hiragana("カタカナABC", &mut out).unwrap();
+        assert_eq!(out, "かたかなABC");
+
+        let mut out = String::new();
+        write_katakana("ひらがなABC", &mut out).unwrap();
+        assert_eq!(out, "ヒラガナABC");
+
+        // 既存の内容の後ろに追記される
+        let mut out = String::from("> ");
+        write_half_width("", &mut out).unwrap();
+        write_half_width("ｘ", &mut out).unwrap();
+        assert_eq!(out, "> x");
+    }
+
+    #[test]
+    fn test_byte_conversions() {
+        let input = "ＡＢＣ　カタカナ　ひらがな abc";
+        assert_eq!(to_half_width_bytes(input.as_bytes()).unwrap(), to_half_width(input));
+        assert_eq!(to_full_width_bytes(input.as_bytes()).unwrap(), to_full_width(input));
+        assert_eq!(to_hiragana_bytes(input.as_bytes()).unwrap(), to_hiragana(input));
+        assert_eq!(to_katakana_bytes(input.as_bytes()).unwrap(), to_katakana(input));
+        assert_eq!(to_half_width_bytes(b"").unwrap(), "");
+
+        // 不正なバイト
+        let err = to_half_width_bytes(b"abc\xFFdef").unwrap_err();
+        assert_eq!(err.valid_up_to(), 3);
+        assert_eq!(err.error_len(), Some(1));
+        // 途中で切れたマルチバイト文字（「あ」の先頭2バイト）
+        let bytes = "ＡＢあ".as_bytes();
+        let err = to_hiragana_bytes(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert_eq!(err.valid_up_to(), 6);
+        assert_eq!(err.error_len(), None);
+        assert!(to_full_width_bytes(b"\xC0\x80").is_err());
+        assert!(to_katakana_bytes(b"\x80").is_err());
+    }
+
+    #[test]
+    fn test_display_wrappers() {
+        let input = "ＡＢＣ　カタカナ　ひらがな abc";
+        assert_eq!(HalfWidth(input).to_string(), to_half_width(input));
+        assert_eq!(FullWidth(input).to_string(), to_full_width(input));
+        assert_eq!(ToHiragana(input).to_string(), to_hiragana(input));
+        assert_eq!(ToKatakana(input).to_string(), to_katakana(input));
+
+        assert_eq!(
+            format!("{}/{}", HalfWidth("ＩＤ：１２"), ToKatakana("あ")),
+            "ID:12/ア"
+        );
+        assert_eq!(HalfWidth("").to_string(), "");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_normalize_reader() {
+        let normalize = |input: &[u8], transforms: &[Transform]| {
+            let mut output = Vec::new();
+            normalize_reader(input, &mut output, transforms)
+                .map(|()| String::from_utf8(output).unwrap())
+        };
+        let transforms = [Transform::HalfWidth, Transform::ToHiragana];
+
+        assert_eq!(
+            normalize("ＡＢＣ\r\nカタカナ\nＸ".as_bytes(), &transforms).unwrap(),
+            "ABC\r\nかたかな\nX"
+        );
+        assert_eq!(normalize(b"", &transforms).unwrap(), "");
+        assert_eq!(normalize(b"\n\r\n", &transforms).unwrap(), "\n\r\n");
+        // \r 単独は改行とみなさない
+        assert_eq!(normalize("Ａ\rＢ".as_bytes(), &transforms).unwrap(), "A\rB");
+        // 行ごとに変換されるので、行末の空白の正規化は改行をまたがない
+        assert_eq!(
+            normalize("  あ  \n  い  \r\n".as_bytes(), &[Transform::NormalizeWhitespace]).unwrap(),
+            format!("{}\n{}\r\n", normalize_whitespace("  あ  "), normalize_whitespace("  い  "))
+        );
+        // 先頭の BOM はそのまま残す
+        let transforms = [Transform::NormalizeWhitespace, Transform::HalfWidth];
+        assert_eq!(
+            normalize("\u{FEFF}ＡＢ\n\u{FEFF}".as_bytes(), &transforms).unwrap(),
+            format!("\u{FEFF}AB\n{}", apply_all(&transforms, "\u{FEFF}"))
+        );
+        // 空の変換列はそのまま書き出す
+        assert_eq!(normalize("カナ\n".as_bytes(), &[]).unwrap(), "カナ\n");
+
+        let err = normalize(b"abc\n\xFF\n", &[]).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    /// 1回の読み取りで1バイトずつしか返さないリーダー
+    #[cfg(feature = "std")]
+    struct OneByteReader<'a>(&'a [u8]);
+
+    #[cfg(feature = "std")]
+    impl std::io::Read for OneByteReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            match self.0.split_first() {
+                Some((&b, rest)) if !buf.is_empty() => {
+                    buf[0] = b;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_convert_reader() {
+        use std::io::Read;
+
+        let mut output = String::new();
+        ConvertReader::half_width("ＡＢＣ　あいう１２３".as_bytes())
+            .read_to_string(&mut output)
+            .unwrap();
+        assert_eq!(output, "ABC あいう123");
+
+        let mut output = String::new();
+        ConvertReader::hiragana("カタカナとABC".as_bytes())
+            .read_to_string(&mut output)
+            .unwrap();
+        assert_eq!(output, "かたかなとABC");
+
+        // マルチバイト文字がチャンク境界で分断されても正しく変換される
+        let input = "Ｈｅｌｌｏ　ワールド";
+        let mut output = String::new();
+        ConvertReader::half_width(OneByteReader(input.as_bytes()))
+            .read_to_string(&mut output)
+            .unwrap();
+        assert_eq!(output, to_half_width(input));
+
+        let mut output = String::new();
+        ConvertReader::katakana(OneByteReader("ひらがな".as_bytes()))
+            .read_to_string(&mut output)
+            .unwrap();
+        assert_eq!(output, "ヒラガナ");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_convert_reader_invalid_utf8() {
+        use std::io::Read;
+
+        let mut output = String::new();
+        let err = ConvertReader::half_width(&b"abc\xFFdef"[..])
+            .read_to_string(&mut output)
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        // 途中で終わるマルチバイト文字
+        let mut output = String::new();
+        let err = ConvertReader::full_width(&"あ".as_bytes()[..2])
+            .read_to_string(&mut output)
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
     }
 }